@@ -40,9 +40,22 @@ fn resolve_enum(item_enum: &ItemEnum) -> syn::Result<proc_macro2::TokenStream> {
     } = &attr_enum_entry
     {
         let mut arm_expr = vec![];
+        let mut default_arm = quote! {
+            _ => {
+                unreachable!()
+            }
+        };
         for variant in variants {
             let variant_ident = &variant.ident;
             let fields = &variant.fields;
+            // #[enum_entry(default)] 的变体兜底所有未知的入口
+            if let EnumEntry::Default = self::attr_enum_entry(&variant.attrs)? {
+                let body = get_match_body(fields, ident, variant_ident)?;
+                default_arm = quote! {
+                    _ => #body
+                };
+                continue;
+            }
             let lit = variant.ident.to_string();
             let expr = get_match_arms(fields, ident, variant_ident, &lit)?;
             arm_expr.push(expr);
@@ -59,12 +72,10 @@ fn resolve_enum(item_enum: &ItemEnum) -> syn::Result<proc_macro2::TokenStream> {
                     let index = <#index_ty as ClassParser>::parse(ctx)?;
                     #debug_token_stream
                     ctx.enum_entry = Box::new(index);
-                    let choice: String = ContextIndex::get(&ctx.#map_ident, index);
+                    let choice: String = ContextIndex::get(&ctx.#map_ident, index)?;
                     let result = match choice.as_str() {
                         #(#arm_expr,)*
-                        _ => {
-                            unreachable!()
-                        }
+                        #default_arm
                     };
                     return Ok(result);
                 }
@@ -79,6 +90,17 @@ fn get_match_arms(
     enum_ident: &Ident,
     variant_ident: &Ident,
     lit: &str,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let body = get_match_body(fields, enum_ident, variant_ident)?;
+    Ok(quote! {
+        #lit => #body
+    })
+}
+
+fn get_match_body(
+    fields: &Fields,
+    enum_ident: &Ident,
+    variant_ident: &Ident,
 ) -> syn::Result<proc_macro2::TokenStream> {
     let constructor = quote! {#enum_ident::#variant_ident};
     let mut temp_idents = vec![];
@@ -103,14 +125,14 @@ fn get_match_arms(
                 parse_stmts.push(stmt);
             }
             Ok(quote! {
-                #lit => {
+                {
                     #(#parse_stmts)*
                     #constructor(#(#temp_idents),*)
                 }
             })
         }
         Fields::Unit => Ok(quote! {
-            #lit => #constructor
+            #constructor
         }),
         _ => {
             syn_err!(
@@ -257,6 +279,7 @@ enum Count {
 enum EnumEntry {
     Get,
     Index { index_ty: Type, map_ident: Ident },
+    Default,
     None,
 }
 
@@ -270,6 +293,11 @@ fn attr_enum_entry(attrs: &Vec<Attribute>) -> syn::Result<EnumEntry> {
                     enum_entry = EnumEntry::Get;
                     return Ok(());
                 }
+                // #[enum_entry(default)]
+                if meta.path.is_ident("default") {
+                    enum_entry = EnumEntry::Default;
+                    return Ok(());
+                }
                 // #[enum_entry(index(map[ty]))]
                 if meta.path.is_ident("index") {
                     let content;
@@ -302,9 +330,14 @@ fn resolve_collection_impl(
             let mut collection = #collection_ident::with_capacity(size);
             let invalid = Constant::Invalid;
             collection.push(invalid);
-            for _ in 0..size-1 {
+            while collection.len() < size {
                 let item = <#inner_ty as ClassParser>::parse(ctx)?;
+                // Long和Double占用两个索引
+                let is_two_words = matches!(item, Constant::Long(_) | Constant::Double(_));
                 collection.push(item);
+                if is_two_words {
+                    collection.push(Constant::Invalid);
+                }
             }
             return Ok(collection);
 
@@ -467,4 +500,22 @@ mod tests {
         print_expanded_fmt(expanded);
         Ok(())
     }
+    #[test]
+    fn test_resolve_enum_default_variant_expand() -> Result<(), Box<dyn Error>> {
+        let code: ItemEnum = parse_quote! {
+            #[enum_entry(index(map[u16]))]
+            enum TestEnum {
+                A(a),
+                #[enum_entry(default)]
+                Unknown(u)
+            }
+        };
+        let expanded = resolve_enum(&code)?;
+        let raw_code = expanded.to_string();
+        assert!(raw_code.contains("\"A\" =>"));
+        assert!(!raw_code.contains("\"Unknown\" =>"));
+        assert!(raw_code.contains("_ => { let temp_0 = < u as ClassParser > :: parse (ctx) ? ;"));
+        assert!(!raw_code.contains("unreachable"));
+        Ok(())
+    }
 }
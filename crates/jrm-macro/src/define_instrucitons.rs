@@ -2,7 +2,9 @@ use convert_case::{Case, Casing};
 use darling::FromMeta;
 use quote::{ToTokens, format_ident, quote};
 use syn::{
-    Ident, ItemFn, ItemMacro, Lit, Macro, Token, braced,
+    FnArg, Ident, ItemFn, ItemMacro, LitInt, Macro, Pat, PatIdent, PatType, ReturnType, Token,
+    braced,
+    ext::IdentExt,
     parse::{self, Parse},
     parse_quote,
     punctuated::Punctuated,
@@ -19,14 +21,15 @@ impl Parse for Args {
     }
 }
 pub struct Arg {
-    opcode: Lit,
+    opcode: LitInt,
     instr_ident: Ident,
     executor: Executor,
 }
 
 impl Parse for Arg {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let opcode: Lit = input.parse()?;
+        let opcode: LitInt = input.parse()?;
+        opcode.base10_parse::<u8>()?;
         input.parse::<Token![=>]>()?;
         let instr_ident: Ident = input.parse()?;
         let content;
@@ -56,7 +59,71 @@ impl Parse for Executor {
         Ok(executor)
     }
 }
+/// 指令的操作数布局由处理函数的参数声明, 按参数顺序紧跟在opcode之后
+fn decode_operands(__fn: &ItemFn) -> syn::Result<(Vec<proc_macro2::TokenStream>, Vec<Ident>)> {
+    let mut decode_stmts = vec![];
+    let mut operand_idents = vec![];
+    let mut offset = quote!(1u16);
+    for input in &__fn.sig.inputs {
+        let FnArg::Typed(PatType { pat, ty, .. }) = input else {
+            return Err(syn::Error::new_spanned(input, "unexpected receiver"));
+        };
+        let Pat::Ident(PatIdent { ident, .. }) = pat.as_ref() else {
+            return Err(syn::Error::new_spanned(
+                pat,
+                "operand must be an identifier",
+            ));
+        };
+        decode_stmts.push(quote! {
+            let #ident = self.read_operand::<#ty>(#offset);
+        });
+        offset = quote!(#offset + <#ty as Operand>::SIZE);
+        operand_idents.push(ident.clone());
+    }
+    Ok((decode_stmts, operand_idents))
+}
+
+/// JVMS 6.5中定义的最后一个opcode(jsr_w), 之后的breakpoint和impdep1/2为保留opcode
+const LAST_OPCODE: u8 = 0xc9;
+
+/// 检查指令表中重复和保留的opcode, 开启`check-opcodes`时还要求覆盖全部opcode
+fn check_opcodes(ast: &Args) -> syn::Result<()> {
+    let mut defined = [false; 256];
+    for arg in ast.args.iter() {
+        let opcode = arg.opcode.base10_parse::<u8>()?;
+        if opcode > LAST_OPCODE {
+            return Err(syn::Error::new_spanned(
+                &arg.opcode,
+                format!("reserved opcode {:#04x}", opcode),
+            ));
+        }
+        if defined[opcode as usize] {
+            return Err(syn::Error::new_spanned(
+                &arg.opcode,
+                format!("duplicate opcode {:#04x}", opcode),
+            ));
+        }
+        defined[opcode as usize] = true;
+    }
+    if cfg!(feature = "check-opcodes") {
+        let missing: Vec<String> = (0..=LAST_OPCODE)
+            .filter(|opcode| !defined[*opcode as usize])
+            .map(|opcode| format!("{:#04x}", opcode))
+            .collect();
+        if !missing.is_empty() {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("missing opcodes: {}", missing.join(", ")),
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub fn define_instructions_inner(ast: &mut Args) -> proc_macro2::TokenStream {
+    if let Err(err) = check_opcodes(ast) {
+        return err.to_compile_error();
+    }
     let mut executor_arms = vec![];
     let mut executor_fns = vec![];
     for arg in ast.args.iter_mut() {
@@ -65,32 +132,127 @@ pub fn define_instructions_inner(ast: &mut Args) -> proc_macro2::TokenStream {
             instr_ident,
             executor,
         } = arg;
-        let instr_ident_string = instr_ident.to_string();
+        // `return`等关键字需要写成`r#return`
+        let instr_ident_string = instr_ident.unraw().to_string();
         let executor_fn_ident = format_ident!("execute_{}", instr_ident_string);
-        let executor_arm = quote! {
-            #opcode => self.#executor_fn_ident(),
-        };
-        executor_arms.push(executor_arm);
         let executor_fn = match executor {
             Executor::Fn(__fn) => {
+                let (decode_stmts, operand_idents) = match decode_operands(__fn) {
+                    Ok(decoded) => decoded,
+                    Err(err) => return err.to_compile_error(),
+                };
+                // 没有返回值的处理函数视为执行成功
+                let call = match __fn.sig.output {
+                    ReturnType::Default => quote! {
+                        {
+                            self.#executor_fn_ident(#(#operand_idents),*);
+                            Ok(())
+                        }
+                    },
+                    _ => quote!(self.#executor_fn_ident(#(#operand_idents),*)),
+                };
+                executor_arms.push(quote! {
+                    #opcode => {
+                        #(#decode_stmts)*
+                        #call
+                    }
+                });
+                let inputs = &__fn.sig.inputs;
                 __fn.sig.ident = executor_fn_ident;
-                __fn.sig.inputs = parse_quote!(&mut self);
+                __fn.sig.inputs = parse_quote!(&mut self, #inputs);
                 __fn.to_token_stream()
             }
-            Executor::Macro(__macro) => __macro.to_token_stream(),
+            Executor::Macro(__macro) => {
+                executor_arms.push(quote! {
+                    #opcode => {
+                        self.#executor_fn_ident();
+                        Ok(())
+                    }
+                });
+                __macro.to_token_stream()
+            }
         };
         executor_fns.push(executor_fn);
     }
 
     quote! {
         impl Thread {
-            pub fn execute(&mut self, opcode: u8) {
+            /// 执行当前pc处的指令, 操作数从opcode之后读取
+            pub fn execute(&mut self, opcode: u8) -> Result<(), RuntimeError> {
                 match opcode {
                     #(#executor_arms)*
-                    _ => unsafe { std::hint::unreachable_unchecked() }
-                };
+                    _ => Err(self.illegal_opcode(opcode)),
+                }
             }
             #(#executor_fns)*
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use crate::define_instrucitons::{Args, check_opcodes, define_instructions_inner};
+
+    // 指令表不完整, 只在默认模式下展开
+    #[cfg(not(feature = "check-opcodes"))]
+    #[test]
+    fn test_define_instructions_operands_expand() {
+        let mut args: Args = parse_quote! {
+            0x00 => nop {
+                fn nop() { self.inc_pc(1) }
+            };
+            0x11 => sipush {
+                fn sipush(value: i16, index: u8) -> Result<(), RuntimeError> {
+                    Ok(())
+                }
+            }
+        };
+        let raw_code = define_instructions_inner(&mut args).to_string();
+        assert!(raw_code.contains("0x00 => { { self . execute_nop () ; Ok (()) } }"));
+        assert!(raw_code.contains("let value = self . read_operand :: < i16 > (1u16) ;"));
+        assert!(raw_code.contains(
+            "let index = self . read_operand :: < u8 > (1u16 + < i16 as Operand > :: SIZE) ;"
+        ));
+        assert!(raw_code.contains("self . execute_sipush (value , index) }"));
+        assert!(raw_code.contains("fn execute_sipush (& mut self , value : i16 , index : u8)"));
+    }
+
+    #[test]
+    fn test_check_opcodes() {
+        let duplicate: Args = parse_quote! {
+            0x00 => nop { fn nop() {} };
+            0x00 => nop2 { fn nop2() {} }
+        };
+        let err = check_opcodes(&duplicate).unwrap_err();
+        assert_eq!(err.to_string(), "duplicate opcode 0x00");
+        let reserved: Args = parse_quote! {
+            0xca => breakpoint { fn breakpoint() {} }
+        };
+        let err = check_opcodes(&reserved).unwrap_err();
+        assert_eq!(err.to_string(), "reserved opcode 0xca");
+        let unknown: syn::Result<Args> = syn::parse_str("0x100 => unknown { fn unknown() {} }");
+        assert!(unknown.is_err());
+    }
+    // 指令表不完整, 只在默认模式下展开
+    #[cfg(not(feature = "check-opcodes"))]
+    #[test]
+    fn test_illegal_opcode_arm_expand() {
+        let mut args: Args = parse_quote! {
+            0x00 => nop { fn nop() {} }
+        };
+        let raw_code = define_instructions_inner(&mut args).to_string();
+        assert!(raw_code.contains("_ => Err (self . illegal_opcode (opcode)) ,"));
+        assert!(!raw_code.contains("unreachable_unchecked"));
+    }
+    #[cfg(feature = "check-opcodes")]
+    #[test]
+    fn test_check_missing_opcodes() {
+        let incomplete: Args = parse_quote! {
+            0x00 => nop { fn nop() {} }
+        };
+        let err = check_opcodes(&incomplete).unwrap_err();
+        assert!(err.to_string().starts_with("missing opcodes: 0x01, 0x02"));
+    }
+}
@@ -7,6 +7,7 @@ mod define_constants;
 mod define_instrucitons;
 mod impl_class_parser_for_vec;
 mod klass_debug;
+mod natives;
 mod utils;
 
 use proc_macro::TokenStream;
@@ -23,6 +24,7 @@ use crate::define_constants::define_constants_inner;
 use crate::define_instrucitons::define_instructions_inner;
 use crate::impl_class_parser_for_vec::impl_class_parser_for_vec_inner;
 use crate::klass_debug::klass_debug_derive_inner;
+use crate::natives::natives_inner;
 
 #[proc_macro]
 pub fn generate_ux(_: TokenStream) -> TokenStream {
@@ -84,7 +86,9 @@ pub fn attribute_enum(input: TokenStream) -> TokenStream {
         #[derive(Debug, ClassParser)]
         #[enum_entry(index(constant_pool[u16]))]
         pub enum Attribute {
-            #(#variants),*
+            #(#variants,)*
+            #[enum_entry(default)]
+            Unknown(UnknownAttribute)
         }
     }
     .into()
@@ -118,3 +122,10 @@ pub fn define_instructions(input: TokenStream) -> TokenStream {
     let mut ast = parse_macro_input!(input as define_instrucitons::Args);
     define_instructions_inner(&mut ast).into()
 }
+
+/// 向本地方法注册表登记Rust实现的本地方法, 描述符在编译期检查
+#[proc_macro]
+pub fn natives(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as natives::Args);
+    natives_inner(&ast).into()
+}
@@ -0,0 +1,178 @@
+use quote::quote;
+use syn::{
+    Expr, Ident, LitStr, Path, Token, braced,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+};
+
+/// `registry; "类名" { 方法名 "描述符" => 函数, ... } ...`
+pub struct Args {
+    registry: Expr,
+    classes: Vec<Class>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let registry: Expr = input.parse()?;
+        input.parse::<Token![;]>()?;
+        let mut classes = vec![];
+        while !input.is_empty() {
+            classes.push(input.parse()?);
+        }
+        Ok(Self { registry, classes })
+    }
+}
+
+struct Class {
+    name: LitStr,
+    methods: Punctuated<Native, Token![,]>,
+}
+
+impl Parse for Class {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: LitStr = input.parse()?;
+        if name.value().contains('.') {
+            return Err(syn::Error::new(
+                name.span(),
+                "class name must use '/' as separator",
+            ));
+        }
+        let content;
+        braced!(content in input);
+        let methods = Punctuated::parse_terminated(&content)?;
+        Ok(Self { name, methods })
+    }
+}
+
+struct Native {
+    // Java方法名可能是Rust关键字, 如Thread.yield
+    name: Ident,
+    descriptor: LitStr,
+    function: Path,
+}
+
+impl Parse for Native {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = Ident::parse_any(input)?;
+        let descriptor: LitStr = input.parse()?;
+        if !is_method_descriptor(&descriptor.value()) {
+            return Err(syn::Error::new(
+                descriptor.span(),
+                "invalid method descriptor",
+            ));
+        }
+        input.parse::<Token![=>]>()?;
+        let function: Path = input.parse()?;
+        Ok(Self {
+            name,
+            descriptor,
+            function,
+        })
+    }
+}
+
+/// `(参数类型*)返回类型`
+fn is_method_descriptor(descriptor: &str) -> bool {
+    let Some(mut rest) = descriptor.strip_prefix('(') else {
+        return false;
+    };
+    while !rest.starts_with(')') {
+        match field_type_len(rest) {
+            Some(len) => rest = &rest[len..],
+            None => return false,
+        }
+    }
+    let return_type = &rest[1..];
+    return_type == "V" || field_type_len(return_type) == Some(return_type.len())
+}
+
+/// `descriptor`开头的字段类型的长度
+fn field_type_len(descriptor: &str) -> Option<usize> {
+    match descriptor.as_bytes().first()? {
+        b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' => Some(1),
+        b'L' => {
+            let end = descriptor.find(';')?;
+            (end > 1).then_some(end + 1)
+        }
+        b'[' => field_type_len(&descriptor[1..]).map(|len| len + 1),
+        _ => None,
+    }
+}
+
+pub fn natives_inner(args: &Args) -> proc_macro2::TokenStream {
+    let registry = &args.registry;
+    let registers = args.classes.iter().flat_map(|class| {
+        let class_name = &class.name;
+        class.methods.iter().map(move |native| {
+            let name = native.name.unraw().to_string();
+            let descriptor = &native.descriptor;
+            let function = &native.function;
+            quote! {
+                registry.register(#class_name, #name, #descriptor, #function);
+            }
+        })
+    });
+    quote! {
+        {
+            let registry = &#registry;
+            #(#registers)*
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use syn::parse_str;
+
+    use crate::natives::{Args, is_method_descriptor, natives_inner};
+
+    #[test]
+    fn test_natives_expand() -> Result<(), Box<dyn Error>> {
+        let code = r#"
+            self.natives;
+            "java/lang/Thread" {
+                yield "()V" => thread_yield,
+                sleep "(J)V" => thread::sleep,
+            }
+            "java/lang/Object" {
+                hashCode "()I" => object_hash_code
+            }
+        "#;
+        let args: Args = parse_str(code)?;
+        let expanded = natives_inner(&args);
+        let raw_code = expanded.to_string();
+        assert!(raw_code.contains(
+            r#"registry . register ("java/lang/Thread" , "yield" , "()V" , thread_yield)"#
+        ));
+        assert!(raw_code.contains(r#""sleep" , "(J)V" , thread :: sleep"#));
+        assert!(raw_code.contains(r#""java/lang/Object" , "hashCode""#));
+        Ok(())
+    }
+
+    #[test]
+    fn test_natives_parse_err() {
+        let bad_descriptor = r#"registry; "java/lang/Object" { hashCode "()" => hash_code }"#;
+        let err = parse_str::<Args>(bad_descriptor).err().unwrap();
+        assert_eq!(err.to_string(), "invalid method descriptor");
+        let dotted = r#"registry; "java.lang.Object" { hashCode "()I" => hash_code }"#;
+        let err = parse_str::<Args>(dotted).err().unwrap();
+        assert_eq!(err.to_string(), "class name must use '/' as separator");
+    }
+
+    #[test]
+    fn test_method_descriptor() {
+        assert!(is_method_descriptor("()V"));
+        assert!(is_method_descriptor(
+            "(IJ[[Ljava/lang/String;)Ljava/lang/Object;"
+        ));
+        assert!(is_method_descriptor("([B)[I"));
+        assert!(!is_method_descriptor("I"));
+        assert!(!is_method_descriptor("(L;)V"));
+        assert!(!is_method_descriptor("(I)"));
+        assert!(!is_method_descriptor("(I)VV"));
+        assert!(!is_method_descriptor("(Q)V"));
+    }
+}
@@ -0,0 +1,93 @@
+class ArraysElement {
+    int value;
+}
+
+class Arrays {
+    static String first;
+    static int count;
+
+    public static void main(String[] args) {
+        count = args.length;
+        if (args.length > 0) {
+            first = args[0];
+        }
+    }
+
+    static int[] range(int n) {
+        int[] values = new int[n];
+        for (int i = 0; i < n; i++) {
+            values[i] = i;
+        }
+        return values;
+    }
+
+    static int sum(int[] values) {
+        int sum = 0;
+        for (int i = 0; i < values.length; i++) {
+            sum += values[i];
+        }
+        return sum;
+    }
+
+    static int typed(int n) {
+        byte[] bytes = new byte[n];
+        char[] chars = new char[n];
+        short[] shorts = new short[n];
+        boolean[] booleans = new boolean[n];
+        long[] longs = new long[n];
+        float[] floats = new float[n];
+        double[] doubles = new double[n];
+        bytes[0] = (byte) 300;
+        chars[0] = (char) -1;
+        shorts[0] = (short) 40000;
+        booleans[0] = true;
+        longs[0] = 1L << 40;
+        floats[0] = 1.5f;
+        doubles[0] = 2.5;
+        return bytes[0] + chars[0] + shorts[0] + (booleans[0] ? 1 : 0)
+                + (int) (longs[0] >> 40) + (int) (floats[0] * 2) + (int) (doubles[0] * 2);
+    }
+
+    static int get(int[] values, int index) {
+        return values[index];
+    }
+
+    static long[][] grid(int rows, int columns) {
+        long[][] grid = new long[rows][columns];
+        grid[rows - 1][columns - 1] = 7;
+        return grid;
+    }
+
+    static int[][][] partial(int n) {
+        return new int[n][n][];
+    }
+
+    static Object[] elements(int n) {
+        ArraysElement[] elements = new ArraysElement[n];
+        for (int i = 0; i < n; i++) {
+            elements[i] = new ArraysElement();
+            elements[i].value = i;
+        }
+        return elements;
+    }
+
+    static void store(Object[] array, Object value) {
+        array[0] = value;
+    }
+
+    static int length(Object array) {
+        return ((Object[]) array).length;
+    }
+
+    static boolean isObjectArray(Object object) {
+        return object instanceof Object[];
+    }
+
+    static boolean isIntArray(Object object) {
+        return object instanceof int[];
+    }
+
+    static boolean isCloneable(Object object) {
+        return object instanceof Cloneable;
+    }
+}
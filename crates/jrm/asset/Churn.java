@@ -0,0 +1,25 @@
+// allocation-heavy workload for comparing the collectors: most strings and
+// boxes die young, a ring of recent ones survives a few hundred iterations
+class Churn {
+    public static void main(String[] args) {
+        int n = Integer.parseInt(args[0]);
+        Object[] ring = new Object[256];
+        long sum = 0;
+        for (int i = 0; i < n; i++) {
+            StringBuilder builder = new StringBuilder();
+            builder.append("item").append(i).append(':').append(i * 31L);
+            String string = builder.toString();
+            Integer boxed = Integer.valueOf(i * 7);
+            sum += string.length() + boxed.intValue();
+            if (i % 2 == 0) {
+                ring[i % ring.length] = string;
+            } else {
+                ring[i % ring.length] = boxed;
+            }
+        }
+        for (Object object : ring) {
+            sum += object.hashCode() % 1000;
+        }
+        System.out.println(sum);
+    }
+}
@@ -0,0 +1,89 @@
+// compiled with: javac -source 8 -target 8 -bootclasspath ../classlib ClassLibrary.java
+public class ClassLibrary {
+    static class NotCloneable {
+        Object copy() throws CloneNotSupportedException {
+            return clone();
+        }
+    }
+
+    public static void main(String[] args) {
+        StringBuilder sb = new StringBuilder();
+        sb.append("a").append(1).append('c').append(2.5).append(true).append(3L).append((Object) null);
+        System.out.println(sb.toString());
+        System.out.println(sb.length());
+        System.out.println(sb.reverse());
+        sb.setLength(0);
+        sb.append("xyz").insert(1, "--").deleteCharAt(0);
+        System.out.println(sb);
+
+        Integer boxed = Integer.valueOf(127);
+        System.out.println(boxed == Integer.valueOf(127));
+        System.out.println(boxed.equals(Integer.valueOf(127)));
+        System.out.println(Integer.parseInt("-123") + 1);
+        System.out.println(Integer.toHexString(255) + " " + Integer.toBinaryString(-1));
+        System.out.println(Long.MAX_VALUE);
+        System.out.println(Integer.MIN_VALUE);
+        System.out.println(Long.parseLong("-9223372036854775808"));
+        System.out.println(Boolean.valueOf(true) + " " + Character.valueOf('z') + " " + Short.valueOf((short) -3));
+
+        System.out.println(0.1 + 0.2);
+        System.out.println(1.0e10);
+        System.out.println(0.001);
+        System.out.println(-0.0);
+        System.out.println(1.5f);
+        System.out.println(Double.parseDouble("2.5e-3") * 2);
+        System.out.println(1.0 / 0.0);
+        System.out.println(Math.max(3, 7) + " " + Math.abs(-2.5) + " " + Math.sqrt(16.0) + " " + Math.pow(2, 10));
+        System.out.println(Math.round(2.5) + " " + Math.floorMod(-7, 3) + " " + Math.floor(-1.5) + " " + Math.min(-0.0, 0.0));
+
+        System.out.println('x');
+        System.out.println(new char[] {'o', 'k'});
+        System.out.println(42L);
+        System.out.println((Object) "object");
+        System.out.println((String) null);
+
+        String s = "Hello, World";
+        System.out.println(s.length() + " " + s.charAt(4) + " " + s.indexOf("World") + " " + s.substring(7) + " " + s.toUpperCase());
+        System.out.println(s.hashCode());
+        System.out.println("  trim  ".trim() + "|" + "abc".compareTo("abd") + "|" + "ABC".equalsIgnoreCase("abc"));
+        System.out.println(s.contains("lo,") + " " + s.startsWith("Hell") + " " + s.endsWith("x") + " " + s.isEmpty());
+        System.out.println(Character.isDigit('7') + " " + Character.toUpperCase('q') + " " + Character.isWhitespace('\t'));
+
+        Object o = new Object();
+        System.out.println(o.equals(o) + " " + (o.hashCode() == System.identityHashCode(o)));
+        System.out.println(new int[0].getClass().getName());
+        System.out.println(s.getClass());
+        int[] src = {1, 2, 3, 4, 5};
+        System.arraycopy(src, 0, src, 1, 4);
+        System.out.println(src[0] + "," + src[1] + "," + src[4]);
+        int[] copy = (int[]) src.clone();
+        System.out.println(copy.length + " " + (copy != src));
+
+        try {
+            Integer.parseInt("x1");
+        } catch (NumberFormatException e) {
+            System.out.println(e);
+        }
+        try {
+            System.arraycopy(src, 3, src, 0, 5);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.out.println(e);
+        }
+        try {
+            Object x = null;
+            x.toString();
+        } catch (NullPointerException e) {
+            System.out.println("npe");
+        }
+        try {
+            new NotCloneable().copy();
+        } catch (CloneNotSupportedException e) {
+            System.out.println(e);
+        }
+        Throwable t = new IllegalStateException("outer", new RuntimeException("inner"));
+        System.out.println(t);
+        System.out.println(t.getCause().getMessage());
+        System.err.println("to stderr");
+        t.printStackTrace();
+    }
+}
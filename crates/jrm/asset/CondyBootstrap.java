@@ -0,0 +1,24 @@
+import java.lang.invoke.MethodHandles;
+
+// bootstrap methods of the dynamic constants in Condy.class, see scripts/GenerateCondy.java
+class CondyBootstrap {
+    static int calls;
+
+    static Object describe(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        calls++;
+        return name + " " + type.getName() + " in " + lookup.lookupClass().getName();
+    }
+
+    static long sum(MethodHandles.Lookup lookup, String name, Class<?> type, int a, long b) {
+        return a + b;
+    }
+
+    static Object identity(MethodHandles.Lookup lookup, String name, Class<?> type, Object value) {
+        return value;
+    }
+
+    static Object fail(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        calls++;
+        throw new IllegalStateException(name);
+    }
+}
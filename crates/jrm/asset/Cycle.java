@@ -0,0 +1,8 @@
+class CycleA extends CycleC {
+}
+
+class CycleB extends CycleA {
+}
+
+class CycleC {
+}
@@ -0,0 +1,51 @@
+class ErrorsMissing {
+    int value() {
+        return 1;
+    }
+}
+
+class Errors {
+    static int depth;
+
+    static int recurse() {
+        depth++;
+        return recurse() + 1;
+    }
+
+    static int catchStackOverflow() {
+        try {
+            return recurse();
+        } catch (StackOverflowError e) {
+            return -1;
+        }
+    }
+
+    static Object catchHugeArray() {
+        try {
+            int[] values = new int[Integer.MAX_VALUE];
+            return values;
+        } catch (OutOfMemoryError e) {
+            return e.getMessage();
+        }
+    }
+
+    static int catchNoClassDef() {
+        try {
+            return new ErrorsMissing().value();
+        } catch (NoClassDefFoundError e) {
+            return -1;
+        }
+    }
+
+    static int catchLinkage() {
+        try {
+            return new ErrorsMissing().value();
+        } catch (LinkageError e) {
+            return -2;
+        }
+    }
+
+    public static void main(String[] args) {
+        recurse();
+    }
+}
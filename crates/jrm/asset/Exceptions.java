@@ -0,0 +1,137 @@
+class ExceptionsCustom extends RuntimeException {
+    int code;
+
+    ExceptionsCustom(int code) {
+        super("custom");
+        this.code = code;
+    }
+}
+
+class ExceptionsError extends Error {
+    ExceptionsError() {
+        super("fatal");
+    }
+}
+
+class Exceptions {
+    static int finallyCount;
+
+    public static void main(String[] args) {
+        thrower(args.length);
+    }
+
+    static int thrower(int code) {
+        throw new ExceptionsCustom(code);
+    }
+
+    static int catchCustom(int code) {
+        try {
+            thrower(code);
+        } catch (ExceptionsCustom e) {
+            return e.code;
+        }
+        return 0;
+    }
+
+    static int catchSuper() {
+        try {
+            thrower(1);
+        } catch (RuntimeException e) {
+            return 2;
+        }
+        return 0;
+    }
+
+    static int catchDivide(int a, int b) {
+        try {
+            return a / b;
+        } catch (ArithmeticException e) {
+            return -1;
+        }
+    }
+
+    static int catchNull(int[] values) {
+        try {
+            return values.length;
+        } catch (NullPointerException e) {
+            return -2;
+        }
+    }
+
+    static int catchIndex(int[] values, int index) {
+        try {
+            return values[index];
+        } catch (IndexOutOfBoundsException e) {
+            return -3;
+        }
+    }
+
+    static int withFinally(int a, int b) {
+        try {
+            return a / b;
+        } finally {
+            finallyCount++;
+        }
+    }
+
+    static int nested(int a) {
+        try {
+            try {
+                return 10 / a;
+            } catch (NullPointerException e) {
+                return -1;
+            }
+        } catch (ArithmeticException e) {
+            return -4;
+        }
+    }
+
+    static int notCaught() {
+        try {
+            thrower(1);
+        } catch (ArithmeticException e) {
+            return -1;
+        }
+        return 0;
+    }
+
+    static int loopCatch(int n) {
+        int sum = 0;
+        for (int i = 0; i < n; i++) {
+            try {
+                sum += i + thrower(i);
+            } catch (ExceptionsCustom e) {
+                sum += 1;
+            }
+        }
+        return sum;
+    }
+
+    static Object caughtMessage() {
+        try {
+            int[] values = null;
+            values[0] = 1;
+            return null;
+        } catch (NullPointerException e) {
+            return e.getMessage();
+        }
+    }
+
+    static int throwNull() {
+        RuntimeException e = null;
+        throw e;
+    }
+
+    static int catchThrowable() {
+        try {
+            throwNull();
+        } catch (Throwable t) {
+            return t instanceof NullPointerException ? 1 : 0;
+        }
+        return 0;
+    }
+
+    static void throwError() {
+        throw new ExceptionsError();
+    }
+}
@@ -0,0 +1,77 @@
+class FieldsBase {
+    byte b;
+    long l;
+    Object o;
+    final int id;
+    static int created;
+
+    FieldsBase(int id) {
+        this.id = id;
+        created++;
+    }
+}
+
+interface FieldsMarker {
+}
+
+abstract class FieldsAbstract {
+}
+
+class Fields extends FieldsBase implements FieldsMarker {
+    static final int CONST = 7;
+    static long total = 100;
+    static boolean flag;
+    boolean z;
+    char c;
+    short s;
+    int i;
+    float f;
+    double d;
+    Fields next;
+
+    Fields(int id) {
+        super(id);
+    }
+
+    static Fields make(int i, long l) {
+        Fields fields = new Fields(i);
+        fields.i = i;
+        fields.l = l;
+        fields.b = (byte) i;
+        fields.c = (char) i;
+        fields.s = (short) i;
+        fields.d = 0.5;
+        total += l;
+        return fields;
+    }
+
+    static long sum(Fields fields) {
+        return fields.b + fields.c + fields.s + fields.i + fields.l + fields.id;
+    }
+
+    static Fields link(Fields first, Fields second) {
+        first.next = second;
+        first.o = second;
+        return first.next;
+    }
+
+    static int readNext(Fields fields) {
+        return fields.next.i;
+    }
+
+    static boolean isMarker(Object object) {
+        return object instanceof FieldsMarker;
+    }
+
+    static boolean isFields(Object object) {
+        return object instanceof Fields;
+    }
+
+    static Fields cast(Object object) {
+        return (Fields) object;
+    }
+
+    static int created() {
+        return created;
+    }
+}
@@ -0,0 +1,91 @@
+class GarbageNode {
+    int value;
+    GarbageNode next;
+
+    GarbageNode(int value, GarbageNode next) {
+        this.value = value;
+        this.next = next;
+    }
+}
+
+class Garbage {
+    static GarbageNode kept;
+    static Object[] table;
+
+    static int sum(GarbageNode head) {
+        int sum = 0;
+        for (GarbageNode node = head; node != null; node = node.next) {
+            sum += node.value;
+        }
+        return sum;
+    }
+
+    // the list is only reachable from locals while garbage arrays are allocated
+    static int buildList(int n) {
+        GarbageNode head = null;
+        for (int i = 0; i < n; i++) {
+            int[] garbage = new int[16];
+            garbage[0] = i;
+            head = new GarbageNode(garbage[0], head);
+        }
+        return sum(head);
+    }
+
+    // objects reachable from static fields and array elements
+    static int keepInStatics(int n) {
+        kept = null;
+        table = new Object[n];
+        for (int i = 0; i < n; i++) {
+            kept = new GarbageNode(i, kept);
+            table[i] = new GarbageNode(i * 2, null);
+            Object garbage = new int[8];
+        }
+        int sum = sum(kept);
+        for (int i = 0; i < n; i++) {
+            sum += ((GarbageNode) table[i]).value;
+        }
+        return sum;
+    }
+
+    static int churn(int n) {
+        int total = 0;
+        for (int i = 0; i < n; i++) {
+            int[] values = new int[1024];
+            values[1023] = i;
+            total += values[1023];
+        }
+        return total;
+    }
+
+    static int grid(int rows, int columns) {
+        long[][] grid = new long[rows][columns];
+        for (int i = 0; i < rows; i++) {
+            Object garbage = new int[columns][2];
+            grid[i][columns - 1] = i;
+        }
+        int sum = 0;
+        for (int i = 0; i < rows; i++) {
+            sum += (int) grid[i][columns - 1];
+        }
+        return sum;
+    }
+
+    static Object[] grow(Object[] chain) {
+        Object[] next = new Object[1024];
+        next[0] = chain;
+        return next;
+    }
+
+    // after the error the chain is only referenced by a dead local
+    static int exhaust() {
+        Object[] chain = null;
+        try {
+            while (true) {
+                chain = grow(chain);
+            }
+        } catch (OutOfMemoryError e) {
+            chain = null;
+        }
+        return churn(10);
+    }
+}
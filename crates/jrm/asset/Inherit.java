@@ -0,0 +1,16 @@
+interface Shape {
+    int area();
+}
+
+interface Named {
+}
+
+abstract class Base implements Shape {
+}
+
+class Square extends Base implements Named {
+    @Override
+    public int area() {
+        return 4;
+    }
+}
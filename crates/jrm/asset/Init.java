@@ -0,0 +1,36 @@
+class InitConstants {
+    static final int INT = 42;
+    static final long LONG = 1L << 40;
+    static final float FLOAT = 2.5f;
+    static final double DOUBLE = -1.5;
+    static final String STRING = "constant";
+    static int counter;
+    static Object ref;
+
+    static {
+    }
+}
+
+interface InitDefault {
+    Object MARK = new Object();
+
+    default void hello() {
+    }
+}
+
+interface InitPlain {
+    Object MARK = new Object();
+
+    void run();
+}
+
+class InitParent {
+    static int value = 1;
+}
+
+class InitChild extends InitParent implements InitDefault, InitPlain {
+    static int value = 2;
+
+    public void run() {
+    }
+}
@@ -0,0 +1,116 @@
+class InvokeBase {
+    int value() {
+        return 1;
+    }
+
+    public int pub() {
+        return 10;
+    }
+
+    private int secret() {
+        return 100;
+    }
+
+    int callSecret() {
+        return secret();
+    }
+
+    static int twice(int x) {
+        return x * 2;
+    }
+
+    static long add(long a, int b, double c) {
+        return a + b + (long) c;
+    }
+
+    static class Nested {
+        static int peek(InvokeBase base) {
+            return base.secret();
+        }
+    }
+}
+
+class InvokeDerived extends InvokeBase {
+    int value() {
+        return 2;
+    }
+
+    public int pub() {
+        return super.pub() + 5;
+    }
+}
+
+interface InvokeIface {
+    default int hello() {
+        return 42;
+    }
+
+    int abs();
+}
+
+interface InvokeOther {
+    default int hello() {
+        return 43;
+    }
+}
+
+abstract class InvokeAbstract implements InvokeIface {
+}
+
+class InvokeImpl extends InvokeAbstract {
+    public int abs() {
+        return 7;
+    }
+}
+
+class InvokeConflict implements InvokeIface, InvokeOther {
+    public int hello() {
+        return InvokeOther.super.hello();
+    }
+
+    public int abs() {
+        return 8;
+    }
+}
+
+class Invoke {
+    static int callValue(InvokeBase base) {
+        return base.value();
+    }
+
+    static int callPub(InvokeBase base) {
+        return base.pub();
+    }
+
+    static int callSecret(InvokeBase base) {
+        return base.callSecret() + InvokeBase.Nested.peek(base);
+    }
+
+    static int callHello(InvokeIface iface) {
+        return iface.hello();
+    }
+
+    static int callAbs(InvokeIface iface) {
+        return iface.abs();
+    }
+
+    static long callAdd() {
+        return InvokeBase.add(1L << 40, InvokeBase.twice(3), 2.5);
+    }
+
+    static int fib(int n) {
+        return n < 2 ? n : fib(n - 1) + fib(n - 2);
+    }
+
+    static float half(float f) {
+        return f / 2;
+    }
+
+    static double neg(double d) {
+        return -d;
+    }
+
+    static Object same(Object o) {
+        return o;
+    }
+}
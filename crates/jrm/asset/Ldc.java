@@ -0,0 +1,30 @@
+class Ldc {
+    static Object string() {
+        return "hello";
+    }
+
+    static Object klass() {
+        return Ldc.class;
+    }
+
+    static int integer() {
+        return 100000;
+    }
+
+    static float fl() {
+        return 1.5f;
+    }
+
+    static long lo() {
+        return 1234567890123L;
+    }
+
+    static double dbl() {
+        return 2.5;
+    }
+
+    static Runnable lambda() {
+        return () -> {
+        };
+    }
+}
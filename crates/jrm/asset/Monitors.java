@@ -0,0 +1,58 @@
+public class Monitors {
+    static int counter;
+    int value;
+
+    // the block re-enters the monitor already held by the method
+    synchronized int increment(int delta) {
+        synchronized (this) {
+            value += delta;
+        }
+        return value;
+    }
+
+    static synchronized int staticCount() {
+        return ++counter;
+    }
+
+    static int nested(Object a, Object b) {
+        synchronized (a) {
+            synchronized (b) {
+                synchronized (a) {
+                    return 3;
+                }
+            }
+        }
+    }
+
+    synchronized void fail() {
+        throw new IllegalStateException("fail");
+    }
+
+    static int failAndCatch(Monitors monitors) {
+        try {
+            monitors.fail();
+        } catch (IllegalStateException e) {
+            return 1;
+        }
+        return 0;
+    }
+
+    static void throwInBlock(Object lock) {
+        synchronized (lock) {
+            throw new IllegalStateException("block");
+        }
+    }
+
+    static Object lockNull(Object lock) {
+        synchronized (lock) {
+            return lock;
+        }
+    }
+
+    static int add(Monitors monitors, int times) {
+        for (int i = 0; i < times; i++) {
+            monitors.increment(1);
+        }
+        return monitors.value;
+    }
+}
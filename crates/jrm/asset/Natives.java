@@ -0,0 +1,40 @@
+public class Natives {
+    static native int add(int a, int b);
+
+    static native long scale(long value, double factor);
+
+    static native int length(Object[] array);
+
+    static native void fail(String message);
+
+    static native int missing();
+
+    static int callAdd() {
+        return add(40, 2);
+    }
+
+    static long callScale() {
+        return scale(1000000000000L, 1.5);
+    }
+
+    static int callLength() {
+        return length(new Object[7]);
+    }
+
+    static int catchFail() {
+        try {
+            fail("boom");
+            return 0;
+        } catch (RuntimeException e) {
+            return 1;
+        }
+    }
+
+    static void uncaughtFail() {
+        fail("boom");
+    }
+
+    static int callMissing() {
+        return missing();
+    }
+}
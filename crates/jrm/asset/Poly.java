@@ -0,0 +1,85 @@
+interface PolyShape {
+    int sides();
+}
+
+abstract class PolyBase implements PolyShape {
+    abstract int value();
+
+    int base() {
+        return 100;
+    }
+}
+
+class PolyA extends PolyBase {
+    int value() {
+        return 1;
+    }
+
+    public int sides() {
+        return 3;
+    }
+}
+
+class PolyB extends PolyBase {
+    int value() {
+        return 2;
+    }
+
+    public int sides() {
+        return 4;
+    }
+}
+
+class PolyC extends PolyA {
+    int value() {
+        return 3;
+    }
+}
+
+class PolyD extends PolyC {
+}
+
+class Poly {
+    // runs the loop named by args[0] for args[1] iterations
+    public static void main(String[] args) {
+        int n = Integer.parseInt(args[1]);
+        PolyBase a = new PolyA();
+        PolyBase b = new PolyB();
+        PolyBase d = new PolyD();
+        int sum;
+        if (args[0].equals("virtual")) {
+            sum = virtualLoop(a, b, d, n);
+        } else if (args[0].equals("interface")) {
+            sum = interfaceLoop(a, b, d, n);
+        } else {
+            sum = monomorphicLoop(d, n);
+        }
+        System.out.println(sum);
+    }
+
+    static int virtualLoop(PolyBase a, PolyBase b, PolyBase c, int n) {
+        int sum = 0;
+        for (int i = 0; i < n; i++) {
+            PolyBase shape = i % 3 == 0 ? a : i % 3 == 1 ? b : c;
+            sum += shape.value() + shape.base();
+        }
+        return sum;
+    }
+
+    static int interfaceLoop(PolyShape a, PolyShape b, PolyShape c, int n) {
+        int sum = 0;
+        for (int i = 0; i < n; i++) {
+            PolyShape shape = i % 3 == 0 ? a : i % 3 == 1 ? b : c;
+            sum += shape.sides();
+        }
+        return sum;
+    }
+
+    static int monomorphicLoop(PolyBase shape, int n) {
+        int sum = 0;
+        for (int i = 0; i < n; i++) {
+            sum += shape.value();
+        }
+        return sum;
+    }
+}
@@ -0,0 +1,23 @@
+interface Greeter {
+    default String greet() {
+        return "hi";
+    }
+}
+
+class Parent {
+    int parentField;
+    static int counter;
+}
+
+class Child extends Parent implements Greeter {
+    int childField;
+
+    void touch() {
+        parentField = 1;
+        childField = 2;
+        counter++;
+        greet();
+        Greeter greeter = this;
+        greeter.greet();
+    }
+}
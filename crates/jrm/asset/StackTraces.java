@@ -0,0 +1,51 @@
+class StackTracesException extends RuntimeException {
+    StackTracesException() {
+        super("deep");
+    }
+}
+
+class StackTraces {
+    static void level2() {
+        throw new StackTracesException();
+    }
+
+    static void level1() {
+        level2();
+    }
+
+    static StackTraceElement[] explicit() {
+        try {
+            level1();
+        } catch (RuntimeException e) {
+            return e.getStackTrace();
+        }
+        return null;
+    }
+
+    static StackTraceElement[] implicit(int[] values) {
+        try {
+            values[0] = 1;
+        } catch (NullPointerException e) {
+            return e.getStackTrace();
+        }
+        return null;
+    }
+
+    static int rethrown() {
+        RuntimeException saved = null;
+        try {
+            level1();
+        } catch (RuntimeException e) {
+            saved = e;
+        }
+        try {
+            throw saved;
+        } catch (RuntimeException e) {
+            return e.getStackTrace()[0].getLineNumber();
+        }
+    }
+
+    public static void main(String[] args) {
+        level1();
+    }
+}
@@ -0,0 +1,43 @@
+public class Strings {
+    static final String HELLO = "hello";
+
+    public static void main(String[] args) {
+        // literals are interned, also across classes
+        String built = new StringBuilder("hel").append("lo").toString();
+        System.out.println(built == HELLO);
+        System.out.println(built.equals(HELLO));
+        System.out.println(built.intern() == HELLO);
+        System.out.println(StringsOther.hello() == HELLO);
+        String fresh = new StringBuilder("fre").append("sh").toString();
+        System.out.println(fresh.intern() == fresh);
+        System.out.println(new StringBuilder("fr").append("esh").toString().intern() == fresh);
+
+        // constants with NUL, non-Latin1 and supplementary characters
+        String nul = "a\0b";
+        System.out.println(nul.length() + " " + (int) nul.charAt(1) + " " + nul.hashCode());
+        String cjk = "\u4e2d\u6587";
+        System.out.println(cjk.length() + " " + cjk.hashCode() + " " + cjk.equals(new StringBuilder("\u4e2d").append('\u6587').toString()));
+        String emoji = "x\ud83d\ude00";
+        System.out.println(emoji.length() + " " + Integer.toHexString(emoji.charAt(1)) + " "
+                + emoji.hashCode() + " " + emoji.indexOf(0x1f600));
+        String lone = "\ud800";
+        System.out.println(lone.length() + " " + Integer.toHexString(lone.charAt(0)));
+        System.out.println("caf\u00e9".toUpperCase().equals("CAF\u00c9"));
+        System.out.println(cjk.concat("!").substring(1).equals("\u6587!"));
+    }
+
+    // allocates only through String natives
+    static int churn(int n) {
+        String kept = "";
+        for (int i = 0; i < n; i++) {
+            kept = kept.concat("ab").toUpperCase().substring(1).toLowerCase();
+        }
+        return kept.length() + kept.hashCode();
+    }
+}
+
+class StringsOther {
+    static String hello() {
+        return "hello";
+    }
+}
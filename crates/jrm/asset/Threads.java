@@ -0,0 +1,193 @@
+public class Threads {
+    static final Object lock = new Object();
+    static int counter;
+
+    static class Counter implements Runnable {
+        public void run() {
+            for (int i = 0; i < 10000; i++) {
+                synchronized (lock) {
+                    counter++;
+                }
+            }
+        }
+    }
+
+    // bounded buffer, producers and consumers wait on the same monitor
+    static class Queue {
+        private final int[] items = new int[2];
+        private int head;
+        private int size;
+
+        synchronized void put(int item) throws InterruptedException {
+            while (size == items.length) {
+                wait();
+            }
+            items[(head + size) % items.length] = item;
+            size++;
+            notifyAll();
+        }
+
+        synchronized int take() throws InterruptedException {
+            while (size == 0) {
+                wait();
+            }
+            int item = items[head];
+            head = (head + 1) % items.length;
+            size--;
+            notifyAll();
+            return item;
+        }
+    }
+
+    static class Sleeper extends Thread {
+        private final StringBuilder order;
+        private final long millis;
+
+        Sleeper(String name, StringBuilder order, long millis) {
+            super(name);
+            this.order = order;
+            this.millis = millis;
+        }
+
+        public void run() {
+            try {
+                Thread.sleep(millis);
+            } catch (InterruptedException e) {
+                return;
+            }
+            synchronized (order) {
+                order.append(getName());
+            }
+        }
+    }
+
+    static class Appender extends Thread {
+        private final StringBuilder builder;
+        private final char ch;
+
+        Appender(StringBuilder builder, char ch) {
+            this.builder = builder;
+            this.ch = ch;
+        }
+
+        public void run() {
+            for (int i = 0; i < 200; i++) {
+                synchronized (builder) {
+                    builder.append(ch);
+                }
+            }
+        }
+    }
+
+    // the result depends only on the scheduler's time slices
+    static String interleave() throws InterruptedException {
+        StringBuilder builder = new StringBuilder();
+        Thread a = new Appender(builder, 'a');
+        Thread b = new Appender(builder, 'b');
+        a.start();
+        b.start();
+        a.join();
+        b.join();
+        return builder.toString();
+    }
+
+    public static void main(String[] args) throws Exception {
+        Thread main = Thread.currentThread();
+        System.out.println(main.getName() + " " + main.isAlive() + " " + (main == Thread.currentThread()));
+
+        Thread[] counters = new Thread[3];
+        for (int i = 0; i < counters.length; i++) {
+            counters[i] = new Thread(new Counter());
+            counters[i].start();
+        }
+        for (Thread thread : counters) {
+            thread.join();
+        }
+        System.out.println(counter + " " + counters[0].isAlive() + " " + counters[2].getName());
+
+        final Queue queue = new Queue();
+        Thread producer = new Thread(new Runnable() {
+            public void run() {
+                try {
+                    for (int i = 1; i <= 100; i++) {
+                        queue.put(i);
+                    }
+                    queue.put(0);
+                } catch (InterruptedException e) {
+                }
+            }
+        }, "producer");
+        producer.start();
+        int sum = 0;
+        for (int item = queue.take(); item != 0; item = queue.take()) {
+            sum += item;
+        }
+        producer.join();
+        System.out.println("sum " + sum);
+
+        StringBuilder order = new StringBuilder();
+        Thread[] sleepers = {
+            new Sleeper("c", order, 30), new Sleeper("a", order, 10), new Sleeper("b", order, 20)
+        };
+        for (Thread sleeper : sleepers) {
+            sleeper.start();
+        }
+        for (Thread sleeper : sleepers) {
+            sleeper.join();
+        }
+        System.out.println(order);
+
+        final String[] name = new String[1];
+        Thread named = new Thread("worker") {
+            public void run() {
+                Thread.yield();
+                name[0] = Thread.currentThread().getName();
+            }
+        };
+        named.start();
+        named.join();
+        System.out.println(name[0] + " " + named);
+
+        long start = System.currentTimeMillis();
+        synchronized (lock) {
+            lock.wait(50);
+        }
+        System.out.println(System.currentTimeMillis() - start >= 50);
+
+        try {
+            lock.notify();
+        } catch (IllegalMonitorStateException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            named.start();
+        } catch (IllegalThreadStateException e) {
+            System.out.println("started");
+        }
+        try {
+            Thread.sleep(-1);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+
+        Thread failing = new Thread("failing") {
+            public void run() {
+                throw new IllegalStateException("boom");
+            }
+        };
+        failing.start();
+        failing.join();
+
+        // a daemon thread does not keep the VM alive
+        Thread daemon = new Thread(new Runnable() {
+            public void run() {
+                while (true) {
+                    Thread.yield();
+                }
+            }
+        });
+        daemon.setDaemon(true);
+        daemon.start();
+        System.out.println("done " + daemon.isDaemon());
+    }
+}
@@ -0,0 +1,11 @@
+package dispatch.a;
+
+public class A {
+    int m() {
+        return 1;
+    }
+
+    public int callM() {
+        return m();
+    }
+}
@@ -0,0 +1,7 @@
+package dispatch.a;
+
+public class B extends A {
+    public int m() {
+        return 2;
+    }
+}
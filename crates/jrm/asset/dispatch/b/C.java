@@ -0,0 +1,8 @@
+package dispatch.b;
+
+// does not override A.m, different package
+public class C extends dispatch.a.A {
+    public int m() {
+        return 3;
+    }
+}
@@ -0,0 +1,8 @@
+package dispatch.b;
+
+// overrides A.m transitively through B.m
+public class D extends dispatch.a.B {
+    public int m() {
+        return 4;
+    }
+}
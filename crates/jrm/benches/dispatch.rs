@@ -0,0 +1,37 @@
+use std::{io, sync::Arc};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use jrm::runtime::{ClassLoader, ClassPath, Console, Vm};
+
+/// 每次执行Poly.main时调用循环的次数
+const ITERATIONS: u32 = 100_000;
+
+/// 从asset目录加载类, 丢弃Java程序的输出
+fn vm(inline_caches: bool) -> Arc<Vm> {
+    let mut class_path = ClassPath::from(concat!(env!("CARGO_MANIFEST_DIR"), "/asset"));
+    class_path.ensure_class_library();
+    let vm = Vm::new(ClassLoader::new(class_path))
+        .with_console(Console::new(io::sink(), io::sink()))
+        .with_inline_caches(inline_caches);
+    Arc::new(vm)
+}
+
+/// 多态调用循环在开启和关闭内联缓存时的耗时
+fn bench_dispatch(c: &mut Criterion) {
+    for loop_name in ["virtual", "interface", "monomorphic"] {
+        let mut group = c.benchmark_group(format!("Poly.{}Loop", loop_name));
+        let args = [loop_name.to_string(), ITERATIONS.to_string()];
+        for (id, inline_caches) in [("cached", true), ("uncached", false)] {
+            let vm = vm(inline_caches);
+            group.bench_function(id, |b| b.iter(|| vm.run_main("Poly", &args).unwrap()));
+        }
+        group.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_dispatch
+}
+criterion_main!(benches);
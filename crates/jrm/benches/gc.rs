@@ -0,0 +1,43 @@
+use std::{io, sync::Arc};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use jrm::runtime::{ClassLoader, ClassPath, CollectorKind, Console, GcOptions, Vm};
+
+/// 每次执行Churn.main时分配循环的次数
+const ITERATIONS: u32 = 20_000;
+
+/// 从asset目录加载类, 丢弃Java程序的输出
+fn vm(collector: CollectorKind) -> Arc<Vm> {
+    let mut class_path = ClassPath::from(concat!(env!("CARGO_MANIFEST_DIR"), "/asset"));
+    class_path.ensure_class_library();
+    let options = GcOptions {
+        collector,
+        // 较小的堆使每次执行中都有多次回收
+        max_heap: 1024 * 1024,
+        ..Default::default()
+    };
+    let vm = Vm::with_options(ClassLoader::new(class_path), options)
+        .with_console(Console::new(io::sink(), io::sink()));
+    Arc::new(vm)
+}
+
+/// 同一个分配密集的程序在两种收集器下的耗时
+fn bench_collectors(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Churn");
+    let args = [ITERATIONS.to_string()];
+    for (id, collector) in [
+        ("mark_sweep", CollectorKind::MarkSweep),
+        ("generational", CollectorKind::Generational),
+    ] {
+        let vm = vm(collector);
+        group.bench_function(id, |b| b.iter(|| vm.run_main("Churn", &args).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_collectors
+}
+criterion_main!(benches);
@@ -0,0 +1,91 @@
+package java.io;
+
+// writes straight to a file descriptor of the VM console instead of an OutputStream
+public class PrintStream {
+    private final int fd;
+
+    public PrintStream(int fd) {
+        this.fd = fd;
+    }
+
+    private native void write(String s, boolean newLine);
+
+    public void flush() {
+    }
+
+    public void print(String s) {
+        write(String.valueOf(s), false);
+    }
+
+    public void print(Object obj) {
+        write(String.valueOf(obj), false);
+    }
+
+    public void print(char[] s) {
+        write(String.valueOf(s), false);
+    }
+
+    public void print(boolean b) {
+        write(String.valueOf(b), false);
+    }
+
+    public void print(char c) {
+        write(String.valueOf(c), false);
+    }
+
+    public void print(int i) {
+        write(String.valueOf(i), false);
+    }
+
+    public void print(long l) {
+        write(String.valueOf(l), false);
+    }
+
+    public void print(float f) {
+        write(String.valueOf(f), false);
+    }
+
+    public void print(double d) {
+        write(String.valueOf(d), false);
+    }
+
+    public void println() {
+        write("", true);
+    }
+
+    public void println(String x) {
+        write(String.valueOf(x), true);
+    }
+
+    public void println(Object x) {
+        write(String.valueOf(x), true);
+    }
+
+    public void println(char[] x) {
+        write(String.valueOf(x), true);
+    }
+
+    public void println(boolean x) {
+        write(String.valueOf(x), true);
+    }
+
+    public void println(char x) {
+        write(String.valueOf(x), true);
+    }
+
+    public void println(int x) {
+        write(String.valueOf(x), true);
+    }
+
+    public void println(long x) {
+        write(String.valueOf(x), true);
+    }
+
+    public void println(float x) {
+        write(String.valueOf(x), true);
+    }
+
+    public void println(double x) {
+        write(String.valueOf(x), true);
+    }
+}
@@ -0,0 +1,80 @@
+package java.lang;
+
+public final class Byte extends Number implements Comparable<Byte> {
+    public static final byte MIN_VALUE = (byte) 0x80;
+    public static final byte MAX_VALUE = 0x7f;
+    public static final int SIZE = 8;
+
+    private static final Byte[] CACHE = new Byte[256];
+
+    static {
+        for (int i = 0; i < CACHE.length; i++) {
+            CACHE[i] = new Byte((byte) (i - 128));
+        }
+    }
+
+    private final byte value;
+
+    public Byte(byte value) {
+        this.value = value;
+    }
+
+    public static Byte valueOf(byte value) {
+        if (value >= -128 && value <= 127) {
+            return CACHE[value + 128];
+        }
+        return new Byte(value);
+    }
+
+    public static byte parseByte(String s) {
+        return (byte) Long.parse(s, 10, MIN_VALUE, MAX_VALUE);
+    }
+
+    public static String toString(byte value) {
+        return Integer.toString(value);
+    }
+
+    public static int compare(byte x, byte y) {
+        return x - y;
+    }
+
+    public static int hashCode(byte value) {
+        return value;
+    }
+
+    public int intValue() {
+        return value;
+    }
+
+    public long longValue() {
+        return value;
+    }
+
+    public float floatValue() {
+        return value;
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public byte byteValue() {
+        return value;
+    }
+
+    public int compareTo(Byte anotherByte) {
+        return compare(value, anotherByte.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Byte && value == ((Byte) obj).value;
+    }
+
+    public int hashCode() {
+        return value;
+    }
+
+    public String toString() {
+        return toString(value);
+    }
+}
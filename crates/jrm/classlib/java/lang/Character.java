@@ -0,0 +1,101 @@
+package java.lang;
+
+public final class Character implements java.io.Serializable, Comparable<Character> {
+    public static final char MIN_VALUE = '\u0000';
+    public static final char MAX_VALUE = '\uffff';
+    public static final int MIN_RADIX = 2;
+    public static final int MAX_RADIX = 36;
+    public static final int SIZE = 16;
+
+    private static final Character[] CACHE = new Character[128];
+
+    static {
+        for (int i = 0; i < CACHE.length; i++) {
+            CACHE[i] = new Character((char) i);
+        }
+    }
+
+    private final char value;
+
+    public Character(char value) {
+        this.value = value;
+    }
+
+    public static Character valueOf(char c) {
+        if (c < 128) {
+            return CACHE[c];
+        }
+        return new Character(c);
+    }
+
+    public static String toString(char c) {
+        return String.valueOf(c);
+    }
+
+    public static int digit(char ch, int radix) {
+        int value;
+        if (ch >= '0' && ch <= '9') {
+            value = ch - '0';
+        } else if (ch >= 'a' && ch <= 'z') {
+            value = ch - 'a' + 10;
+        } else if (ch >= 'A' && ch <= 'Z') {
+            value = ch - 'A' + 10;
+        } else {
+            return -1;
+        }
+        return value < radix ? value : -1;
+    }
+
+    public static char forDigit(int digit, int radix) {
+        if (digit >= radix || digit < 0 || radix < MIN_RADIX || radix > MAX_RADIX) {
+            return '\0';
+        }
+        return Integer.DIGITS[digit];
+    }
+
+    public static native boolean isDigit(char ch);
+
+    public static native boolean isLetter(char ch);
+
+    public static boolean isLetterOrDigit(char ch) {
+        return isLetter(ch) || isDigit(ch);
+    }
+
+    public static native boolean isWhitespace(char ch);
+
+    public static native boolean isUpperCase(char ch);
+
+    public static native boolean isLowerCase(char ch);
+
+    public static native char toUpperCase(char ch);
+
+    public static native char toLowerCase(char ch);
+
+    public static int compare(char x, char y) {
+        return x - y;
+    }
+
+    public static int hashCode(char value) {
+        return value;
+    }
+
+    public char charValue() {
+        return value;
+    }
+
+    public int compareTo(Character anotherCharacter) {
+        return compare(value, anotherCharacter.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Character && value == ((Character) obj).value;
+    }
+
+    public int hashCode() {
+        return value;
+    }
+
+    public String toString() {
+        return toString(value);
+    }
+}
@@ -0,0 +1,17 @@
+package java.lang;
+
+public final class Class<T> implements java.io.Serializable {
+    // instances are created by the VM only
+    private Class() {
+    }
+
+    public native String getName();
+
+    public native boolean isInterface();
+
+    public native boolean isArray();
+
+    public String toString() {
+        return (isInterface() ? "interface " : "class ") + getName();
+    }
+}
@@ -0,0 +1,117 @@
+package java.lang;
+
+public final class Double extends Number implements Comparable<Double> {
+    public static final double POSITIVE_INFINITY = 1.0 / 0.0;
+    public static final double NEGATIVE_INFINITY = -1.0 / 0.0;
+    public static final double NaN = 0.0d / 0.0;
+    public static final double MAX_VALUE = 0x1.fffffffffffffP+1023;
+    public static final double MIN_VALUE = 0x0.0000000000001P-1022;
+    public static final int SIZE = 64;
+
+    private final double value;
+
+    public Double(double value) {
+        this.value = value;
+    }
+
+    public static Double valueOf(double d) {
+        return new Double(d);
+    }
+
+    public static Double valueOf(String s) {
+        return new Double(parseDouble(s));
+    }
+
+    public static native double parseDouble(String s);
+
+    public static native String toString(double d);
+
+    public static boolean isNaN(double v) {
+        return v != v;
+    }
+
+    public static boolean isInfinite(double v) {
+        return v == POSITIVE_INFINITY || v == NEGATIVE_INFINITY;
+    }
+
+    public static boolean isFinite(double d) {
+        return Math.abs(d) <= MAX_VALUE;
+    }
+
+    public static native long doubleToRawLongBits(double value);
+
+    public static long doubleToLongBits(double value) {
+        return isNaN(value) ? 0x7ff8000000000000L : doubleToRawLongBits(value);
+    }
+
+    public static native double longBitsToDouble(long bits);
+
+    public static int compare(double d1, double d2) {
+        if (d1 < d2) {
+            return -1;
+        }
+        if (d1 > d2) {
+            return 1;
+        }
+        long bits1 = doubleToLongBits(d1);
+        long bits2 = doubleToLongBits(d2);
+        return (bits1 == bits2) ? 0 : ((bits1 < bits2) ? -1 : 1);
+    }
+
+    public static int hashCode(double value) {
+        return Long.hashCode(doubleToLongBits(value));
+    }
+
+    public static double max(double a, double b) {
+        return Math.max(a, b);
+    }
+
+    public static double min(double a, double b) {
+        return Math.min(a, b);
+    }
+
+    public static double sum(double a, double b) {
+        return a + b;
+    }
+
+    public boolean isNaN() {
+        return isNaN(value);
+    }
+
+    public boolean isInfinite() {
+        return isInfinite(value);
+    }
+
+    public int intValue() {
+        return (int) value;
+    }
+
+    public long longValue() {
+        return (long) value;
+    }
+
+    public float floatValue() {
+        return (float) value;
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public int compareTo(Double anotherDouble) {
+        return compare(value, anotherDouble.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Double
+                && doubleToLongBits(((Double) obj).value) == doubleToLongBits(value);
+    }
+
+    public int hashCode() {
+        return hashCode(value);
+    }
+
+    public String toString() {
+        return toString(value);
+    }
+}
@@ -0,0 +1,10 @@
+package java.lang;
+
+public class InstantiationError extends IncompatibleClassChangeError {
+    public InstantiationError() {
+    }
+
+    public InstantiationError(String message) {
+        super(message);
+    }
+}
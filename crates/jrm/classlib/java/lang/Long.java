@@ -0,0 +1,183 @@
+package java.lang;
+
+public final class Long extends Number implements Comparable<Long> {
+    public static final long MIN_VALUE = 0x8000000000000000L;
+    public static final long MAX_VALUE = 0x7fffffffffffffffL;
+    public static final int SIZE = 64;
+
+    private static final Long[] CACHE = new Long[256];
+
+    static {
+        for (int i = 0; i < CACHE.length; i++) {
+            CACHE[i] = new Long(i - 128);
+        }
+    }
+
+    private final long value;
+
+    public Long(long value) {
+        this.value = value;
+    }
+
+    public static Long valueOf(long l) {
+        if (l >= -128 && l <= 127) {
+            return CACHE[(int) l + 128];
+        }
+        return new Long(l);
+    }
+
+    public static Long valueOf(String s) {
+        return valueOf(parseLong(s, 10));
+    }
+
+    public static long parseLong(String s) {
+        return parseLong(s, 10);
+    }
+
+    public static long parseLong(String s, int radix) {
+        return parse(s, radix, MIN_VALUE, MAX_VALUE);
+    }
+
+    // accumulates negatively so that MIN_VALUE can be parsed without overflow
+    static long parse(String s, int radix, long min, long max) {
+        if (s == null) {
+            throw new NumberFormatException("Cannot parse null string: null");
+        }
+        if (radix < 2 || radix > 36) {
+            throw new NumberFormatException("radix " + radix + " out of range");
+        }
+        int length = s.length();
+        if (length == 0) {
+            throw forInputString(s, radix);
+        }
+        int i = 0;
+        boolean negative = false;
+        long limit = -max;
+        char first = s.charAt(0);
+        if (first == '-' || first == '+') {
+            if (length == 1) {
+                throw forInputString(s, radix);
+            }
+            negative = first == '-';
+            if (negative) {
+                limit = min;
+            }
+            i++;
+        }
+        long multiplyLimit = limit / radix;
+        long result = 0;
+        while (i < length) {
+            int digit = Character.digit(s.charAt(i++), radix);
+            if (digit < 0 || result < multiplyLimit) {
+                throw forInputString(s, radix);
+            }
+            result *= radix;
+            if (result < limit + digit) {
+                throw forInputString(s, radix);
+            }
+            result -= digit;
+        }
+        return negative ? result : -result;
+    }
+
+    private static NumberFormatException forInputString(String s, int radix) {
+        String suffix = radix == 10 ? "" : " under radix " + radix;
+        return new NumberFormatException("For input string: \"" + s + "\"" + suffix);
+    }
+
+    public static String toString(long l) {
+        return toString(l, 10);
+    }
+
+    public static String toString(long l, int radix) {
+        if (radix < 2 || radix > 36) {
+            radix = 10;
+        }
+        char[] buf = new char[65];
+        int pos = buf.length;
+        boolean negative = l < 0;
+        if (!negative) {
+            l = -l;
+        }
+        // digits of a negative number, so MIN_VALUE needs no special case
+        do {
+            buf[--pos] = Integer.DIGITS[(int) -(l % radix)];
+            l /= radix;
+        } while (l != 0);
+        if (negative) {
+            buf[--pos] = '-';
+        }
+        return String.valueOf(buf, pos, buf.length - pos);
+    }
+
+    static String toUnsignedString(long l, int shift) {
+        char[] buf = new char[64];
+        int pos = buf.length;
+        long mask = (1L << shift) - 1;
+        do {
+            buf[--pos] = Integer.DIGITS[(int) (l & mask)];
+            l >>>= shift;
+        } while (l != 0);
+        return String.valueOf(buf, pos, buf.length - pos);
+    }
+
+    public static String toHexString(long l) {
+        return toUnsignedString(l, 4);
+    }
+
+    public static String toBinaryString(long l) {
+        return toUnsignedString(l, 1);
+    }
+
+    public static int compare(long x, long y) {
+        return (x < y) ? -1 : ((x == y) ? 0 : 1);
+    }
+
+    public static int hashCode(long value) {
+        return (int) (value ^ (value >>> 32));
+    }
+
+    public static long max(long a, long b) {
+        return Math.max(a, b);
+    }
+
+    public static long min(long a, long b) {
+        return Math.min(a, b);
+    }
+
+    public static long sum(long a, long b) {
+        return a + b;
+    }
+
+    public int intValue() {
+        return (int) value;
+    }
+
+    public long longValue() {
+        return value;
+    }
+
+    public float floatValue() {
+        return value;
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public int compareTo(Long anotherLong) {
+        return compare(value, anotherLong.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Long && value == ((Long) obj).value;
+    }
+
+    public int hashCode() {
+        return hashCode(value);
+    }
+
+    public String toString() {
+        return toString(value);
+    }
+}
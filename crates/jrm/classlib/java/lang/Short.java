@@ -0,0 +1,80 @@
+package java.lang;
+
+public final class Short extends Number implements Comparable<Short> {
+    public static final short MIN_VALUE = (short) 0x8000;
+    public static final short MAX_VALUE = 0x7fff;
+    public static final int SIZE = 16;
+
+    private static final Short[] CACHE = new Short[256];
+
+    static {
+        for (int i = 0; i < CACHE.length; i++) {
+            CACHE[i] = new Short((short) (i - 128));
+        }
+    }
+
+    private final short value;
+
+    public Short(short value) {
+        this.value = value;
+    }
+
+    public static Short valueOf(short value) {
+        if (value >= -128 && value <= 127) {
+            return CACHE[value + 128];
+        }
+        return new Short(value);
+    }
+
+    public static short parseShort(String s) {
+        return (short) Long.parse(s, 10, MIN_VALUE, MAX_VALUE);
+    }
+
+    public static String toString(short value) {
+        return Integer.toString(value);
+    }
+
+    public static int compare(short x, short y) {
+        return x - y;
+    }
+
+    public static int hashCode(short value) {
+        return value;
+    }
+
+    public int intValue() {
+        return value;
+    }
+
+    public long longValue() {
+        return value;
+    }
+
+    public float floatValue() {
+        return value;
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public short shortValue() {
+        return value;
+    }
+
+    public int compareTo(Short anotherShort) {
+        return compare(value, anotherShort.value);
+    }
+
+    public boolean equals(Object obj) {
+        return obj instanceof Short && value == ((Short) obj).value;
+    }
+
+    public int hashCode() {
+        return value;
+    }
+
+    public String toString() {
+        return toString(value);
+    }
+}
@@ -0,0 +1,99 @@
+package java.lang;
+
+// the VM stores the characters of a string outside the Java heap as Latin1 or UTF-16 bytes,
+// so most methods are native
+public final class String implements java.io.Serializable, Comparable<String>, CharSequence {
+    private String() {
+    }
+
+    public native int length();
+
+    public boolean isEmpty() {
+        return length() == 0;
+    }
+
+    public native char charAt(int index);
+
+    public native void getChars(int srcBegin, int srcEnd, char[] dst, int dstBegin);
+
+    public char[] toCharArray() {
+        char[] result = new char[length()];
+        getChars(0, result.length, result, 0);
+        return result;
+    }
+
+    public native boolean equals(Object anObject);
+
+    public native boolean equalsIgnoreCase(String anotherString);
+
+    public native int hashCode();
+
+    public native int compareTo(String anotherString);
+
+    public native int indexOf(int ch);
+
+    public native int indexOf(String str);
+
+    public boolean contains(CharSequence s) {
+        return indexOf(s.toString()) >= 0;
+    }
+
+    public native boolean startsWith(String prefix);
+
+    public native boolean endsWith(String suffix);
+
+    public native String substring(int beginIndex, int endIndex);
+
+    public String substring(int beginIndex) {
+        return substring(beginIndex, length());
+    }
+
+    public native String concat(String str);
+
+    public native String trim();
+
+    public native String toUpperCase();
+
+    public native String toLowerCase();
+
+    // returns the canonical instance from the VM string table, the same one ldc uses
+    public native String intern();
+
+    public String toString() {
+        return this;
+    }
+
+    public static String valueOf(Object obj) {
+        return obj == null ? "null" : obj.toString();
+    }
+
+    public static native String valueOf(char[] data, int offset, int count);
+
+    public static String valueOf(char[] data) {
+        return valueOf(data, 0, data.length);
+    }
+
+    public static String valueOf(boolean b) {
+        return b ? "true" : "false";
+    }
+
+    public static String valueOf(char c) {
+        return valueOf(new char[] { c }, 0, 1);
+    }
+
+    public static String valueOf(int i) {
+        return Integer.toString(i);
+    }
+
+    public static String valueOf(long l) {
+        return Long.toString(l);
+    }
+
+    public static String valueOf(float f) {
+        return Float.toString(f);
+    }
+
+    public static String valueOf(double d) {
+        return Double.toString(d);
+    }
+}
@@ -0,0 +1,146 @@
+package java.lang;
+
+public final class StringBuilder implements CharSequence {
+    private char[] value;
+    private int count;
+
+    public StringBuilder() {
+        value = new char[16];
+    }
+
+    public StringBuilder(int capacity) {
+        value = new char[capacity];
+    }
+
+    public StringBuilder(String str) {
+        value = new char[str.length() + 16];
+        append(str);
+    }
+
+    public int length() {
+        return count;
+    }
+
+    public char charAt(int index) {
+        checkIndex(index);
+        return value[index];
+    }
+
+    public void setCharAt(int index, char ch) {
+        checkIndex(index);
+        value[index] = ch;
+    }
+
+    public void setLength(int newLength) {
+        if (newLength < 0) {
+            throw new StringIndexOutOfBoundsException(newLength);
+        }
+        ensureCapacity(newLength);
+        for (int i = count; i < newLength; i++) {
+            value[i] = '\0';
+        }
+        count = newLength;
+    }
+
+    public StringBuilder append(String str) {
+        if (str == null) {
+            str = "null";
+        }
+        int length = str.length();
+        ensureCapacity(count + length);
+        str.getChars(0, length, value, count);
+        count += length;
+        return this;
+    }
+
+    public StringBuilder append(Object obj) {
+        return append(String.valueOf(obj));
+    }
+
+    public StringBuilder append(CharSequence s) {
+        return append(String.valueOf(s));
+    }
+
+    public StringBuilder append(char[] str) {
+        return append(String.valueOf(str));
+    }
+
+    public StringBuilder append(char c) {
+        ensureCapacity(count + 1);
+        value[count++] = c;
+        return this;
+    }
+
+    public StringBuilder append(boolean b) {
+        return append(String.valueOf(b));
+    }
+
+    public StringBuilder append(int i) {
+        return append(Integer.toString(i));
+    }
+
+    public StringBuilder append(long l) {
+        return append(Long.toString(l));
+    }
+
+    public StringBuilder append(float f) {
+        return append(Float.toString(f));
+    }
+
+    public StringBuilder append(double d) {
+        return append(Double.toString(d));
+    }
+
+    public StringBuilder insert(int offset, String str) {
+        if (offset < 0 || offset > count) {
+            throw new StringIndexOutOfBoundsException("offset " + offset + ", length " + count);
+        }
+        if (str == null) {
+            str = "null";
+        }
+        int length = str.length();
+        ensureCapacity(count + length);
+        System.arraycopy(value, offset, value, offset + length, count - offset);
+        str.getChars(0, length, value, offset);
+        count += length;
+        return this;
+    }
+
+    public StringBuilder deleteCharAt(int index) {
+        checkIndex(index);
+        System.arraycopy(value, index + 1, value, index, count - index - 1);
+        count--;
+        return this;
+    }
+
+    public StringBuilder reverse() {
+        for (int i = 0, j = count - 1; i < j; i++, j--) {
+            char c = value[i];
+            value[i] = value[j];
+            value[j] = c;
+        }
+        return this;
+    }
+
+    public String toString() {
+        return String.valueOf(value, 0, count);
+    }
+
+    private void checkIndex(int index) {
+        if (index < 0 || index >= count) {
+            throw new StringIndexOutOfBoundsException("index " + index + ",length " + count);
+        }
+    }
+
+    private void ensureCapacity(int minimumCapacity) {
+        if (minimumCapacity > value.length) {
+            int capacity = value.length * 2 + 2;
+            if (capacity < minimumCapacity) {
+                capacity = minimumCapacity;
+            }
+            char[] copy = new char[capacity];
+            System.arraycopy(value, 0, copy, 0, count);
+            value = copy;
+        }
+    }
+}
@@ -0,0 +1,25 @@
+package java.lang;
+
+import java.io.PrintStream;
+
+public final class System {
+    public static final PrintStream out = new PrintStream(1);
+    public static final PrintStream err = new PrintStream(2);
+
+    private System() {
+    }
+
+    public static native long currentTimeMillis();
+
+    public static native long nanoTime();
+
+    public static native void arraycopy(Object src, int srcPos, Object dest, int destPos, int length);
+
+    public static native int identityHashCode(Object x);
+
+    public static native void gc();
+
+    public static String lineSeparator() {
+        return "\n";
+    }
+}
@@ -0,0 +1,106 @@
+package java.lang;
+
+public class Thread implements Runnable {
+    private static int threadInitNumber;
+
+    private String name;
+    private final Runnable target;
+    private boolean daemon;
+    private long tid;
+
+    public Thread() {
+        this(null, null);
+    }
+
+    public Thread(Runnable target) {
+        this(target, null);
+    }
+
+    public Thread(String name) {
+        this(null, name);
+    }
+
+    public Thread(Runnable target, String name) {
+        this.target = target;
+        this.name = name != null ? name : "Thread-" + nextThreadNum();
+    }
+
+    private static synchronized int nextThreadNum() {
+        return threadInitNumber++;
+    }
+
+    public static native Thread currentThread();
+
+    public static native void yield();
+
+    public static native void sleep(long millis) throws InterruptedException;
+
+    public synchronized void start() {
+        if (tid != 0) {
+            throw new IllegalThreadStateException();
+        }
+        start0();
+    }
+
+    private native void start0();
+
+    public void run() {
+        if (target != null) {
+            target.run();
+        }
+    }
+
+    public final native boolean isAlive();
+
+    public final void join() throws InterruptedException {
+        join(0);
+    }
+
+    public final synchronized void join(long millis) throws InterruptedException {
+        if (millis < 0) {
+            throw new IllegalArgumentException("timeout value is negative");
+        }
+        if (millis == 0) {
+            while (isAlive()) {
+                wait(0);
+            }
+            return;
+        }
+        long deadline = System.currentTimeMillis() + millis;
+        long delay = millis;
+        while (isAlive() && delay > 0) {
+            wait(delay);
+            delay = deadline - System.currentTimeMillis();
+        }
+    }
+
+    public final String getName() {
+        return name;
+    }
+
+    public final synchronized void setName(String name) {
+        if (name == null) {
+            throw new NullPointerException("name cannot be null");
+        }
+        this.name = name;
+    }
+
+    public final boolean isDaemon() {
+        return daemon;
+    }
+
+    public final void setDaemon(boolean on) {
+        if (isAlive()) {
+            throw new IllegalThreadStateException();
+        }
+        daemon = on;
+    }
+
+    public long getId() {
+        return tid;
+    }
+
+    public String toString() {
+        return "Thread[" + name + ",5," + (isAlive() ? "main" : "") + "]";
+    }
+}
@@ -0,0 +1,97 @@
+package java.lang;
+
+public class Throwable implements java.io.Serializable {
+    // stack trace recorded by the VM, not visible to Java code
+    private transient Object backtrace;
+    private String detailMessage;
+    private StackTraceElement[] stackTrace;
+    private Throwable cause;
+
+    public Throwable() {
+        fillInStackTrace();
+    }
+
+    public Throwable(String message) {
+        fillInStackTrace();
+        detailMessage = message;
+    }
+
+    public Throwable(String message, Throwable cause) {
+        fillInStackTrace();
+        detailMessage = message;
+        this.cause = cause;
+    }
+
+    public Throwable(Throwable cause) {
+        fillInStackTrace();
+        detailMessage = (cause == null) ? null : cause.toString();
+        this.cause = cause;
+    }
+
+    public String getMessage() {
+        return detailMessage;
+    }
+
+    public String getLocalizedMessage() {
+        return getMessage();
+    }
+
+    public Throwable getCause() {
+        return cause;
+    }
+
+    public Throwable initCause(Throwable cause) {
+        if (this.cause != null) {
+            throw new IllegalStateException("Can't overwrite cause with " + cause, this);
+        }
+        if (cause == this) {
+            throw new IllegalArgumentException("Self-causation not permitted", this);
+        }
+        this.cause = cause;
+        return this;
+    }
+
+    public String toString() {
+        String message = getLocalizedMessage();
+        String name = getClass().getName();
+        return (message != null) ? (name + ": " + message) : name;
+    }
+
+    public void printStackTrace() {
+        java.io.PrintStream err = System.err;
+        err.println(this);
+        StackTraceElement[] trace = getStackTrace();
+        for (int i = 0; i < trace.length; i++) {
+            err.println("\tat " + trace[i]);
+        }
+        for (Throwable cause = this.cause; cause != null; cause = cause.cause) {
+            err.println("Caused by: " + cause);
+            StackTraceElement[] causeTrace = cause.getStackTrace();
+            for (int i = 0; i < causeTrace.length; i++) {
+                err.println("\tat " + causeTrace[i]);
+            }
+        }
+    }
+
+    public Throwable fillInStackTrace() {
+        stackTrace = null;
+        return fillInStackTrace(0);
+    }
+
+    private native Throwable fillInStackTrace(int dummy);
+
+    public StackTraceElement[] getStackTrace() {
+        if (stackTrace == null) {
+            int depth = getStackTraceDepth();
+            stackTrace = new StackTraceElement[depth];
+            for (int i = 0; i < depth; i++) {
+                stackTrace[i] = getStackTraceElement(i);
+            }
+        }
+        return stackTrace;
+    }
+
+    native int getStackTraceDepth();
+
+    native StackTraceElement getStackTraceElement(int index);
+}
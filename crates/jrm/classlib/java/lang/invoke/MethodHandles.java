@@ -0,0 +1,23 @@
+package java.lang.invoke;
+
+public class MethodHandles {
+    private MethodHandles() {
+    }
+
+    // instances are created by the VM only, for bootstrap methods
+    public static final class Lookup {
+        private final Class<?> lookupClass;
+
+        private Lookup(Class<?> lookupClass) {
+            this.lookupClass = lookupClass;
+        }
+
+        public Class<?> lookupClass() {
+            return lookupClass;
+        }
+
+        public String toString() {
+            return lookupClass.getName();
+        }
+    }
+}
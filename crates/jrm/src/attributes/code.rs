@@ -20,10 +20,11 @@ pub struct CodeAttribute {
 
 #[derive(Debug, ClassParser)]
 pub struct Exception {
-    start_pc: u16,
-    end_pc: u16,
-    handler_pc: u16,
-    catch_type: u16,
+    pub start_pc: u16,
+    pub end_pc: u16,
+    pub handler_pc: u16,
+    /// 0表示捕获所有异常(finally)
+    pub catch_type: u16,
 }
 
 #[base_attribute(suffix(count_ident = line_number_table_length, item_ty = LineNumber, rename = line_number_table))]
@@ -4,9 +4,79 @@ use crate::class_file_parser::{ClassParser, ContextIndex, ParserContext};
 use jrm_macro::{ClassParser, attribute_enum, base_attribute, impl_class_parser_for_vec};
 
 use code::*;
-attribute_enum! {SourceFile, Code, LineNumberTable, LocalVariableTable}
+attribute_enum! {SourceFile, ConstantValue, Code, LineNumberTable, LocalVariableTable, NestHost, BootstrapMethods}
 impl_class_parser_for_vec! {Attribute}
 
 #[base_attribute(single(ident = sourcefile_index, ty = u16, constant_index_check))]
 #[derive(Debug, ClassParser)]
 pub struct SourceFileAttribute {}
+
+impl SourceFileAttribute {
+    pub fn sourcefile_index(&self) -> u16 {
+        self.sourcefile_index
+    }
+}
+
+#[base_attribute(single(ident = constantvalue_index, ty = u16, constant_index_check))]
+#[derive(Debug, ClassParser)]
+pub struct ConstantValueAttribute {}
+
+impl ConstantValueAttribute {
+    pub fn constantvalue_index(&self) -> u16 {
+        self.constantvalue_index
+    }
+}
+
+#[base_attribute(single(ident = host_class_index, ty = u16, constant_index_check))]
+#[derive(Debug, ClassParser)]
+pub struct NestHostAttribute {}
+
+impl NestHostAttribute {
+    pub fn host_class_index(&self) -> u16 {
+        self.host_class_index
+    }
+}
+
+#[base_attribute(suffix(
+    count_ident = num_bootstrap_methods,
+    item_ty = BootstrapMethod,
+    rename = bootstrap_methods
+))]
+#[derive(Debug, ClassParser)]
+pub struct BootstrapMethodsAttribute {}
+
+/// `bootstrap_method_ref`为CONSTANT_MethodHandle的索引, 静态参数为可加载常量的索引
+#[derive(Debug, ClassParser)]
+pub struct BootstrapMethod {
+    pub bootstrap_method_ref: u16,
+    #[count(set)]
+    pub num_bootstrap_arguments: u16,
+    #[count(get)]
+    pub bootstrap_arguments: Vec<u16>,
+}
+
+/// 未识别的属性, 内容按照attribute_length跳过
+#[derive(Debug)]
+pub struct UnknownAttribute {
+    pub attribute_name_index: u16,
+    pub attribute_length: u32,
+}
+
+impl ClassParser for UnknownAttribute {
+    fn parse(ctx: &mut ParserContext) -> anyhow::Result<Self> {
+        // 属性名的索引已经由Attribute读取
+        let attribute_name_index = *ctx.enum_entry.downcast_ref::<u16>().unwrap();
+        let attribute_length = <u32 as ClassParser>::parse(ctx)?;
+        if ctx
+            .class_reader
+            .read_bytes(attribute_length as usize)
+            .is_none()
+        {
+            anyhow::bail!("attribute #{} is truncated", attribute_name_index);
+        }
+        Ok(Self {
+            attribute_name_index,
+            attribute_length,
+        })
+    }
+}
@@ -17,21 +17,24 @@ use crate::{
 
 pub trait ContextIndex {
     type Idx;
-    fn get(&self, index: Self::Idx) -> String;
+    fn get(&self, index: Self::Idx) -> anyhow::Result<String>;
 }
 
 /// TODO 同129行
 impl<T: ContextIndex> ContextIndex for Arc<T> {
     type Idx = T::Idx;
-    fn get(&self, index: Self::Idx) -> String {
+    fn get(&self, index: Self::Idx) -> anyhow::Result<String> {
         self.deref().get(index)
     }
 }
 
 impl ContextIndex for HashMap<u8, &'static str> {
     type Idx = u8;
-    fn get(&self, index: Self::Idx) -> String {
-        self[&index].to_string()
+    fn get(&self, index: Self::Idx) -> anyhow::Result<String> {
+        match HashMap::get(self, &index) {
+            Some(name) => Ok(name.to_string()),
+            None => anyhow::bail!("unknown index {}", index),
+        }
     }
 }
 pub struct ParserContext {
@@ -60,7 +63,7 @@ impl ParserContext {
             12 => "NameAndType",
             15 => "MethodHandle",
             16 => "MethodType",
-            17 => "DynamicType",
+            17 => "Dynamic",
             18 => "InvokeDynamic",
             19 => "Module",
             20 => "Package",
@@ -1,6 +1,7 @@
-use std::{fmt::Debug, hint::unreachable_unchecked, ops::Deref, sync::Arc};
+use std::{fmt::Debug, ops::Deref, sync::Arc};
 
 use crate::class_file_parser::{ClassParser, ContextIndex, ParserContext};
+use crate::modified_utf8;
 use anyhow::bail;
 use jrm_macro::{ClassParser, constant, constant_enum, define_constants};
 
@@ -25,7 +26,7 @@ impl Debug for ConstantPool {
 
 impl ContextIndex for ConstantPool {
     type Idx = u16;
-    fn get(&self, index: Self::Idx) -> String {
+    fn get(&self, index: Self::Idx) -> anyhow::Result<String> {
         self.get_utf8_string(index)
     }
 }
@@ -41,11 +42,19 @@ impl TryFrom<&Constant> for String {
 }
 
 impl ConstantPool {
-    pub fn get_utf8_string(&self, index: u16) -> String {
-        if let Constant::Utf8(utf8) = self.0[index as usize].clone() {
-            return String::from(utf8);
+    /// 索引来自类文件, 可能越界或不是CONSTANT_Utf8
+    pub fn get_utf8_string(&self, index: u16) -> anyhow::Result<String> {
+        match self.0.get(index as usize) {
+            Some(Constant::Utf8(utf8)) => Ok(String::from(utf8.clone())),
+            _ => bail!("constant #{} is not a valid Utf8", index),
         }
-        unsafe { unreachable_unchecked() }
+    }
+    /// 索引来自类文件, 可能越界或不是CONSTANT_Class
+    pub fn get_class_name(&self, index: u16) -> anyhow::Result<String> {
+        let Some(Constant::Class(class)) = self.0.get(index as usize) else {
+            bail!("constant #{} is not a valid Class", index);
+        };
+        self.get_utf8_string(class.name_index)
     }
 }
 
@@ -178,7 +187,7 @@ impl From<String> for ConstantUtf8 {
 
 impl From<ConstantUtf8> for String {
     fn from(value: ConstantUtf8) -> Self {
-        unsafe { String::from_utf8_unchecked(value.bytes) }
+        modified_utf8::to_string_lossy(&value.bytes)
     }
 }
 
@@ -207,13 +216,31 @@ mod tests {
     fn test_constant_pool_index() {
         let constant_pool = test_constant_pool();
         let i = 0_u16;
-        let utf8 = ContextIndex::get(&constant_pool, 1_u16);
+        let utf8 = ContextIndex::get(&constant_pool, 1_u16).unwrap();
         assert_eq!(utf8, "aaaa");
     }
     #[test]
     fn test_constant_pool_get_utf8_string() {
         let constant_pool = test_constant_pool();
-        let utf8_string = constant_pool.get_utf8_string(1);
+        let utf8_string = constant_pool.get_utf8_string(1).unwrap();
         assert_eq!(utf8_string, "aaaa");
+        // 不是CONSTANT_Utf8或越界
+        let err = constant_pool.get_utf8_string(0).unwrap_err();
+        assert_eq!(err.to_string(), "constant #0 is not a valid Utf8");
+        assert!(constant_pool.get_utf8_string(100).is_err());
+    }
+    #[test]
+    fn test_constant_pool_get_class_name() {
+        let mut constant_pool = test_constant_pool();
+        // name_index指向不存在的常量
+        assert!(constant_pool.get_class_name(0).is_err());
+        // 不是CONSTANT_Class或越界
+        assert!(constant_pool.get_class_name(1).is_err());
+        assert!(constant_pool.get_class_name(100).is_err());
+        constant_pool.0.push(Constant::Class(ConstantClass {
+            tag: 7,
+            name_index: 1,
+        }));
+        assert_eq!(constant_pool.get_class_name(2).unwrap(), "aaaa");
     }
 }
@@ -38,7 +38,7 @@ bitflags! {
 }
 
 bitflags! {
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
     pub struct MethodAccessFlags: u16 {
         const PUBLIC        = 0x0001;
         const PRIVATE       = 0x0002;
@@ -85,7 +85,7 @@ pub struct InstanceKlass {
     access_flags: ClassAccessFlags,
     #[constant_index(check)]
     this_class: u16,
-    #[constant_index(check)]
+    // java/lang/Object的super_class为0, 不能用constant_index(check), 由类加载器校验
     super_class: u16,
     #[count(set)]
     interfaces_count: u16,
@@ -105,74 +105,136 @@ pub struct InstanceKlass {
     attributes: Vec<Attribute>,
 }
 impl InstanceKlass {
-    pub fn find_method(&self, name: &str, descriptor: &str) -> FrameMethod {
-        let mut max_locals = None;
-        let mut max_stack = None;
-        let mut code = None;
-        let mut is_static = false;
-        unsafe {
-            self.methods
-                .iter()
-                .find(|method| {
-                    let method_name = self.constant_pool.get_utf8_string(method.name_index);
-                    let method_descriptor =
-                        self.constant_pool.get_utf8_string(method.descriptor_index);
-                    method_name == name || method_descriptor == descriptor
-                })
-                .map(|method| {
-                    is_static = method.access_flags.contains(MethodAccessFlags::STATIC);
-                    &method.attributes
-                })
-                .unwrap_unchecked()
-                .iter()
-                .for_each(|attr| {
-                    if let Attribute::Code(code_attr) = attr {
-                        max_locals = Some(code_attr.max_locals);
-                        max_stack = Some(code_attr.max_stack);
-                        code = Some(&code_attr.code);
-                    }
-                });
-
-            FrameMethod {
-                name: name.to_string(),
-                descriptor: descriptor.to_string(),
-                max_locals: max_locals.unwrap_unchecked(),
-                max_stack: max_stack.unwrap_unchecked(),
-                code: code.unwrap_unchecked().clone(),
-                is_static,
+    /// 按名称和描述符查找方法. native和abstract方法没有Code属性, 它们的code为空
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Option<FrameMethod> {
+        let method = self.methods.iter().find(|method| {
+            self.constant_pool
+                .get_utf8_string(method.name_index)
+                .is_ok_and(|method_name| method_name == name)
+                && self
+                    .constant_pool
+                    .get_utf8_string(method.descriptor_index)
+                    .is_ok_and(|method_descriptor| method_descriptor == descriptor)
+        })?;
+        let mut result = FrameMethod {
+            name: name.to_string(),
+            descriptor: descriptor.to_string(),
+            is_static: method.access_flags.contains(MethodAccessFlags::STATIC),
+            access_flags: method.access_flags,
+            ..Default::default()
+        };
+        for attr in &method.attributes {
+            if let Attribute::Code(code_attr) = attr {
+                result.max_locals = code_attr.max_locals;
+                result.max_stack = code_attr.max_stack;
+                result.code = code_attr.code.clone();
             }
         }
+        Some(result)
+    }
+    pub fn minor_version(&self) -> u16 {
+        self.minor_version
+    }
+    pub fn major_version(&self) -> u16 {
+        self.major_version
     }
     pub fn get_constant_pool(&self) -> Arc<ConstantPool> {
         self.constant_pool.clone()
     }
+    pub fn access_flags(&self) -> ClassAccessFlags {
+        self.access_flags
+    }
+    pub fn this_class_name(&self) -> anyhow::Result<String> {
+        self.constant_pool.get_class_name(self.this_class)
+    }
+    pub fn super_class_name(&self) -> anyhow::Result<Option<String>> {
+        if self.super_class == 0 {
+            return Ok(None);
+        }
+        self.constant_pool
+            .get_class_name(self.super_class)
+            .map(Some)
+    }
+    pub fn interface_names(&self) -> anyhow::Result<Vec<String>> {
+        // interfaces中的name_index实际是CONSTANT_Class的索引
+        self.interfaces
+            .iter()
+            .map(|interface| self.constant_pool.get_class_name(interface.name_index))
+            .collect()
+    }
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+    pub fn methods(&self) -> &[Method] {
+        &self.methods
+    }
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
 }
 type Interface = ConstantClass;
 #[derive(Debug, ClassParser)]
 pub struct Field {
-    access_flags: FieldAccessFlags,
-    name_index: u16,
-    descriptor_index: u16,
+    pub access_flags: FieldAccessFlags,
+    pub name_index: u16,
+    pub descriptor_index: u16,
     #[count(set)]
-    attributes_count: u16,
+    pub attributes_count: u16,
     #[count(impled)]
-    attributes: Vec<Attribute>,
+    pub attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, ClassParser)]
 pub struct Method {
-    access_flags: MethodAccessFlags,
-    name_index: u16,
-    descriptor_index: u16,
+    pub access_flags: MethodAccessFlags,
+    pub name_index: u16,
+    pub descriptor_index: u16,
     #[count(set)]
-    attributes_count: u16,
+    pub attributes_count: u16,
     #[count(impled)]
-    attributes: Vec<Attribute>,
+    pub attributes: Vec<Attribute>,
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{instance_klass::ClassAccessFlags, test_context::TestContext};
+    use crate::{
+        attributes::Attribute, instance_klass::ClassAccessFlags, test_context::TestContext,
+    };
+
+    #[test]
+    fn test_code_attribute() {
+        let instance_klass = TestContext::parse_class_file("Simple1Impl.class");
+        // Simple1Impl的方法都有且只有一个Code属性
+        for method in &instance_klass.methods {
+            let codes = method
+                .attributes
+                .iter()
+                .filter(|attr| matches!(attr, Attribute::Code(_)))
+                .count();
+            assert_eq!(codes, 1);
+        }
+        let method = instance_klass
+            .find_method("main", "([Ljava/lang/String;)V")
+            .unwrap();
+        assert_eq!((method.max_stack, method.max_locals), (2, 1));
+    }
+
+    #[test]
+    fn test_unknown_attribute() {
+        // Threads的InnerClasses不在识别的属性中, 按attribute_length跳过
+        let instance_klass = TestContext::parse_class_file("Threads.class");
+        let attributes = instance_klass.attributes();
+        assert!(
+            attributes
+                .iter()
+                .any(|attr| matches!(attr, Attribute::Unknown(attr) if attr.attribute_length > 0))
+        );
+        assert!(
+            attributes
+                .iter()
+                .any(|attr| matches!(attr, Attribute::SourceFile(_)))
+        );
+    }
 
     #[test]
     fn test_class_access_flag() {
@@ -187,8 +249,26 @@ mod tests {
     #[test]
     fn test_find_method() {
         let instance_klass = TestContext::parse_class_file("Simple1Impl.class");
-        let method = instance_klass.find_method("main", "([Ljava/lang/String)V");
+        let method = instance_klass
+            .find_method("main", "([Ljava/lang/String;)V")
+            .unwrap();
         println!("method {} is: {:?}", method.name, method);
         assert!(method.is_static);
+        assert!(!method.code.is_empty());
+        // 名称和描述符都要匹配
+        assert!(
+            instance_klass
+                .find_method("main", "([Ljava/lang/String)V")
+                .is_none()
+        );
+        assert!(instance_klass.find_method("missing", "()V").is_none());
+    }
+
+    #[test]
+    fn test_find_native_method() {
+        let instance_klass = TestContext::parse_class_file("Natives.class");
+        let method = instance_klass.find_method("add", "(II)I").unwrap();
+        assert!(method.is_native());
+        assert!(method.code.is_empty());
     }
 }
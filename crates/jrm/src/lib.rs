@@ -0,0 +1,10 @@
+mod attributes;
+pub mod class_file_parser;
+pub mod class_reader;
+mod constant_pool;
+pub mod instance_klass;
+mod modified_utf8;
+pub mod runtime;
+#[cfg(test)]
+mod test_context;
+mod util;
@@ -1,41 +1,95 @@
-mod attributes;
-mod class_file_parser;
-mod class_reader;
-mod constant_pool;
-mod instance_klass;
-mod runtime;
-mod test_context;
-mod util;
-
 use std::{
     fs,
     io::{self, Read},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
 };
 
 use bpaf::{Bpaf, Parser};
-
-use crate::{
+use jrm::{
     class_file_parser::{ClassParser, ParserContext},
     class_reader::ClassReader,
     instance_klass::InstanceKlass,
+    runtime::{ClassLoader, ClassPath, GcOptions, Vm},
 };
 
 #[derive(Bpaf)]
-/// 解析*.class文件并生成ast
+/// 执行*.class文件的main方法, 没有JDK时使用内建的类库
 struct Args {
-    /// 标准输入和文件输入
+    /// 以`:`分隔的类路径, 执行类名而不是文件时使用, 默认为当前目录
+    #[bpaf(long("classpath"), short('c'), argument("PATH"))]
+    class_path: Option<String>,
+    /// 虚拟机选项, 如-Xmx64m, -Xlog:gc和-XX:+UseGenerationalGC
+    #[bpaf(short('X'), argument("OPTION"))]
+    x_options: Vec<String>,
+    /// 只解析类文件并打印ast, 不指定文件时读取标准输入
+    #[bpaf(long("dump"), switch)]
+    dump: bool,
+    /// 类文件或者类名
     #[bpaf(positional("FILE"), optional)]
     file: Option<String>,
+    /// 传给main方法的参数
+    #[bpaf(positional("ARG"), many)]
+    args: Vec<String>,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
     let args = args().run();
-    let file = resolve_stdin(&args.file);
-    let class_reader = ClassReader::from(file);
+    let mut gc_options = GcOptions::default();
+    for option in &args.x_options {
+        gc_options.parse_x_option(option)?;
+    }
+    let file = match args.file {
+        Some(file) if !args.dump => file,
+        file => {
+            let file = resolve_stdin(&file);
+            let class_reader = ClassReader::from(file);
+            let mut parse_ctx = ParserContext::new(class_reader);
+            let klass = <InstanceKlass as ClassParser>::parse(&mut parse_ctx)?;
+            println!("{:?}", klass);
+            return Ok(ExitCode::SUCCESS);
+        }
+    };
+    let (mut class_path, class_name) = match file.strip_suffix(".class") {
+        Some(_) => {
+            let path = Path::new(&file);
+            let class_name = read_class_name(path)?;
+            let root = class_path_root(path, &class_name);
+            (ClassPath::from(root.to_string_lossy().as_ref()), class_name)
+        }
+        None => (
+            ClassPath::from(args.class_path.as_deref().unwrap_or(".")),
+            file.replace('.', "/"),
+        ),
+    };
+    class_path.ensure_class_library();
+    let vm = Arc::new(Vm::with_options(ClassLoader::new(class_path), gc_options));
+    match vm.run_main(&class_name, &args.args) {
+        Ok(()) => Ok(ExitCode::SUCCESS),
+        // 启动失败和未捕获的异常都已经输出到标准错误
+        Err(_) => Ok(ExitCode::FAILURE),
+    }
+}
+
+/// 类文件中的二进制名, 如`com/example/Main`
+fn read_class_name(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let class_reader = ClassReader::from(fs::read(path)?);
     let mut parse_ctx = ParserContext::new(class_reader);
     let klass = <InstanceKlass as ClassParser>::parse(&mut parse_ctx)?;
-    println!("{:?}", klass);
-    Ok(())
+    Ok(klass.this_class_name()?)
+}
+
+/// 类文件所在的目录按包名逐级向上, 得到类路径的根目录
+fn class_path_root(path: &Path, class_name: &str) -> PathBuf {
+    let mut root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+    for _ in class_name.matches('/') {
+        root.pop();
+    }
+    if root.as_os_str().is_empty() {
+        root.push(".");
+    }
+    root
 }
 
 fn resolve_stdin(file: &Option<String>) -> String {
@@ -51,9 +105,21 @@ fn resolve_stdin(file: &Option<String>) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_context::TestContext;
-    #[test]
-    fn test_code_attribute() {
-        let _ = TestContext::parse_class_file("Simple1Impl.class");
+    use std::path::Path;
+
+    use rstest::rstest;
+
+    use crate::class_path_root;
+
+    #[rstest]
+    #[case("Simple1Impl.class", "Simple1Impl", ".")]
+    #[case("out/Simple1Impl.class", "Simple1Impl", "out")]
+    #[case("out/com/example/Main.class", "com/example/Main", "out")]
+    #[case("/tmp/com/Main.class", "com/Main", "/tmp")]
+    fn test_class_path_root(#[case] path: &str, #[case] class_name: &str, #[case] expected: &str) {
+        assert_eq!(
+            class_path_root(Path::new(path), class_name),
+            Path::new(expected)
+        );
     }
 }
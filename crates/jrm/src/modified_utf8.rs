@@ -0,0 +1,98 @@
+/// 解码class文件中CONSTANT_Utf8使用的modified UTF-8, 格式错误时返回None.
+/// 与标准UTF-8不同, `\0`编码为`0xC0 0x80`, 增补字符按UTF-16代理对分别编码为三个字节
+pub fn decode(bytes: &[u8]) -> Option<Vec<u16>> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+    let continuation = |byte: Option<&u8>| match byte {
+        Some(byte) if byte & 0xc0 == 0x80 => Some((byte & 0x3f) as u16),
+        _ => None,
+    };
+    while let Some(&byte) = iter.next() {
+        let unit = match byte {
+            0x01..=0x7f => byte as u16,
+            0xc0..=0xdf => ((byte & 0x1f) as u16) << 6 | continuation(iter.next())?,
+            0xe0..=0xef => {
+                let high = ((byte & 0x0f) as u16) << 12 | continuation(iter.next())? << 6;
+                high | continuation(iter.next())?
+            }
+            // 0x00, 单独的后续字节和四字节形式都不合法
+            _ => return None,
+        };
+        units.push(unit);
+    }
+    Some(units)
+}
+
+/// 编码UTF-16代码单元, 单独的代理项也原样编码
+pub fn encode(units: impl IntoIterator<Item = u16>) -> Vec<u8> {
+    let mut bytes = vec![];
+    for unit in units {
+        match unit {
+            0x0001..=0x007f => bytes.push(unit as u8),
+            0x0000 | 0x0080..=0x07ff => {
+                bytes.push(0xc0 | (unit >> 6) as u8);
+                bytes.push(0x80 | (unit & 0x3f) as u8);
+            }
+            _ => {
+                bytes.push(0xe0 | (unit >> 12) as u8);
+                bytes.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+                bytes.push(0x80 | (unit & 0x3f) as u8);
+            }
+        }
+    }
+    bytes
+}
+
+/// 转换为Rust字符串, 不能组成字符的代理项和格式错误的字节替换为U+FFFD
+pub fn to_string_lossy(bytes: &[u8]) -> String {
+    // 不含\0和增补字符时与UTF-8相同
+    if let Ok(string) = std::str::from_utf8(bytes)
+        && !string.contains(|ch: char| ch == '\0' || ch > '\u{ffff}')
+    {
+        return string.to_string();
+    }
+    match decode(bytes) {
+        Some(units) => String::from_utf16_lossy(&units),
+        None => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::modified_utf8::{decode, encode, to_string_lossy};
+
+    #[rstest]
+    #[case("hello", b"hello")]
+    #[case("\0", &[0xc0, 0x80])]
+    #[case("é", &[0xc3, 0xa9])]
+    #[case("中", &[0xe4, 0xb8, 0xad])]
+    #[case("\u{1F600}", &[0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80])]
+    fn test_round_trip(#[case] string: &str, #[case] bytes: &[u8]) {
+        assert_eq!(encode(string.encode_utf16()), bytes);
+        assert_eq!(
+            decode(bytes).unwrap(),
+            string.encode_utf16().collect::<Vec<_>>()
+        );
+        assert_eq!(to_string_lossy(bytes), string);
+    }
+
+    #[test]
+    fn test_lone_surrogate() {
+        let bytes = encode([0x61, 0xd800]);
+        assert_eq!(bytes, [0x61, 0xed, 0xa0, 0x80]);
+        assert_eq!(decode(&bytes).unwrap(), [0x61, 0xd800]);
+        assert_eq!(to_string_lossy(&bytes), "a\u{fffd}");
+    }
+
+    #[rstest]
+    #[case(&[0x00])]
+    #[case(&[0x80])]
+    #[case(&[0xc3])]
+    #[case(&[0xe4, 0xb8])]
+    #[case(&[0xf0, 0x9f, 0x98, 0x80])]
+    fn test_malformed(#[case] bytes: &[u8]) {
+        assert!(decode(bytes).is_none());
+    }
+}
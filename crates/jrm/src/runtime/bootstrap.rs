@@ -0,0 +1,315 @@
+use std::sync::Arc;
+
+use crate::{
+    attributes::BootstrapMethod,
+    constant_pool::{Constant, ConstantDynamic},
+    runtime::{
+        Klass, Method, RuntimeConstantPool, RuntimeError, Vm,
+        descriptor::FieldType,
+        heap::instance_bytes,
+        runtime_constant_pool::{MethodRef, Resolved},
+        slot::{ObjectRef, Slot},
+        string::JavaString,
+        thread::Thread,
+    },
+};
+
+/// MethodHandle的引用类型REF_invokeStatic
+const REF_INVOKE_STATIC: u8 = 6;
+
+/// 引导方法的前三个参数依次为`MethodHandles.Lookup`, 常量名和常量类型
+const FIXED_ARGUMENTS: usize = 3;
+
+/// JVMS 5.4.3.6, 执行引导方法解析`constant_pool`中的动态常量, 结果转换为常量的字段类型
+pub fn resolve_dynamic(
+    thread: &mut Thread,
+    constant_pool: &RuntimeConstantPool,
+    index: u16,
+) -> Result<Slot, RuntimeError> {
+    constant_pool.resolve_dynamic(index, |dynamic| bootstrap(thread, constant_pool, dynamic))
+}
+
+fn bootstrap(
+    thread: &mut Thread,
+    constant_pool: &RuntimeConstantPool,
+    dynamic: &ConstantDynamic,
+) -> Result<Slot, RuntimeError> {
+    let vm = thread.vm().clone();
+    let caller = vm.class_loader().load_class(constant_pool.class_name())?;
+    let bootstrap_method = caller
+        .bootstrap_method(dynamic.bootstrap_method_attr_index)
+        .ok_or_else(|| {
+            RuntimeError::ClassFormatError(format!(
+                "{}: bootstrap method #{} not found",
+                caller.name(),
+                dynamic.bootstrap_method_attr_index
+            ))
+        })?;
+    let (name, descriptor) = constant_pool.name_and_type(dynamic.name_and_type_index)?;
+    let field_type = FieldType::parse(&descriptor)?;
+    let method_ref = resolve_bootstrap_method(constant_pool, bootstrap_method, &vm)?;
+    thread.initialize_class(&method_ref.klass)?;
+
+    // 参数在调用之前只由Rust持有, 分配其他参数可能触发GC, 用全局引用保持
+    let mut pinned = vec![];
+    let result = bootstrap_args(thread, &caller, &name, &field_type, &mut pinned)
+        .and_then(|mut args| {
+            let descriptor = method_ref.method.parsed_descriptor()?;
+            let parameters = &descriptor.parameters[FIXED_ARGUMENTS..];
+            for (index, parameter) in bootstrap_method.bootstrap_arguments.iter().zip(parameters) {
+                let (value, value_type) = static_arg(thread, constant_pool, *index)?;
+                let arg = convert(thread, value, &value_type, parameter)?;
+                if let Slot::Ref(Some(object_ref)) = arg {
+                    pinned.push(vm.heap().new_global_ref(object_ref));
+                }
+                args.push(arg);
+            }
+            let value = thread.call(method_ref.method.clone(), args)?;
+            let (Some(value), Some(return_type)) = (value, &descriptor.return_type) else {
+                return Err(RuntimeError::IllegalState);
+            };
+            convert(thread, value, return_type, &field_type)
+        })
+        .map_err(wrap_exception);
+    for handle in pinned {
+        vm.heap().delete_global_ref(handle);
+    }
+    result
+}
+
+/// 引导方法只支持REF_invokeStatic, 需要有返回值, 参数个数与静态参数对应
+fn resolve_bootstrap_method(
+    constant_pool: &RuntimeConstantPool,
+    bootstrap_method: &BootstrapMethod,
+    vm: &Vm,
+) -> Result<MethodRef, RuntimeError> {
+    let index = bootstrap_method.bootstrap_method_ref;
+    let method_ref = match constant_pool.resolve(index, vm.class_loader())? {
+        Resolved::MethodHandle(method_handle)
+            if method_handle.reference_kind == REF_INVOKE_STATIC =>
+        {
+            match *method_handle.member {
+                Resolved::Method(method_ref) => method_ref,
+                _ => return Err(RuntimeError::IllegalState),
+            }
+        }
+        _ => {
+            return Err(RuntimeError::BootstrapMethodError(format!(
+                "{}: bootstrap method #{} is not a static method handle",
+                constant_pool.class_name(),
+                index
+            )));
+        }
+    };
+    let descriptor = method_ref.method.parsed_descriptor()?;
+    let arguments = FIXED_ARGUMENTS + bootstrap_method.num_bootstrap_arguments as usize;
+    if descriptor.parameters.len() != arguments || descriptor.return_type.is_none() {
+        return Err(RuntimeError::BootstrapMethodError(format!(
+            "bootstrap method {}.{}{} cannot be invoked with {} arguments",
+            method_ref.klass.name(),
+            method_ref.method.name,
+            method_ref.method.descriptor,
+            arguments
+        )));
+    }
+    Ok(method_ref)
+}
+
+/// 引导方法固定的前三个参数: Lookup, 常量名和常量类型.
+/// 虚拟机没有基本类型的Class对象, 基本类型的常量类型参数为null
+fn bootstrap_args(
+    thread: &mut Thread,
+    caller: &Arc<Klass>,
+    name: &str,
+    field_type: &FieldType,
+    pinned: &mut Vec<usize>,
+) -> Result<Vec<Slot>, RuntimeError> {
+    let vm = thread.vm().clone();
+    let heap = vm.heap();
+    let lookup = new_lookup(thread, caller)?;
+    pinned.push(heap.new_global_ref(lookup));
+    let name = vm.intern(JavaString::from(name), || thread.roots())?;
+    let mirror = type_class(thread, field_type)?.map(|class| class.mirror(heap));
+    Ok(vec![
+        Slot::Ref(Some(lookup)),
+        Slot::Ref(Some(name)),
+        Slot::Ref(mirror),
+    ])
+}
+
+/// 以`caller`为查找类的`MethodHandles.Lookup`
+fn new_lookup(thread: &mut Thread, caller: &Arc<Klass>) -> Result<ObjectRef, RuntimeError> {
+    let vm = thread.vm().clone();
+    let klass = vm
+        .class_loader()
+        .load_class("java/lang/invoke/MethodHandles$Lookup")?;
+    thread.initialize_class(&klass)?;
+    let (_, field) = klass
+        .lookup_field("lookupClass", "Ljava/lang/Class;")
+        .ok_or_else(|| {
+            RuntimeError::NoSuchFieldError(
+                "java/lang/invoke/MethodHandles$Lookup.lookupClass".into(),
+            )
+        })?;
+    thread.reserve(instance_bytes(&klass))?;
+    let lookup = vm.heap().alloc_instance(klass);
+    let mirror = caller.mirror(vm.heap());
+    vm.heap()
+        .put_field(Some(lookup), &field, Slot::Ref(Some(mirror)))?;
+    Ok(lookup)
+}
+
+/// 静态参数的值和类型, 动态常量可以作为静态参数
+fn static_arg(
+    thread: &mut Thread,
+    constant_pool: &RuntimeConstantPool,
+    index: u16,
+) -> Result<(Slot, FieldType), RuntimeError> {
+    let arg = match constant_pool.get(index)? {
+        Constant::Integer(integer) => (Slot::Bits32(integer.bytes), FieldType::Int),
+        Constant::Float(float) => (Slot::Bits32(float.bytes), FieldType::Float),
+        Constant::Long(long) => (
+            Slot::Bits64(((long.high_bytes as u64) << 32) | long.low_bytes as u64),
+            FieldType::Long,
+        ),
+        Constant::Double(double) => (
+            Slot::Bits64(((double.high_bytes as u64) << 32) | double.low_bytes as u64),
+            FieldType::Double,
+        ),
+        Constant::Dynamic(dynamic) => {
+            let (_, descriptor) = constant_pool.name_and_type(dynamic.name_and_type_index)?;
+            let value = resolve_dynamic(thread, constant_pool, index)?;
+            (value, FieldType::parse(&descriptor)?)
+        }
+        _ => {
+            let object_ref = constant_pool.resolve_object(index, thread.vm(), || thread.roots())?;
+            (
+                Slot::Ref(Some(object_ref)),
+                FieldType::Object("java/lang/Object".to_string()),
+            )
+        }
+    };
+    Ok(arg)
+}
+
+/// 与`MethodHandle.asType`相同, 基本类型和引用类型之间装箱或拆箱, 引用类型检查能否转换
+fn convert(
+    thread: &mut Thread,
+    value: Slot,
+    from: &FieldType,
+    to: &FieldType,
+) -> Result<Slot, RuntimeError> {
+    match (from.is_reference(), to.is_reference()) {
+        (false, false) if from == to => Ok(value),
+        (false, false) => Err(RuntimeError::ClassCastException(format!(
+            "cannot convert {} to {}",
+            from, to
+        ))),
+        (false, true) => {
+            let boxed = box_value(thread, value, from)?;
+            check_cast(thread, boxed, to)
+        }
+        (true, false) => unbox_value(thread, value.into(), to),
+        (true, true) => check_cast(thread, value, to),
+    }
+}
+
+/// 基本类型的包装类和拆箱方法
+fn wrapper(field_type: &FieldType) -> Option<(&'static str, &'static str)> {
+    let wrapper = match field_type {
+        FieldType::Boolean => ("java/lang/Boolean", "booleanValue"),
+        FieldType::Byte => ("java/lang/Byte", "byteValue"),
+        FieldType::Char => ("java/lang/Character", "charValue"),
+        FieldType::Short => ("java/lang/Short", "shortValue"),
+        FieldType::Int => ("java/lang/Integer", "intValue"),
+        FieldType::Long => ("java/lang/Long", "longValue"),
+        FieldType::Float => ("java/lang/Float", "floatValue"),
+        FieldType::Double => ("java/lang/Double", "doubleValue"),
+        FieldType::Object(_) | FieldType::Array(_) => return None,
+    };
+    Some(wrapper)
+}
+
+/// 调用包装类的`valueOf`装箱
+fn box_value(thread: &mut Thread, value: Slot, from: &FieldType) -> Result<Slot, RuntimeError> {
+    let (class_name, _) = wrapper(from).ok_or(RuntimeError::IllegalState)?;
+    let descriptor = format!("({})L{};", from, class_name);
+    let (class, method) = wrapper_method(thread, class_name, "valueOf", &descriptor)?;
+    thread.initialize_class(&class)?;
+    thread
+        .call(method, vec![value])?
+        .ok_or(RuntimeError::IllegalState)
+}
+
+/// 调用包装类的`xxxValue`拆箱, null抛出NullPointerException
+fn unbox_value(
+    thread: &mut Thread,
+    value: Option<ObjectRef>,
+    to: &FieldType,
+) -> Result<Slot, RuntimeError> {
+    let (class_name, method_name) = wrapper(to).ok_or(RuntimeError::IllegalState)?;
+    let object_ref = value.ok_or_else(|| {
+        RuntimeError::NullPointerException(format!("cannot unbox null value to {}", to))
+    })?;
+    let (class, method) = wrapper_method(thread, class_name, method_name, &format!("(){}", to))?;
+    check_cast(
+        thread,
+        Slot::Ref(Some(object_ref)),
+        &FieldType::Object(class.name().to_string()),
+    )?;
+    thread
+        .call(method, vec![Slot::Ref(Some(object_ref))])?
+        .ok_or(RuntimeError::IllegalState)
+}
+
+fn wrapper_method(
+    thread: &Thread,
+    class_name: &str,
+    name: &str,
+    descriptor: &str,
+) -> Result<(Arc<Klass>, Arc<Method>), RuntimeError> {
+    let class = thread.vm().class_loader().load_class(class_name)?;
+    let method = class.find_method(name, descriptor).ok_or_else(|| {
+        RuntimeError::NoSuchMethodError(format!("{}.{}{}", class_name, name, descriptor))
+    })?;
+    Ok((class, method))
+}
+
+/// null可以转换为任何引用类型
+fn check_cast(thread: &Thread, value: Slot, to: &FieldType) -> Result<Slot, RuntimeError> {
+    let Slot::Ref(Some(object_ref)) = value else {
+        return Ok(value);
+    };
+    let class = type_class(thread, to)?.ok_or(RuntimeError::IllegalState)?;
+    let object_class = thread.object_class(object_ref)?;
+    if !object_class.is_subclass_of(&class) {
+        return Err(RuntimeError::ClassCastException(format!(
+            "class {} cannot be cast to class {}",
+            object_class.name().replace('/', "."),
+            class.name().replace('/', ".")
+        )));
+    }
+    Ok(value)
+}
+
+/// 引用类型对应的类, 数组类以描述符为类名
+fn type_class(thread: &Thread, field_type: &FieldType) -> Result<Option<Arc<Klass>>, RuntimeError> {
+    let class_loader = thread.vm().class_loader();
+    match field_type {
+        FieldType::Object(name) => class_loader.load_class(name).map(Some),
+        FieldType::Array(_) => class_loader.load_class(&field_type.to_string()).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// 引导方法和结果转换抛出的异常不是Error时包装为BootstrapMethodError
+fn wrap_exception(err: RuntimeError) -> RuntimeError {
+    match err {
+        RuntimeError::IllegalState | RuntimeError::Parked | RuntimeError::Deadlock => err,
+        err if err.is_error() => err,
+        err => RuntimeError::BootstrapMethodError(format!(
+            "bootstrap method initialization exception: {}",
+            err
+        )),
+    }
+}
@@ -0,0 +1,532 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    thread::{self, ThreadId},
+};
+
+use rust_embed::RustEmbed;
+
+use crate::{
+    class_file_parser::{ClassParser, ParserContext},
+    class_reader::ClassReader,
+    instance_klass::InstanceKlass,
+    runtime::{RuntimeError, klass::Klass},
+};
+
+/// classpath中的一项, 按二进制名(`java/lang/Object`)读取类文件
+pub trait ClassPathEntry: Send + Sync {
+    fn read_class(&self, name: &str) -> Option<Vec<u8>>;
+}
+
+pub struct DirEntry(PathBuf);
+
+impl DirEntry {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self(path.into())
+    }
+}
+
+impl ClassPathEntry for DirEntry {
+    fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+        fs::read(self.0.join(format!("{}.class", name))).ok()
+    }
+}
+
+/// 编译进虚拟机的最小类库, 没有JDK时提供`java.lang`和`java.io.PrintStream`
+#[derive(RustEmbed)]
+#[folder = "classlib/"]
+#[exclude("*.java")]
+pub struct BootClassPath;
+
+impl ClassPathEntry for BootClassPath {
+    fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+        let file = Self::get(&format!("{}.class", name))?;
+        Some(file.data.into_owned())
+    }
+}
+
+#[derive(Default)]
+pub struct ClassPath {
+    entries: Vec<Box<dyn ClassPathEntry>>,
+}
+
+impl ClassPath {
+    pub fn push<E: ClassPathEntry + 'static>(&mut self, entry: E) {
+        self.entries.push(Box::new(entry));
+    }
+    pub fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+        self.entries.iter().find_map(|entry| entry.read_class(name))
+    }
+    /// classpath中找不到`java/lang/Object`时追加内建的类库
+    pub fn ensure_class_library(&mut self) {
+        if self.read_class("java/lang/Object").is_none() {
+            self.push(BootClassPath);
+        }
+    }
+}
+
+/// 以`:`分隔的目录列表
+impl From<&str> for ClassPath {
+    fn from(value: &str) -> Self {
+        let mut class_path = ClassPath::default();
+        value
+            .split(':')
+            .filter(|path| !path.is_empty())
+            .for_each(|path| class_path.push(DirEntry::new(path)));
+        class_path
+    }
+}
+
+/// 支持的class文件主版本号, 45为JDK 1.0.2, 65为Java 21
+const MIN_MAJOR_VERSION: u16 = 45;
+const MAX_MAJOR_VERSION: u16 = 65;
+
+fn check_class_version(name: &str, instance_klass: &InstanceKlass) -> Result<(), RuntimeError> {
+    let major = instance_klass.major_version();
+    let minor = instance_klass.minor_version();
+    if major > MAX_MAJOR_VERSION {
+        return Err(RuntimeError::UnsupportedClassVersionError(format!(
+            "{} has been compiled by a more recent version of the Java Runtime (class file version {}.{}), \
+             this version of the Java Runtime only recognizes class file versions up to {}.0",
+            name.replace('/', "."),
+            major,
+            minor,
+            MAX_MAJOR_VERSION
+        )));
+    }
+    if major < MIN_MAJOR_VERSION {
+        return Err(RuntimeError::UnsupportedClassVersionError(format!(
+            "{} (Unsupported major.minor version {}.{})",
+            name, major, minor
+        )));
+    }
+    Ok(())
+}
+
+/// 已加载类的缓存, 以二进制名为键
+#[derive(Default)]
+pub struct MethodArea {
+    classes: RwLock<HashMap<String, Arc<Klass>>>,
+}
+
+impl MethodArea {
+    pub fn get(&self, name: &str) -> Option<Arc<Klass>> {
+        self.classes.read().unwrap().get(name).cloned()
+    }
+    pub fn classes(&self) -> Vec<Arc<Klass>> {
+        self.classes.read().unwrap().values().cloned().collect()
+    }
+    pub fn class_count(&self) -> usize {
+        self.classes.read().unwrap().len()
+    }
+    fn insert(&self, klass: Arc<Klass>) -> Arc<Klass> {
+        self.classes
+            .write()
+            .unwrap()
+            .entry(klass.name().to_string())
+            .or_insert(klass)
+            .clone()
+    }
+}
+
+pub struct ClassLoader {
+    class_path: ClassPath,
+    method_area: MethodArea,
+    // 每个线程正在加载的类, 用于检测继承环. 加载过程不会挂起, 按系统线程区分
+    loading: Mutex<HashMap<ThreadId, Vec<String>>>,
+}
+
+impl ClassLoader {
+    pub fn new(class_path: ClassPath) -> Self {
+        Self {
+            class_path,
+            method_area: Default::default(),
+            loading: Default::default(),
+        }
+    }
+    pub fn method_area(&self) -> &MethodArea {
+        &self.method_area
+    }
+    pub fn load_class(&self, name: &str) -> Result<Arc<Klass>, RuntimeError> {
+        let name = name.replace('.', "/");
+        if let Some(klass) = self.method_area.get(&name) {
+            return Ok(klass);
+        }
+        let thread_id = thread::current().id();
+        {
+            let mut loading = self.loading.lock().unwrap();
+            let loading = loading.entry(thread_id).or_default();
+            if loading.contains(&name) {
+                return Err(RuntimeError::ClassCircularityError(name));
+            }
+            loading.push(name.clone());
+        }
+        let result = if name.starts_with('[') {
+            self.define_array_class(&name)
+        } else {
+            self.define_class(&name)
+        };
+        // 其他线程同时加载同一个类时, 方法区中保留先定义的
+        let mut loading = self.loading.lock().unwrap();
+        if let Some(names) = loading.get_mut(&thread_id) {
+            names.retain(|loading| loading != &name);
+            if names.is_empty() {
+                loading.remove(&thread_id);
+            }
+        }
+        result
+    }
+    fn define_class(&self, name: &str) -> Result<Arc<Klass>, RuntimeError> {
+        let bytes = self
+            .class_path
+            .read_class(name)
+            .ok_or_else(|| RuntimeError::NoClassDefFoundError(name.to_string()))?;
+        let mut parse_ctx = ParserContext::new(ClassReader::from(bytes));
+        let instance_klass = <InstanceKlass as ClassParser>::parse(&mut parse_ctx)
+            .map_err(|err| RuntimeError::ClassFormatError(format!("{}: {}", name, err)))?;
+        check_class_version(name, &instance_klass)?;
+        let class_format_error =
+            |err: anyhow::Error| RuntimeError::ClassFormatError(format!("{}: {}", name, err));
+        let this_class_name = instance_klass
+            .this_class_name()
+            .map_err(class_format_error)?;
+        if this_class_name != name {
+            return Err(RuntimeError::NoClassDefFoundError(format!(
+                "{} (wrong name: {})",
+                name, this_class_name
+            )));
+        }
+
+        // super_class可以为0, 不在解析时检查, 在这里校验它指向CONSTANT_Class
+        let super_class = match instance_klass
+            .super_class_name()
+            .map_err(class_format_error)?
+        {
+            Some(super_name) => {
+                let super_class = self.load_class(&super_name)?;
+                if super_class.is_interface() {
+                    return Err(RuntimeError::IncompatibleClassChangeError(format!(
+                        "class {} has interface {} as super class",
+                        name, super_name
+                    )));
+                }
+                Some(super_class)
+            }
+            None if name == "java/lang/Object" => None,
+            None => {
+                return Err(RuntimeError::ClassFormatError(format!(
+                    "{}: invalid superclass index 0",
+                    name
+                )));
+            }
+        };
+        let mut interfaces = vec![];
+        for interface_name in instance_klass
+            .interface_names()
+            .map_err(class_format_error)?
+        {
+            let interface = self.load_class(&interface_name)?;
+            if !interface.is_interface() {
+                return Err(RuntimeError::IncompatibleClassChangeError(format!(
+                    "class {} can not implement {}, because it is not an interface",
+                    name, interface_name
+                )));
+            }
+            interfaces.push(interface);
+        }
+
+        let klass = Arc::new(Klass::new(instance_klass, super_class, interfaces)?);
+        klass.link();
+        Ok(self.method_area.insert(klass))
+    }
+    /// 数组类不从classpath读取, 元素为引用类型时先加载元素类
+    fn define_array_class(&self, name: &str) -> Result<Arc<Klass>, RuntimeError> {
+        let component_class =
+            match &name[1..] {
+                component if component.starts_with('[') => Some(self.load_class(component)?),
+                component => match component.strip_prefix('L') {
+                    Some(class_name) => {
+                        Some(self.load_class(class_name.strip_suffix(';').ok_or_else(|| {
+                            RuntimeError::NoClassDefFoundError(name.to_string())
+                        })?)?)
+                    }
+                    None => None,
+                },
+            };
+        let object = self.load_class("java/lang/Object")?;
+        let interfaces = vec![
+            self.load_class("java/lang/Cloneable")?,
+            self.load_class("java/io/Serializable")?,
+        ];
+        let klass = Klass::new_array(name.to_string(), component_class, object, interfaces)
+            .map_err(|_| RuntimeError::NoClassDefFoundError(name.to_string()))?;
+        let klass = Arc::new(klass);
+        klass.link();
+        Ok(self.method_area.insert(klass))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use rstest::{fixture, rstest};
+
+    use crate::{
+        instance_klass::ClassAccessFlags,
+        runtime::{
+            Klass, RuntimeError,
+            class_loader::{ClassLoader, ClassPath, ClassPathEntry},
+            descriptor::FieldType,
+        },
+        test_context::TestContext,
+    };
+
+    #[fixture]
+    fn class_loader() -> ClassLoader {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        ClassLoader::new(class_path)
+    }
+
+    #[rstest]
+    fn test_load_class(class_loader: ClassLoader) {
+        let klass = class_loader.load_class("Simple1Impl").unwrap();
+        assert_eq!(klass.name(), "Simple1Impl");
+        assert_eq!(klass.super_class().unwrap().name(), "java/lang/Object");
+        assert_eq!(klass.interfaces()[0].name(), "java/lang/Runnable");
+        assert!(klass.interfaces()[0].is_interface());
+        assert!(
+            klass
+                .find_method("main", "([Ljava/lang/String;)V")
+                .is_some()
+        );
+    }
+
+    #[rstest]
+    fn test_load_class_cached(class_loader: ClassLoader) {
+        let first = class_loader.load_class("Square").unwrap();
+        let second = class_loader.load_class("Square").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        let base = class_loader.load_class("Base").unwrap();
+        assert!(Arc::ptr_eq(first.super_class().unwrap(), &base));
+        // Square, Base, Shape, Named, java/lang/Object
+        assert_eq!(class_loader.method_area().class_count(), 5);
+    }
+
+    #[rstest]
+    fn test_is_subclass_of(class_loader: ClassLoader) {
+        let square = class_loader.load_class("Square").unwrap();
+        let shape = class_loader.load_class("Shape").unwrap();
+        let named = class_loader.load_class("Named").unwrap();
+        let object = class_loader.load_class("java.lang.Object").unwrap();
+        assert!(square.is_subclass_of(&shape));
+        assert!(square.is_subclass_of(&named));
+        assert!(square.is_subclass_of(&object));
+        assert!(!shape.is_subclass_of(&square));
+    }
+
+    #[rstest]
+    fn test_class_not_found(class_loader: ClassLoader) {
+        let err = class_loader.load_class("NotExist").unwrap_err();
+        assert!(matches!(err, RuntimeError::NoClassDefFoundError(name) if name == "NotExist"));
+    }
+
+    #[rstest]
+    fn test_load_array_class(class_loader: ClassLoader) {
+        let klass = class_loader.load_class("[[Ljava/lang/String;").unwrap();
+        assert!(klass.is_array());
+        assert_eq!(klass.super_class().unwrap().name(), "java/lang/Object");
+        let interfaces: Vec<_> = klass.interfaces().iter().map(|i| i.name()).collect();
+        assert_eq!(interfaces, ["java/lang/Cloneable", "java/io/Serializable"]);
+        assert!(klass.access_flags().contains(
+            ClassAccessFlags::PUBLIC | ClassAccessFlags::FINAL | ClassAccessFlags::ABSTRACT
+        ));
+        let component = klass.component_class().unwrap();
+        assert_eq!(component.name(), "[Ljava/lang/String;");
+        assert_eq!(
+            component.component_class().unwrap().name(),
+            "java/lang/String"
+        );
+        assert!(Arc::ptr_eq(
+            component,
+            &class_loader.load_class("[Ljava/lang/String;").unwrap()
+        ));
+
+        let ints = class_loader.load_class("[I").unwrap();
+        assert_eq!(ints.component_type(), Some(&FieldType::Int));
+        assert!(ints.component_class().is_none());
+        let objects = class_loader.load_class("[Ljava/lang/Object;").unwrap();
+        assert!(klass.is_subclass_of(&objects));
+        assert!(!ints.is_subclass_of(&objects));
+
+        let err = class_loader.load_class("[LNotExist;").unwrap_err();
+        assert!(matches!(err, RuntimeError::NoClassDefFoundError(name) if name == "NotExist"));
+    }
+
+    impl ClassPathEntry for HashMap<String, Vec<u8>> {
+        fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+            self.get(name).cloned()
+        }
+    }
+
+    #[test]
+    fn test_ensure_class_library() {
+        let mut class_path = ClassPath::default();
+        class_path.push(HashMap::from([("Main".to_string(), vec![])]));
+        assert!(class_path.read_class("java/lang/Object").is_none());
+        class_path.ensure_class_library();
+        assert!(class_path.read_class("java/lang/String").is_some());
+        assert!(class_path.read_class("java/io/PrintStream").is_some());
+
+        // 已经有java/lang/Object时不追加
+        let object = class_path.read_class("java/lang/Object").unwrap();
+        let mut class_path = ClassPath::default();
+        class_path.push(HashMap::from([("java/lang/Object".to_string(), object)]));
+        class_path.ensure_class_library();
+        assert!(class_path.read_class("java/lang/String").is_none());
+    }
+
+    #[test]
+    fn test_class_circularity() {
+        // 把CycleA的父类从CycleC改为CycleB, 构造 CycleA -> CycleB -> CycleA
+        let mut bytes = TestContext::read_class(&TestContext, "CycleA").unwrap();
+        let pos = bytes
+            .windows(6)
+            .position(|window| window == b"CycleC")
+            .unwrap();
+        bytes[pos..pos + 6].copy_from_slice(b"CycleB");
+        let mut class_path = ClassPath::default();
+        class_path.push(HashMap::from([("CycleA".to_string(), bytes)]));
+        class_path.push(TestContext);
+        let class_loader = ClassLoader::new(class_path);
+
+        let err = class_loader.load_class("CycleB").unwrap_err();
+        assert!(matches!(err, RuntimeError::ClassCircularityError(name) if name == "CycleB"));
+        assert!(class_loader.method_area().get("CycleA").is_none());
+    }
+
+    /// 读取类文件时停顿, 让多个线程同时处于加载过程中
+    struct SlowEntry;
+
+    impl ClassPathEntry for SlowEntry {
+        fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            TestContext.read_class(name)
+        }
+    }
+
+    #[test]
+    fn test_concurrent_load() {
+        let mut class_path = ClassPath::default();
+        class_path.push(SlowEntry);
+        let class_loader = ClassLoader::new(class_path);
+        let classes: Vec<Arc<Klass>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| class_loader.load_class("Simple1Impl").unwrap()))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        let klass = class_loader.method_area().get("Simple1Impl").unwrap();
+        // 同时定义的类中只保留一个
+        assert!(classes.iter().all(|class| Arc::ptr_eq(class, &klass)));
+        assert!(class_loader.loading.lock().unwrap().is_empty());
+    }
+
+    /// 跳过常量池, 返回super_class在类文件中的偏移
+    fn super_class_offset(bytes: &[u8]) -> usize {
+        let count = u16::from_be_bytes([bytes[8], bytes[9]]);
+        let (mut pos, mut index) = (10, 1);
+        while index < count {
+            let tag = bytes[pos];
+            pos += 1 + match tag {
+                1 => 2 + u16::from_be_bytes([bytes[pos + 1], bytes[pos + 2]]) as usize,
+                7 | 8 | 16 | 19 | 20 => 2,
+                15 => 3,
+                5 | 6 => 8,
+                _ => 4,
+            };
+            index += if matches!(tag, 5 | 6) { 2 } else { 1 };
+        }
+        // access_flags, this_class
+        pos + 4
+    }
+
+    /// 跳过接口表, 返回第一个字段的name_index在类文件中的偏移
+    fn first_field_offset(bytes: &[u8]) -> usize {
+        let pos = super_class_offset(bytes) + 2;
+        let interfaces_count = u16::from_be_bytes([bytes[pos], bytes[pos + 1]]) as usize;
+        // interfaces, fields_count, access_flags
+        pos + 2 + interfaces_count * 2 + 2 + 2
+    }
+
+    #[rstest]
+    // #1是Methodref, Fields的第一个字段是CONST
+    #[case::name_not_utf8(0, 1, "Fields: constant #1 is not a valid Utf8")]
+    #[case::descriptor_out_of_range(2, u16::MAX, "Fields: constant #65535 is not a valid Utf8")]
+    #[case::invalid_descriptor(2, 0, "Fields: invalid descriptor \"CONST\" of field CONST")]
+    fn test_invalid_field(#[case] position: usize, #[case] index: u16, #[case] expected: &str) {
+        let mut bytes = TestContext::read_class(&TestContext, "Fields").unwrap();
+        let offset = first_field_offset(&bytes);
+        // 为0时用字段名作为描述符
+        let index = match index {
+            0 => u16::from_be_bytes([bytes[offset], bytes[offset + 1]]),
+            index => index,
+        };
+        bytes[offset + position..offset + position + 2].copy_from_slice(&index.to_be_bytes());
+        let mut class_path = ClassPath::default();
+        class_path.push(HashMap::from([("Fields".to_string(), bytes)]));
+        class_path.push(TestContext);
+        let class_loader = ClassLoader::new(class_path);
+
+        let err = class_loader.load_class("Fields").unwrap_err();
+        assert!(
+            matches!(err, RuntimeError::ClassFormatError(ref msg) if msg == expected),
+            "{}",
+            err
+        );
+    }
+
+    #[rstest]
+    // javac生成的#1是父类构造方法的Methodref
+    #[case::not_class(1)]
+    #[case::out_of_range(u16::MAX)]
+    fn test_invalid_super_class(#[case] index: u16) {
+        let mut bytes = TestContext::read_class(&TestContext, "Simple1Impl").unwrap();
+        let offset = super_class_offset(&bytes);
+        bytes[offset..offset + 2].copy_from_slice(&index.to_be_bytes());
+        let mut class_path = ClassPath::default();
+        class_path.push(HashMap::from([("Simple1Impl".to_string(), bytes)]));
+        let class_loader = ClassLoader::new(class_path);
+
+        let err = class_loader.load_class("Simple1Impl").unwrap_err();
+        assert!(
+            matches!(err, RuntimeError::ClassFormatError(msg) if msg == format!("Simple1Impl: constant #{} is not a valid Class", index))
+        );
+    }
+
+    #[rstest]
+    #[case::too_new(
+        66,
+        "Simple1Impl has been compiled by a more recent version of the Java Runtime (class file version 66.0), this version of the Java Runtime only recognizes class file versions up to 65.0"
+    )]
+    #[case::too_old(44, "Simple1Impl (Unsupported major.minor version 44.0)")]
+    fn test_unsupported_class_version(#[case] major: u16, #[case] expected: &str) {
+        // magic之后依次为minor_version和major_version
+        let mut bytes = TestContext::read_class(&TestContext, "Simple1Impl").unwrap();
+        bytes[4..8].copy_from_slice(&[0, 0, (major >> 8) as u8, major as u8]);
+        let mut class_path = ClassPath::default();
+        class_path.push(HashMap::from([("Simple1Impl".to_string(), bytes)]));
+        class_path.push(TestContext);
+        let class_loader = ClassLoader::new(class_path);
+
+        let err = class_loader.load_class("Simple1Impl").unwrap_err();
+        assert!(matches!(err, RuntimeError::UnsupportedClassVersionError(msg) if msg == expected));
+        assert!(class_loader.method_area().get("Simple1Impl").is_none());
+    }
+}
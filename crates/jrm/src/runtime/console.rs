@@ -0,0 +1,36 @@
+use std::{
+    io::{self, Write},
+    sync::Mutex,
+};
+
+type Sink = Mutex<Box<dyn Write + Send>>;
+
+/// `System.out`和`System.err`写入的目标, 默认为进程的标准输出和标准错误
+pub struct Console {
+    out: Sink,
+    err: Sink,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self::new(io::stdout(), io::stderr())
+    }
+}
+
+impl Console {
+    pub fn new(out: impl Write + Send + 'static, err: impl Write + Send + 'static) -> Self {
+        Self {
+            out: Mutex::new(Box::new(out)),
+            err: Mutex::new(Box::new(err)),
+        }
+    }
+    /// 按文件描述符写入并刷新, 1为标准输出, 其他为标准错误
+    pub fn write(&self, fd: i32, bytes: &[u8]) -> io::Result<()> {
+        let mut sink = match fd {
+            1 => self.out.lock().unwrap(),
+            _ => self.err.lock().unwrap(),
+        };
+        sink.write_all(bytes)?;
+        sink.flush()
+    }
+}
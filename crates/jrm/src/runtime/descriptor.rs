@@ -0,0 +1,169 @@
+use std::{fmt::Display, iter::Peekable, str::Chars};
+
+use crate::runtime::RuntimeError;
+
+/// 字段描述符, 也用于方法描述符的参数和返回值
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+    Object(String),
+    Array(Box<FieldType>),
+}
+
+impl FieldType {
+    pub fn parse(descriptor: &str) -> Result<Self, RuntimeError> {
+        let mut chars = descriptor.chars().peekable();
+        let field_type = Self::parse_next(&mut chars, descriptor)?;
+        if chars.next().is_some() {
+            return Err(invalid_descriptor(descriptor));
+        }
+        Ok(field_type)
+    }
+    fn parse_next(chars: &mut Peekable<Chars>, descriptor: &str) -> Result<Self, RuntimeError> {
+        let field_type = match chars.next() {
+            Some('B') => Self::Byte,
+            Some('C') => Self::Char,
+            Some('D') => Self::Double,
+            Some('F') => Self::Float,
+            Some('I') => Self::Int,
+            Some('J') => Self::Long,
+            Some('S') => Self::Short,
+            Some('Z') => Self::Boolean,
+            Some('L') => {
+                let name: String = chars.by_ref().take_while(|ch| *ch != ';').collect();
+                if name.is_empty() {
+                    return Err(invalid_descriptor(descriptor));
+                }
+                Self::Object(name)
+            }
+            Some('[') => Self::Array(Box::new(Self::parse_next(chars, descriptor)?)),
+            _ => return Err(invalid_descriptor(descriptor)),
+        };
+        Ok(field_type)
+    }
+    /// long和double为2, 其余为1
+    pub fn category(&self) -> usize {
+        match self {
+            Self::Long | Self::Double => 2,
+            _ => 1,
+        }
+    }
+    pub fn is_reference(&self) -> bool {
+        matches!(self, Self::Object(_) | Self::Array(_))
+    }
+    /// 需要解析的类名, 数组取其元素类型
+    pub fn class_name(&self) -> Option<&str> {
+        match self {
+            Self::Object(name) => Some(name),
+            Self::Array(component) => component.class_name(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Byte => write!(f, "B"),
+            Self::Char => write!(f, "C"),
+            Self::Double => write!(f, "D"),
+            Self::Float => write!(f, "F"),
+            Self::Int => write!(f, "I"),
+            Self::Long => write!(f, "J"),
+            Self::Short => write!(f, "S"),
+            Self::Boolean => write!(f, "Z"),
+            Self::Object(name) => write!(f, "L{};", name),
+            Self::Array(component) => write!(f, "[{}", component),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodDescriptor {
+    pub parameters: Vec<FieldType>,
+    /// `None`表示`V`
+    pub return_type: Option<FieldType>,
+}
+
+impl MethodDescriptor {
+    pub fn parse(descriptor: &str) -> Result<Self, RuntimeError> {
+        let mut chars = descriptor.chars().peekable();
+        if chars.next() != Some('(') {
+            return Err(invalid_descriptor(descriptor));
+        }
+        let mut parameters = vec![];
+        while chars.peek().is_some_and(|ch| *ch != ')') {
+            parameters.push(FieldType::parse_next(&mut chars, descriptor)?);
+        }
+        if chars.next() != Some(')') {
+            return Err(invalid_descriptor(descriptor));
+        }
+        let return_type = if chars.peek() == Some(&'V') {
+            chars.next();
+            None
+        } else {
+            Some(FieldType::parse_next(&mut chars, descriptor)?)
+        };
+        if chars.next().is_some() {
+            return Err(invalid_descriptor(descriptor));
+        }
+        Ok(Self {
+            parameters,
+            return_type,
+        })
+    }
+    /// 参数在局部变量表中占用的槽数, 不含this
+    pub fn parameter_slots(&self) -> usize {
+        self.parameters.iter().map(FieldType::category).sum()
+    }
+}
+
+fn invalid_descriptor(descriptor: &str) -> RuntimeError {
+    RuntimeError::ClassFormatError(format!("invalid descriptor: {}", descriptor))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::descriptor::{FieldType, MethodDescriptor};
+
+    #[test]
+    fn test_field_type_parse() {
+        assert_eq!(FieldType::parse("I").unwrap(), FieldType::Int);
+        assert_eq!(
+            FieldType::parse("[[Ljava/lang/String;").unwrap(),
+            FieldType::Array(Box::new(FieldType::Array(Box::new(FieldType::Object(
+                "java/lang/String".to_string()
+            )))))
+        );
+        assert_eq!(
+            FieldType::parse("[[Ljava/lang/String;")
+                .unwrap()
+                .to_string(),
+            "[[Ljava/lang/String;"
+        );
+        assert!(FieldType::parse("II").is_err());
+        assert!(FieldType::parse("L;").is_err());
+    }
+
+    #[test]
+    fn test_method_descriptor_parse() {
+        let descriptor = MethodDescriptor::parse("(IJ[Ljava/lang/Object;D)V").unwrap();
+        assert_eq!(descriptor.parameters.len(), 4);
+        assert_eq!(descriptor.parameter_slots(), 6);
+        assert_eq!(descriptor.return_type, None);
+        let descriptor = MethodDescriptor::parse("()Ljava/lang/String;").unwrap();
+        assert!(descriptor.parameters.is_empty());
+        assert_eq!(
+            descriptor.return_type.unwrap().class_name(),
+            Some("java/lang/String")
+        );
+        assert!(MethodDescriptor::parse("(I").is_err());
+    }
+}
@@ -0,0 +1,477 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, OnceLock},
+};
+
+use crate::{
+    instance_klass::MethodAccessFlags,
+    runtime::{Klass, Method, RuntimeError, runtime_constant_pool::MethodRef},
+};
+
+/// JVMS 5.4.5, `klass`中声明的`method`是否覆盖`overridden_klass`中声明的`overridden`
+pub fn overrides(
+    klass: &Arc<Klass>,
+    method: &Method,
+    overridden_klass: &Arc<Klass>,
+    overridden: &Method,
+) -> bool {
+    if method.name != overridden.name
+        || method.descriptor != overridden.descriptor
+        || method.is_private()
+    {
+        return false;
+    }
+    if overridden
+        .access_flags
+        .intersects(MethodAccessFlags::PUBLIC | MethodAccessFlags::PROTECTED)
+        || klass.package_name() == overridden_klass.package_name()
+    {
+        return true;
+    }
+    // 包私有方法只能经由中间类中同包的方法间接覆盖
+    let mut current = klass.super_class();
+    while let Some(middle) = current {
+        if Arc::ptr_eq(middle, overridden_klass) {
+            break;
+        }
+        if let Some(middle_method) = middle.find_method(&method.name, &method.descriptor)
+            && overrides(klass, method, middle, &middle_method)
+            && overrides(middle, &middle_method, overridden_klass, overridden)
+        {
+            return true;
+        }
+        current = middle.super_class();
+    }
+    false
+}
+
+/// JVMS 5.4.6, invokevirtual和invokeinterface按接收者的类选择方法
+pub fn select_method(
+    receiver: &Arc<Klass>,
+    method_ref: &MethodRef,
+) -> Result<Arc<Method>, RuntimeError> {
+    let resolved = &method_ref.method;
+    if resolved.is_private() {
+        return Ok(resolved.clone());
+    }
+    let mut current = Some(receiver);
+    while let Some(klass) = current {
+        if let Some(method) = klass.find_method(&resolved.name, &resolved.descriptor)
+            && !method.is_static
+            && overrides(klass, &method, &method_ref.klass, resolved)
+        {
+            return check_not_abstract(method);
+        }
+        current = klass.super_class();
+    }
+    select_maximally_specific(receiver, resolved)
+}
+
+/// 用vtable和itable实现的`select_method`, 结果与之相同
+pub fn select_virtual(
+    receiver: &Arc<Klass>,
+    method_ref: &MethodRef,
+) -> Result<Arc<Method>, RuntimeError> {
+    let resolved = &method_ref.method;
+    if resolved.is_private() {
+        return Ok(resolved.clone());
+    }
+    match (resolved.vtable_index, resolved.itable_index) {
+        (Some(index), _) => check_not_abstract(receiver.vtable()[index].clone()),
+        (None, Some(index)) => receiver.itable_method(&method_ref.klass, index),
+        (None, None) => select_method(receiver, method_ref),
+    }
+}
+
+/// 单态内联缓存, 绑定调用点第一次遇到的接收者类; 其他接收者类走vtable和itable
+#[derive(Default)]
+pub struct InlineCache {
+    entry: OnceLock<(Arc<Klass>, Arc<Method>)>,
+}
+
+impl Debug for InlineCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.entry.get() {
+            Some((klass, method)) => write!(f, "InlineCache({} -> {})", klass.name(), method.name),
+            None => write!(f, "InlineCache(empty)"),
+        }
+    }
+}
+
+impl InlineCache {
+    /// 接收者类与缓存相同时直接返回缓存的方法, 否则调用`select`, 缓存为空时填充缓存
+    pub fn lookup<F>(&self, receiver: &Arc<Klass>, select: F) -> Result<Arc<Method>, RuntimeError>
+    where
+        F: FnOnce() -> Result<Arc<Method>, RuntimeError>,
+    {
+        if let Some((klass, method)) = self.entry.get()
+            && Arc::ptr_eq(klass, receiver)
+        {
+            return Ok(method.clone());
+        }
+        let method = select()?;
+        let _ = self.entry.set((receiver.clone(), method.clone()));
+        Ok(method)
+    }
+    #[cfg(test)]
+    pub fn receiver(&self) -> Option<&Arc<Klass>> {
+        self.entry.get().map(|(klass, _)| klass)
+    }
+}
+
+/// 一个方法中所有invokevirtual和invokeinterface调用点的内联缓存, 按pc升序排列
+#[derive(Debug, Default)]
+pub struct InlineCaches {
+    sites: Vec<(u16, InlineCache)>,
+}
+
+impl InlineCaches {
+    pub fn new(code: &[u8]) -> Self {
+        let mut sites = vec![];
+        let mut pc = 0;
+        while pc < code.len() {
+            if matches!(code[pc], 0xb6 | 0xb9) {
+                sites.push((pc as u16, InlineCache::default()));
+            }
+            pc += instruction_length(code, pc);
+        }
+        Self { sites }
+    }
+    pub fn get(&self, pc: u16) -> Option<&InlineCache> {
+        self.sites
+            .binary_search_by_key(&pc, |(site, _)| *site)
+            .ok()
+            .map(|index| &self.sites[index].1)
+    }
+}
+
+/// `pc`处指令的字节数
+fn instruction_length(code: &[u8], pc: usize) -> usize {
+    let read_i32 = |pos: usize| {
+        code.get(pos..pos + 4)
+            .map_or(0, |bytes| i32::from_be_bytes(bytes.try_into().unwrap()))
+    };
+    match code[pc] {
+        0x10 | 0x12 | 0x15..=0x19 | 0x36..=0x3a | 0xa9 | 0xbc => 2,
+        0x11 | 0x13 | 0x14 | 0x84 | 0x99..=0xa8 | 0xb2..=0xb8 | 0xbb | 0xbd | 0xc0 | 0xc1 => 3,
+        0xc6 | 0xc7 => 3,
+        0xc5 => 4,
+        0xb9 | 0xba | 0xc8 | 0xc9 => 5,
+        0xc4 if code.get(pc + 1) == Some(&0x84) => 6,
+        0xc4 => 4,
+        // tableswitch和lookupswitch的操作数从4字节对齐的地址开始
+        0xaa => {
+            let base = pc + 1 + (3 - pc % 4);
+            let (low, high) = (read_i32(base + 4), read_i32(base + 8));
+            base - pc + 12 + (high as i64 - low as i64 + 1).max(0) as usize * 4
+        }
+        0xab => {
+            let base = pc + 1 + (3 - pc % 4);
+            base - pc + 8 + read_i32(base + 4).max(0) as usize * 8
+        }
+        _ => 1,
+    }
+}
+
+/// invokespecial的方法选择
+pub fn select_special(
+    current: &Arc<Klass>,
+    method_ref: &MethodRef,
+) -> Result<Arc<Method>, RuntimeError> {
+    let resolved = &method_ref.method;
+    let class = &method_ref.class;
+    // ACC_SUPER: 调用严格父类中的方法时从当前类的直接父类开始查找
+    let start = match current.super_class() {
+        Some(super_class)
+            if resolved.name != "<init>"
+                && !class.is_interface()
+                && !Arc::ptr_eq(current, class)
+                && current.is_subclass_of(class)
+                && current.is_super() =>
+        {
+            super_class
+        }
+        _ => class,
+    };
+    let name = &resolved.name;
+    let descriptor = &resolved.descriptor;
+    if let Some(method) = start.find_method(name, descriptor) {
+        return check_not_abstract(method);
+    }
+    if !start.is_interface() {
+        if let Some((_, method)) = start
+            .super_class()
+            .and_then(|super_class| super_class.lookup_method_in_supers(name, descriptor))
+            .filter(|(_, method)| !method.is_static)
+        {
+            return check_not_abstract(method);
+        }
+    } else if let Some(method) = start
+        .super_class()
+        .and_then(|object| object.find_method(name, descriptor))
+        .filter(|method| {
+            method.access_flags.contains(MethodAccessFlags::PUBLIC) && !method.is_static
+        })
+    {
+        return Ok(method);
+    }
+    select_maximally_specific(start, resolved)
+}
+
+/// 父接口中恰好有一个非抽象的最具体方法时选择它
+fn select_maximally_specific(
+    klass: &Arc<Klass>,
+    resolved: &Method,
+) -> Result<Arc<Method>, RuntimeError> {
+    let candidates: Vec<_> = klass
+        .maximally_specific_methods(&resolved.name, &resolved.descriptor)
+        .into_iter()
+        .filter(|(_, method)| !method.is_abstract())
+        .collect();
+    match candidates.as_slice() {
+        [(_, method)] => Ok(method.clone()),
+        [] => Err(RuntimeError::AbstractMethodError(format!(
+            "Receiver class {} does not define or inherit an implementation of the resolved method '{}{}'",
+            klass.name(),
+            resolved.name,
+            resolved.descriptor
+        ))),
+        _ => Err(RuntimeError::IncompatibleClassChangeError(format!(
+            "Conflicting default methods: {}",
+            candidates
+                .iter()
+                .map(|(interface, method)| format!("{}.{}", interface.name(), method.name))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ))),
+    }
+}
+
+fn check_not_abstract(method: Arc<Method>) -> Result<Arc<Method>, RuntimeError> {
+    if method.is_abstract() {
+        return Err(RuntimeError::AbstractMethodError(format!(
+            "{}.{}{}",
+            method.constant_pool.class_name(),
+            method.name,
+            method.descriptor
+        )));
+    }
+    Ok(method)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, sync::Arc};
+
+    use rstest::{fixture, rstest};
+
+    use crate::{
+        runtime::{
+            ClassLoader, ClassPath, Method, RuntimeError,
+            dispatch::{
+                InlineCache, InlineCaches, overrides, select_method, select_special, select_virtual,
+            },
+            runtime_constant_pool::MethodRef,
+        },
+        test_context::TestContext,
+    };
+
+    #[fixture]
+    fn class_loader() -> ClassLoader {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        ClassLoader::new(class_path)
+    }
+
+    fn method_ref(class_loader: &ClassLoader, class: &str, name: &str) -> MethodRef {
+        let class = class_loader.load_class(class).unwrap();
+        let (klass, method) = class.lookup_method_in_supers(name, "()I").unwrap();
+        MethodRef {
+            class,
+            klass,
+            method,
+        }
+    }
+
+    fn declared_in(method: &Method, class: &str) {
+        assert_eq!(method.constant_pool.class_name(), class);
+    }
+
+    #[rstest]
+    fn test_select_virtual(class_loader: ClassLoader) {
+        let method_ref = method_ref(&class_loader, "InvokeBase", "value");
+        let derived = class_loader.load_class("InvokeDerived").unwrap();
+        declared_in(
+            &select_method(&derived, &method_ref).unwrap(),
+            "InvokeDerived",
+        );
+        let base = class_loader.load_class("InvokeBase").unwrap();
+        declared_in(&select_method(&base, &method_ref).unwrap(), "InvokeBase");
+    }
+
+    #[rstest]
+    fn test_select_default(class_loader: ClassLoader) {
+        let hello = method_ref(&class_loader, "InvokeIface", "hello");
+        let implementation = class_loader.load_class("InvokeImpl").unwrap();
+        declared_in(
+            &select_method(&implementation, &hello).unwrap(),
+            "InvokeIface",
+        );
+        let conflict = class_loader.load_class("InvokeConflict").unwrap();
+        declared_in(&select_method(&conflict, &hello).unwrap(), "InvokeConflict");
+
+        let abs = method_ref(&class_loader, "InvokeIface", "abs");
+        let abstract_class = class_loader.load_class("InvokeAbstract").unwrap();
+        assert!(matches!(
+            select_method(&abstract_class, &abs),
+            Err(RuntimeError::AbstractMethodError(_))
+        ));
+    }
+
+    #[rstest]
+    fn test_select_special(class_loader: ClassLoader) {
+        // InvokeDerived.pub中的super.pub()
+        let super_ref = method_ref(&class_loader, "InvokeBase", "pub");
+        let derived = class_loader.load_class("InvokeDerived").unwrap();
+        declared_in(&select_special(&derived, &super_ref).unwrap(), "InvokeBase");
+        // InvokeConflict.hello中的InvokeOther.super.hello()
+        let default_ref = method_ref(&class_loader, "InvokeOther", "hello");
+        let conflict = class_loader.load_class("InvokeConflict").unwrap();
+        declared_in(
+            &select_special(&conflict, &default_ref).unwrap(),
+            "InvokeOther",
+        );
+    }
+
+    #[rstest]
+    #[case::same_package("dispatch/a/B", "dispatch/a/B")]
+    #[case::other_package("dispatch/b/C", "dispatch/a/A")]
+    #[case::transitive("dispatch/b/D", "dispatch/b/D")]
+    fn test_select_package_private(
+        class_loader: ClassLoader,
+        #[case] receiver: &str,
+        #[case] expected: &str,
+    ) {
+        let method_ref = method_ref(&class_loader, "dispatch/a/A", "m");
+        let receiver = class_loader.load_class(receiver).unwrap();
+        declared_in(&select_method(&receiver, &method_ref).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::same_package("dispatch/a/B", true)]
+    #[case::other_package("dispatch/b/C", false)]
+    #[case::transitive("dispatch/b/D", true)]
+    fn test_overrides(class_loader: ClassLoader, #[case] class: &str, #[case] expected: bool) {
+        let a = class_loader.load_class("dispatch/a/A").unwrap();
+        let klass = class_loader.load_class(class).unwrap();
+        let overridden = a.find_method("m", "()I").unwrap();
+        let method = klass.find_method("m", "()I").unwrap();
+        assert_eq!(overrides(&klass, &method, &a, &overridden), expected);
+    }
+
+    #[rstest]
+    fn test_select_virtual_agrees(class_loader: ClassLoader) {
+        let method_refs = [
+            method_ref(&class_loader, "InvokeBase", "value"),
+            method_ref(&class_loader, "InvokeBase", "pub"),
+            method_ref(&class_loader, "InvokeIface", "hello"),
+            method_ref(&class_loader, "InvokeIface", "abs"),
+            method_ref(&class_loader, "dispatch/a/A", "m"),
+            method_ref(&class_loader, "PolyBase", "value"),
+            method_ref(&class_loader, "PolyBase", "base"),
+            method_ref(&class_loader, "PolyShape", "sides"),
+        ];
+        let receivers = [
+            "InvokeBase",
+            "InvokeDerived",
+            "InvokeAbstract",
+            "InvokeImpl",
+            "InvokeConflict",
+            "dispatch/a/A",
+            "dispatch/a/B",
+            "dispatch/b/C",
+            "dispatch/b/D",
+            "PolyBase",
+            "PolyA",
+            "PolyB",
+            "PolyC",
+            "PolyD",
+        ];
+        for receiver in receivers {
+            let receiver = class_loader.load_class(receiver).unwrap();
+            for method_ref in method_refs
+                .iter()
+                .filter(|method_ref| receiver.is_subclass_of(&method_ref.class))
+            {
+                match (
+                    select_method(&receiver, method_ref),
+                    select_virtual(&receiver, method_ref),
+                ) {
+                    (Ok(expected), Ok(actual)) => assert!(Arc::ptr_eq(&expected, &actual)),
+                    (Err(expected), Err(actual)) => assert_eq!(
+                        std::mem::discriminant(&expected),
+                        std::mem::discriminant(&actual)
+                    ),
+                    (expected, actual) => panic!(
+                        "{:?}.{}: {:?} != {:?}",
+                        receiver, method_ref.method.name, expected, actual
+                    ),
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    fn test_inline_cache(class_loader: ClassLoader) {
+        let method_ref = method_ref(&class_loader, "PolyBase", "value");
+        let a = class_loader.load_class("PolyA").unwrap();
+        let b = class_loader.load_class("PolyB").unwrap();
+        let cache = InlineCache::default();
+        let misses = Cell::new(0);
+        let lookup = |receiver| {
+            cache
+                .lookup(receiver, || {
+                    misses.set(misses.get() + 1);
+                    select_virtual(receiver, &method_ref)
+                })
+                .unwrap()
+        };
+        declared_in(&lookup(&a), "PolyA");
+        declared_in(&lookup(&a), "PolyA");
+        assert_eq!(misses.get(), 1);
+        // 缓存保持绑定到第一次的接收者类
+        declared_in(&lookup(&b), "PolyB");
+        declared_in(&lookup(&b), "PolyB");
+        assert_eq!(misses.get(), 3);
+        assert!(Arc::ptr_eq(cache.receiver().unwrap(), &a));
+        declared_in(&lookup(&a), "PolyA");
+        assert_eq!(misses.get(), 3);
+    }
+
+    #[rstest]
+    fn test_inline_cache_sites(class_loader: ClassLoader) {
+        let poly = class_loader.load_class("Poly").unwrap();
+        let method = poly
+            .find_method("virtualLoop", "(LPolyBase;LPolyBase;LPolyBase;I)I")
+            .unwrap();
+        assert!(method.inline_caches.get(42).is_some());
+        assert!(method.inline_caches.get(47).is_some());
+        assert!(method.inline_caches.get(45).is_none());
+
+        // tableswitch, lookupswitch和wide之后的调用点
+        let mut code = vec![0x00, 0xaa, 0x00, 0x00];
+        // default 0, low 0, high 1, 两个跳转偏移
+        code.extend([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        code.extend([0; 8]);
+        code.extend([0xab, 0x00, 0x00, 0x00]);
+        // default 0, npairs 1, 一个match-offset对
+        code.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+        code.extend([0; 8]);
+        code.extend([0xc4, 0x84, 0x00, 0x01, 0x00, 0x01]);
+        code.extend([0xb9, 0x00, 0x01, 0x01, 0x00, 0xb6, 0x00, 0x01]);
+        let caches = InlineCaches::new(&code);
+        assert!(caches.get(50).is_some());
+        assert!(caches.get(55).is_some());
+        assert_eq!(caches.sites.len(), 2);
+    }
+}
@@ -1,4 +1,14 @@
-use crate::runtime::slot::Slot;
+use std::sync::{Arc, OnceLock};
+
+use crate::{
+    instance_klass::MethodAccessFlags,
+    runtime::{
+        RuntimeConstantPool, RuntimeError,
+        descriptor::MethodDescriptor,
+        dispatch::InlineCaches,
+        slot::{ObjectRef, Slot},
+    },
+};
 
 pub struct OperandStack {
     stack: Vec<Slot>,
@@ -28,25 +38,62 @@ impl OperandStack {
             max_size,
         }
     }
+    /// 查看栈顶之下第`depth`个值, 0为栈顶
+    pub fn peek(&self, depth: usize) -> &Slot {
+        debug_assert!(depth < self.stack.len(), "empty stack");
+        &self.stack[self.stack.len() - 1 - depth]
+    }
 }
 
 pub trait LocalVarsLike {
     fn get<T: From<Slot>>(&self, index: usize) -> T;
     fn set<T: Into<Slot>>(&mut self, index: usize, operand: T);
 }
+/// 局部变量表, long和double占用`index`和`index + 1`两个槽
 pub struct LocalVars {
     local_vars: Vec<Slot>,
 }
 impl LocalVarsLike for LocalVars {
     fn get<T: From<Slot>>(&self, index: usize) -> T {
-        todo!()
+        debug_assert!(index < self.local_vars.len(), "invalid local index");
+        self.local_vars[index].clone().into()
     }
     fn set<T: Into<Slot>>(&mut self, index: usize, operand: T) {
-        todo!()
+        debug_assert!(index < self.local_vars.len(), "invalid local index");
+        let operand = operand.into();
+        if let Slot::Bits64(_) = operand {
+            // 第二个槽只占位
+            self.local_vars[index + 1] = Slot::Bits32(0);
+        }
+        self.local_vars[index] = operand;
     }
 }
 
-// TODO 异常处理
+impl LocalVars {
+    pub fn new(max_locals: u16) -> Self {
+        Self {
+            local_vars: vec![Slot::Bits32(0); max_locals as usize],
+        }
+    }
+}
+
+/// 异常表中的一项, `[start_pc, end_pc)`内抛出的异常由`handler_pc`处理
+#[derive(Debug, Clone)]
+pub struct ExceptionHandler {
+    pub start_pc: u16,
+    pub end_pc: u16,
+    pub handler_pc: u16,
+    /// 捕获的异常类在常量池中的下标, 0表示捕获所有异常
+    pub catch_type: u16,
+}
+
+/// LineNumberTable中的一项, 从`start_pc`开始的指令属于源文件的`line_number`行
+#[derive(Debug, Clone)]
+pub struct LineNumber {
+    pub start_pc: u16,
+    pub line_number: u16,
+}
+
 #[derive(Debug, Default)]
 pub struct Method {
     pub name: String,
@@ -54,15 +101,64 @@ pub struct Method {
     pub max_locals: u16,
     pub max_stack: u16,
     pub code: Vec<u8>,
+    /// 按class文件中的顺序查找
+    pub exception_table: Vec<ExceptionHandler>,
+    /// 可能为空或无序
+    pub line_numbers: Vec<LineNumber>,
+    /// 所属类SourceFile属性中的源文件名
+    pub source_file: Option<Arc<str>>,
     pub is_static: bool,
+    pub access_flags: MethodAccessFlags,
+    /// 所属类的运行时常量池
+    pub constant_pool: Arc<RuntimeConstantPool>,
+    // 描述符的解析结果, 由parsed_descriptor()填充
+    pub(crate) parsed_descriptor: OnceLock<Result<MethodDescriptor, RuntimeError>>,
+    /// 类中可被覆盖的方法在vtable中的下标
+    pub vtable_index: Option<usize>,
+    /// 接口中的方法在itable中的下标
+    pub itable_index: Option<usize>,
+    // invokevirtual和invokeinterface调用点的内联缓存
+    pub(crate) inline_caches: InlineCaches,
+}
+
+impl Method {
+    /// 解析后的方法描述符, 第一次调用时解析并缓存
+    pub fn parsed_descriptor(&self) -> Result<&MethodDescriptor, RuntimeError> {
+        self.parsed_descriptor
+            .get_or_init(|| MethodDescriptor::parse(&self.descriptor))
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+    pub fn is_native(&self) -> bool {
+        self.access_flags.contains(MethodAccessFlags::NATIVE)
+    }
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags.contains(MethodAccessFlags::ABSTRACT)
+    }
+    pub fn is_private(&self) -> bool {
+        self.access_flags.contains(MethodAccessFlags::PRIVATE)
+    }
+    pub fn is_synchronized(&self) -> bool {
+        self.access_flags.contains(MethodAccessFlags::SYNCHRONIZED)
+    }
+    /// `pc`处指令对应的源代码行号, 取起始地址不大于`pc`的最后一项
+    pub fn line_number(&self, pc: u16) -> Option<u16> {
+        self.line_numbers
+            .iter()
+            .filter(|entry| entry.start_pc <= pc)
+            .max_by_key(|entry| entry.start_pc)
+            .map(|entry| entry.line_number)
+    }
 }
 
 pub struct Frame {
     operand_stack: OperandStack,
     locals: LocalVars,
     return_pc: u16,
-    method: Method,
+    method: Arc<Method>,
     pub pc: u16,
+    /// 同步方法进入时获得锁的对象, 返回或因异常退出时释放
+    pub monitor: Option<ObjectRef>,
 }
 
 impl OperandStackLike for Frame {
@@ -84,17 +180,48 @@ impl LocalVarsLike for Frame {
 }
 
 impl Frame {
-    pub fn new(method: Method, return_pc: u16) -> Self {
+    pub fn new(method: Arc<Method>, return_pc: u16) -> Self {
         let operand_stack = OperandStack::new(method.max_stack);
 
         Self {
             operand_stack,
-            locals: LocalVars {
-                local_vars: Vec::with_capacity(method.max_locals as usize),
-            },
+            locals: LocalVars::new(method.max_locals),
             method,
             return_pc,
             pc: 0,
+            monitor: None,
+        }
+    }
+    pub fn method(&self) -> &Arc<Method> {
+        &self.method
+    }
+    pub fn peek(&self, depth: usize) -> &Slot {
+        self.operand_stack.peek(depth)
+    }
+    /// 进入异常处理器前清空操作数栈
+    pub fn clear_operand_stack(&mut self) {
+        self.operand_stack.stack.clear();
+    }
+    /// 局部变量表, 操作数栈和同步方法的锁对象中的引用
+    pub fn push_roots(&self, roots: &mut Vec<ObjectRef>) {
+        let slots = self
+            .locals
+            .local_vars
+            .iter()
+            .chain(&self.operand_stack.stack);
+        roots.extend(slots.filter_map(|slot| match slot {
+            Slot::Ref(object_ref) => *object_ref,
+            _ => None,
+        }));
+        roots.extend(self.monitor);
+    }
+    /// 按顺序把参数放入局部变量表, long和double占两个槽
+    pub fn set_args(&mut self, args: Vec<Slot>) {
+        let mut index = 0;
+        for arg in args {
+            let size = arg.category();
+            self.locals.set(index, arg);
+            index += size;
         }
     }
     #[cfg(test)]
@@ -106,8 +233,11 @@ impl Frame {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use crate::runtime::{
-        frame::{Frame, LocalVars, LocalVarsLike, OperandStack, OperandStackLike},
+        Method,
+        frame::{Frame, LineNumber, LocalVars, LocalVarsLike, OperandStack, OperandStackLike},
         slot::Slot,
     };
 
@@ -138,4 +268,54 @@ mod tests {
         assert_eq!(frame.operand_stack.max_size, 0);
         assert_eq!(frame.return_pc, 0);
     }
+    #[test]
+    fn test_local_vars() {
+        let mut local_vars = LocalVars::new(4);
+        local_vars.set(0, 1_i32);
+        local_vars.set(1, -2_i64);
+        local_vars.set(3, 1.5_f32);
+        assert_eq!(local_vars.get::<i32>(0), 1);
+        assert_eq!(local_vars.get::<i64>(1), -2);
+        assert_eq!(local_vars.get::<f32>(3), 1.5);
+    }
+    #[test]
+    #[should_panic(expected = "invalid local index")]
+    fn test_local_vars_out_of_range() {
+        let local_vars = LocalVars::new(1);
+        let _: i32 = local_vars.get(1);
+    }
+    #[test]
+    fn test_frame_set_args() {
+        let method = Method {
+            max_locals: 5,
+            ..Default::default()
+        };
+        let mut frame = Frame::new(Arc::new(method), 0);
+        frame.set_args(vec![
+            Slot::Ref(None),
+            Slot::from(2.5_f64),
+            Slot::from(7_i32),
+        ]);
+        assert!(matches!(frame.get::<Slot>(0), Slot::Ref(None)));
+        assert_eq!(frame.get::<f64>(1), 2.5);
+        assert_eq!(frame.get::<i32>(3), 7);
+    }
+    #[test]
+    fn test_line_number() {
+        // LineNumberTable不保证有序
+        let method = Method {
+            line_numbers: [(8, 12), (0, 10), (4, 11)]
+                .into_iter()
+                .map(|(start_pc, line_number)| LineNumber {
+                    start_pc,
+                    line_number,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(method.line_number(0), Some(10));
+        assert_eq!(method.line_number(5), Some(11));
+        assert_eq!(method.line_number(20), Some(12));
+        assert_eq!(Method::default().line_number(0), None);
+    }
 }
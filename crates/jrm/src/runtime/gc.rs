@@ -0,0 +1,368 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::runtime::{
+    heap::{HeapObject, MAX_AGE, Objects},
+    slot::ObjectRef,
+};
+
+/// 默认的最大堆大小
+const DEFAULT_MAX_HEAP: usize = 256 << 20;
+
+/// 启动时选择的收集器
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CollectorKind {
+    /// 只有一个代的标记-清除, 对应`-XX:+UseMarkSweepGC`
+    #[default]
+    MarkSweep,
+    /// 复制式新生代加标记-清除老年代, 对应`-XX:+UseGenerationalGC`
+    Generational,
+}
+
+#[derive(Debug, Clone)]
+pub struct GcOptions {
+    pub collector: CollectorKind,
+    /// 堆中对象占用的最大字节数, 对应`-Xmx`
+    pub max_heap: usize,
+    /// 新生代的大小, 对应`-Xmn`, 默认为最大堆的三分之一
+    pub young_size: Option<usize>,
+    /// 新生代对象存活多少次回收后晋升到老年代, 对应`-XX:MaxTenuringThreshold`
+    pub tenuring_threshold: u8,
+    /// 每次分配前都进行回收, 用于检查GC根是否完整
+    pub stress: bool,
+    /// 每次回收后输出日志, 对应`-Xlog:gc`
+    pub log: bool,
+}
+
+impl Default for GcOptions {
+    fn default() -> Self {
+        Self {
+            collector: CollectorKind::default(),
+            max_heap: DEFAULT_MAX_HEAP,
+            young_size: None,
+            tenuring_threshold: MAX_AGE,
+            stress: false,
+            log: false,
+        }
+    }
+}
+
+impl GcOptions {
+    /// 解析去掉`-X`前缀的选项, 如`mx64m`, `log:gc`和`X:+UseGenerationalGC`
+    pub fn parse_x_option(&mut self, option: &str) -> Result<(), String> {
+        match option {
+            "log:gc" => self.log = true,
+            "X:+UseMarkSweepGC" => self.collector = CollectorKind::MarkSweep,
+            "X:+UseGenerationalGC" => self.collector = CollectorKind::Generational,
+            _ => {
+                if let Some(size) = option.strip_prefix("mx") {
+                    self.max_heap = parse_size(size)
+                        .ok_or_else(|| format!("Invalid maximum heap size: -X{}", option))?
+                } else if let Some(size) = option.strip_prefix("mn") {
+                    let size = parse_size(size)
+                        .filter(|size| *size > 0)
+                        .ok_or_else(|| format!("Invalid young generation size: -X{}", option))?;
+                    self.young_size = Some(size);
+                } else if let Some(threshold) = option.strip_prefix("X:MaxTenuringThreshold=") {
+                    self.tenuring_threshold = threshold
+                        .parse()
+                        .ok()
+                        .filter(|threshold| *threshold <= MAX_AGE)
+                        .ok_or_else(|| {
+                            format!("Invalid maximum tenuring threshold: -X{}", option)
+                        })?;
+                } else {
+                    return Err(format!("Unrecognized option: -X{}", option));
+                }
+            }
+        }
+        Ok(())
+    }
+    /// 按选项创建收集器
+    pub fn collector(&self) -> Box<dyn GarbageCollector> {
+        match self.collector {
+            CollectorKind::MarkSweep => Box::new(MarkSweep),
+            CollectorKind::Generational => Box::new(Generational {
+                young_size: self
+                    .young_size
+                    .unwrap_or(self.max_heap / 3)
+                    .min(self.max_heap),
+                tenuring_threshold: self.tenuring_threshold,
+            }),
+        }
+    }
+}
+
+/// 带有可选单位(k, m, g, 不区分大小写)的字节数
+pub fn parse_size(size: &str) -> Option<usize> {
+    let (digits, shift) = match size.as_bytes().last()? {
+        b'k' | b'K' => (&size[..size.len() - 1], 10),
+        b'm' | b'M' => (&size[..size.len() - 1], 20),
+        b'g' | b'G' => (&size[..size.len() - 1], 30),
+        _ => (size, 0),
+    };
+    let value: usize = digits.parse().ok()?;
+    value.checked_mul(1 << shift)
+}
+
+/// 触发回收的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GcCause {
+    /// 剩余空间不足以完成分配
+    AllocationFailure,
+    /// `GcOptions::stress`
+    Stress,
+    /// 由Java代码或虚拟机主动请求
+    Explicit,
+}
+
+impl Display for GcCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cause = match self {
+            GcCause::AllocationFailure => "Allocation Failure",
+            GcCause::Stress => "GC Stress",
+            GcCause::Explicit => "System.gc()",
+        };
+        write!(f, "{}", cause)
+    }
+}
+
+/// 一次回收的范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GcKind {
+    /// 只回收新生代
+    Young,
+    /// 回收整个堆
+    Full,
+}
+
+impl Display for GcKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GcKind::Young => write!(f, "Young"),
+            GcKind::Full => write!(f, "Full"),
+        }
+    }
+}
+
+/// 虚拟机启动以来的累计统计
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GcStats {
+    /// 包括新生代回收在内的回收次数
+    pub collections: u64,
+    pub young_collections: u64,
+    pub freed_objects: u64,
+    pub freed_bytes: u64,
+    pub total_pause: Duration,
+}
+
+/// 一次回收释放的对象
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Collected {
+    pub objects: usize,
+    pub bytes: usize,
+}
+
+/// 可替换的收集器. 只在安全点持有对象表的写锁时调用
+pub trait GarbageCollector: Send + Sync {
+    /// 放置新分配的对象
+    fn allocate(&self, objects: &mut Objects, object: HeapObject) -> ObjectRef;
+    /// 分配`size`字节之前需要进行的回收, `force`为true时(压力测试)总是回收
+    fn plan(&self, objects: &Objects, size: usize, max_heap: usize, force: bool) -> Option<GcKind>;
+    /// 以`roots`为根回收, `roots`已经包括所有线程, 类和全局引用
+    fn collect(&self, objects: &mut Objects, kind: GcKind, roots: Vec<ObjectRef>) -> Collected;
+    /// 对象`holder`的引用字段或元素被赋值之后调用
+    fn write_barrier(&self, _objects: &mut Objects, _holder: ObjectRef) {}
+}
+
+/// 所有对象都在同一个代中, 每次回收都标记整个堆
+pub struct MarkSweep;
+
+impl GarbageCollector for MarkSweep {
+    fn allocate(&self, objects: &mut Objects, object: HeapObject) -> ObjectRef {
+        objects.insert(object)
+    }
+    fn plan(&self, objects: &Objects, size: usize, max_heap: usize, force: bool) -> Option<GcKind> {
+        let full = objects.used_bytes().saturating_add(size) > max_heap;
+        (force || full).then_some(GcKind::Full)
+    }
+    fn collect(&self, objects: &mut Objects, _kind: GcKind, roots: Vec<ObjectRef>) -> Collected {
+        mark_sweep(objects, roots)
+    }
+}
+
+/// 新生代使用半空间复制, 老年代使用标记-清除. 卡表记录可能引用新生代的老年代对象
+pub struct Generational {
+    /// from空间的容量
+    pub young_size: usize,
+    pub tenuring_threshold: u8,
+}
+
+impl GarbageCollector for Generational {
+    fn allocate(&self, objects: &mut Objects, object: HeapObject) -> ObjectRef {
+        // 新生代已满或过大的对象直接在老年代分配
+        if objects.nursery_bytes() + object.size() <= self.young_size {
+            objects.insert_young(object)
+        } else {
+            objects.insert(object)
+        }
+    }
+    fn plan(&self, objects: &Objects, size: usize, max_heap: usize, force: bool) -> Option<GcKind> {
+        let young_full =
+            size <= self.young_size && objects.nursery_bytes() + size > self.young_size;
+        if force || young_full {
+            Some(GcKind::Young)
+        } else if objects.used_bytes().saturating_add(size) > max_heap {
+            Some(GcKind::Full)
+        } else {
+            None
+        }
+    }
+    fn collect(&self, objects: &mut Objects, kind: GcKind, roots: Vec<ObjectRef>) -> Collected {
+        match kind {
+            GcKind::Young => copy_nursery(objects, roots, self.tenuring_threshold),
+            GcKind::Full => {
+                // 新生代对象全部晋升后, 整个堆只有一个代
+                for (object_ref, object) in objects.take_nursery() {
+                    objects.set_old(object_ref, object);
+                }
+                objects.take_dirty_cards();
+                mark_sweep(objects, roots)
+            }
+        }
+    }
+    fn write_barrier(&self, objects: &mut Objects, holder: ObjectRef) {
+        objects.dirty_card(holder);
+    }
+}
+
+/// 把从`roots`和脏卡可达的新生代对象复制到to空间, 已经存活`tenuring_threshold`次的晋升到老年代.
+/// 没有被复制的对象随from空间一起释放
+pub fn copy_nursery(
+    objects: &mut Objects,
+    roots: Vec<ObjectRef>,
+    tenuring_threshold: u8,
+) -> Collected {
+    let mut pending = roots;
+    let mut remembered = objects.take_dirty_cards();
+    for object_ref in &remembered {
+        objects[*object_ref].push_references(&mut pending);
+    }
+    let mut from: Vec<_> = objects.take_nursery().into_iter().map(Some).collect();
+    let mut to = Vec::new();
+    while let Some(object_ref) = pending.pop() {
+        // 老年代对象和已经复制的对象的表项不再指向from空间中的对象
+        let Some(index) = objects.young_index(object_ref) else {
+            continue;
+        };
+        let Some((_, object)) = from[index].take() else {
+            continue;
+        };
+        object.push_references(&mut pending);
+        if object.header.age() >= tenuring_threshold {
+            objects.set_old(object_ref, object);
+            remembered.push(object_ref);
+        } else {
+            object.header.increment_age();
+            to.push((object_ref, object));
+        }
+    }
+    let mut collected = Collected::default();
+    for (object_ref, object) in from.into_iter().flatten() {
+        collected.objects += 1;
+        collected.bytes += object.size();
+        objects.free_young(object_ref);
+    }
+    objects.set_nursery(to);
+    // 重建卡表: 只有扫描过的老年代对象和刚晋升的对象可能引用新生代
+    for object_ref in remembered {
+        let mut references = Vec::new();
+        objects[object_ref].push_references(&mut references);
+        if references
+            .iter()
+            .any(|reference| objects.is_young(*reference))
+        {
+            objects.dirty_card(object_ref);
+        }
+    }
+    collected
+}
+
+/// 标记-清除: 从`roots`出发标记所有可达对象, 然后释放未标记的对象并清除标记
+pub fn mark_sweep(objects: &mut Objects, roots: Vec<ObjectRef>) -> Collected {
+    let mut pending = roots;
+    while let Some(object_ref) = pending.pop() {
+        let object = &objects[object_ref];
+        if object.header.mark() {
+            object.push_references(&mut pending);
+        }
+    }
+    let mut collected = Collected::default();
+    for object_ref in objects.refs() {
+        if objects[object_ref].header.unmark() {
+            continue;
+        }
+        collected.objects += 1;
+        collected.bytes += objects.remove(object_ref);
+    }
+    collected
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{CollectorKind, GcOptions, parse_size};
+
+    #[rstest]
+    #[case("4096", Some(4096))]
+    #[case("64k", Some(64 << 10))]
+    #[case("64M", Some(64 << 20))]
+    #[case("2g", Some(2 << 30))]
+    #[case("", None)]
+    #[case("m", None)]
+    #[case("-1m", None)]
+    fn test_parse_size(#[case] size: &str, #[case] expected: Option<usize>) {
+        assert_eq!(parse_size(size), expected);
+    }
+
+    #[test]
+    fn test_parse_x_option() {
+        let mut options = GcOptions::default();
+        options.parse_x_option("mx16m").unwrap();
+        options.parse_x_option("log:gc").unwrap();
+        assert_eq!(options.max_heap, 16 << 20);
+        assert!(options.log);
+        assert_eq!(
+            options.parse_x_option("mxlots").unwrap_err(),
+            "Invalid maximum heap size: -Xmxlots"
+        );
+        assert_eq!(
+            options.parse_x_option("ss1m").unwrap_err(),
+            "Unrecognized option: -Xss1m"
+        );
+    }
+
+    #[test]
+    fn test_parse_generational_options() {
+        let mut options = GcOptions::default();
+        assert_eq!(options.collector, CollectorKind::MarkSweep);
+        options.parse_x_option("X:+UseGenerationalGC").unwrap();
+        options.parse_x_option("mn4m").unwrap();
+        options.parse_x_option("X:MaxTenuringThreshold=2").unwrap();
+        assert_eq!(options.collector, CollectorKind::Generational);
+        assert_eq!(options.young_size, Some(4 << 20));
+        assert_eq!(options.tenuring_threshold, 2);
+        assert_eq!(
+            options
+                .parse_x_option("X:MaxTenuringThreshold=16")
+                .unwrap_err(),
+            "Invalid maximum tenuring threshold: -XX:MaxTenuringThreshold=16"
+        );
+        assert_eq!(
+            options.parse_x_option("mn0").unwrap_err(),
+            "Invalid young generation size: -Xmn0"
+        );
+        options.parse_x_option("X:+UseMarkSweepGC").unwrap();
+        assert_eq!(options.collector, CollectorKind::MarkSweep);
+    }
+}
@@ -0,0 +1,1137 @@
+use std::{
+    collections::HashMap,
+    ops::{Index, IndexMut},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicU32, AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+
+use crate::runtime::{
+    Console, Klass, RuntimeError,
+    descriptor::FieldType,
+    gc::{Collected, GarbageCollector, GcCause, GcKind, GcOptions, GcStats},
+    klass::Field,
+    monitor::Monitors,
+    runtime_constant_pool::MethodHandleRef,
+    slot::{ObjectRef, Slot},
+    stack_trace::StackTraceElement,
+    string::JavaString,
+};
+
+/// 堆中的对象
+#[derive(Debug, Clone)]
+pub enum Object {
+    /// `java.lang.Class`的实例
+    Class(Arc<Klass>),
+    String(JavaString),
+    MethodType(Arc<str>),
+    MethodHandle(MethodHandleRef),
+    Array(Array),
+    Instance(Instance),
+    /// `Throwable.backtrace`字段引用的栈轨迹, Java代码不可见
+    Backtrace(Arc<[StackTraceElement]>),
+}
+
+/// mark word中GC的标记位
+const MARK_BIT: u64 = 1 << 32;
+/// mark word中对象年龄的位置, 年龄为新生代回收中存活的次数
+const AGE_SHIFT: u32 = 33;
+pub const MAX_AGE: u8 = 15;
+
+/// 对象头: 类指针和mark word
+#[derive(Debug, Default)]
+pub struct ObjectHeader {
+    /// 普通对象的类, 其他内建对象的类由`Object`的变体决定
+    klass: Option<Arc<Klass>>,
+    /// 低32位为identity hash, 0表示尚未生成; 之后依次为GC的标记位, 年龄和锁
+    mark: AtomicU64,
+}
+
+impl ObjectHeader {
+    pub fn klass(&self) -> Option<&Arc<Klass>> {
+        self.klass.as_ref()
+    }
+    /// 由`Monitors`读写其中锁的位
+    pub fn mark_word(&self) -> &AtomicU64 {
+        &self.mark
+    }
+    /// 设置标记位, 返回之前是否未标记
+    pub fn mark(&self) -> bool {
+        self.mark.fetch_or(MARK_BIT, Ordering::AcqRel) & MARK_BIT == 0
+    }
+    /// 清除标记位, 返回之前是否已标记
+    pub fn unmark(&self) -> bool {
+        self.mark.fetch_and(!MARK_BIT, Ordering::AcqRel) & MARK_BIT != 0
+    }
+    pub fn age(&self) -> u8 {
+        (self.mark.load(Ordering::Acquire) >> AGE_SHIFT) as u8 & MAX_AGE
+    }
+    /// 年龄加一并返回新的年龄, 达到`MAX_AGE`后不再增加
+    pub fn increment_age(&self) -> u8 {
+        let age = (self.age() + 1).min(MAX_AGE);
+        let mask = (MAX_AGE as u64) << AGE_SHIFT;
+        let _ = self
+            .mark
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |mark| {
+                Some(mark & !mask | (age as u64) << AGE_SHIFT)
+            });
+        age
+    }
+    /// 第一次调用时生成并写入mark word
+    pub fn identity_hash(&self) -> i32 {
+        let mark = self.mark.load(Ordering::Acquire);
+        if mark as u32 != 0 {
+            return mark as u32 as i32;
+        }
+        let hash = next_hash() as u64;
+        match self
+            .mark
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |mark| {
+                (mark as u32 == 0).then_some(mark | hash)
+            }) {
+            Ok(_) => hash as i32,
+            // 其他线程已经生成
+            Err(mark) => mark as u32 as i32,
+        }
+    }
+}
+
+/// xorshift生成的非零正数
+fn next_hash() -> u32 {
+    static SEED: AtomicU32 = AtomicU32::new(0x9e37_79b9);
+    let mut hash = 0;
+    let _ = SEED.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |mut seed| {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        hash = seed & 0x7fff_ffff;
+        Some(seed)
+    });
+    hash.max(1)
+}
+
+/// 按对象内容估算大小时对象头占用的字节数, 也是`Class`等没有数据的对象的大小
+pub const HEADER_SIZE: usize = 16;
+
+/// `klass`的实例占用的字节数
+pub fn instance_bytes(klass: &Klass) -> usize {
+    HEADER_SIZE + klass.instance_size()
+}
+
+/// 元素类型为`component`, 长度为`length`的数组占用的字节数
+pub fn array_bytes(component: &FieldType, length: usize) -> usize {
+    let element_size = match component {
+        FieldType::Boolean | FieldType::Byte => 1,
+        FieldType::Char | FieldType::Short => 2,
+        FieldType::Long | FieldType::Double => 8,
+        _ => 4,
+    };
+    HEADER_SIZE + length * element_size
+}
+
+/// 有`length`帧的栈轨迹占用的字节数
+pub fn backtrace_bytes(length: usize) -> usize {
+    HEADER_SIZE + length * size_of::<StackTraceElement>()
+}
+
+/// 内容为`string`的字符串对象占用的字节数
+pub fn string_bytes(string: &JavaString) -> usize {
+    HEADER_SIZE + string.value().len()
+}
+
+pub struct HeapObject {
+    pub header: ObjectHeader,
+    pub object: Object,
+}
+
+impl HeapObject {
+    pub fn size(&self) -> usize {
+        match &self.object {
+            Object::Instance(instance) => HEADER_SIZE + instance.data.len(),
+            Object::Array(array) => array_bytes(&array.component, array.elements.len()),
+            Object::String(string) => string_bytes(string),
+            Object::Backtrace(elements) => backtrace_bytes(elements.len()),
+            Object::Class(_) | Object::MethodType(_) | Object::MethodHandle(_) => HEADER_SIZE,
+        }
+    }
+    /// 对象的字段或元素引用的对象
+    pub fn push_references(&self, references: &mut Vec<ObjectRef>) {
+        match &self.object {
+            Object::Instance(instance) => {
+                let Some(klass) = self.header.klass() else {
+                    return;
+                };
+                references.extend(
+                    klass
+                        .reference_offsets()
+                        .iter()
+                        .filter_map(|offset| instance.reference_at(*offset)),
+                );
+            }
+            Object::Array(array) => {
+                references.extend(array.elements.iter().filter_map(|element| match element {
+                    Slot::Ref(object_ref) => *object_ref,
+                    _ => None,
+                }))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 对象表项: 老年代对象直接存放在表中, 新生代对象存放在`Objects::nursery`中
+enum Entry {
+    Old(HeapObject),
+    Young(usize),
+}
+
+/// 每张卡覆盖`1 << CARD_SHIFT`个对象表项
+const CARD_SHIFT: usize = 4;
+
+/// 以`ObjectRef`为下标的对象表, 回收后空出的位置留给之后的分配.
+/// 对象移动时只修改表项, `ObjectRef`保持不变
+#[derive(Default)]
+pub struct Objects {
+    slots: Vec<Option<Entry>>,
+    free: Vec<ObjectRef>,
+    /// 新生代的from空间, 按分配顺序存放
+    nursery: Vec<(ObjectRef, HeapObject)>,
+    /// 卡表, 老年代对象的引用字段被赋值后所在的卡置脏
+    cards: Vec<bool>,
+    used_bytes: usize,
+    nursery_bytes: usize,
+}
+
+impl Objects {
+    fn new_ref(&mut self, entry: Entry) -> ObjectRef {
+        match self.free.pop() {
+            Some(object_ref) => {
+                self.slots[object_ref.index()] = Some(entry);
+                object_ref
+            }
+            None => {
+                self.slots.push(Some(entry));
+                ObjectRef::new(self.slots.len() as u32 - 1)
+            }
+        }
+    }
+    /// 在老年代分配
+    pub fn insert(&mut self, object: HeapObject) -> ObjectRef {
+        self.used_bytes += object.size();
+        self.new_ref(Entry::Old(object))
+    }
+    /// 在新生代分配
+    pub fn insert_young(&mut self, object: HeapObject) -> ObjectRef {
+        let size = object.size();
+        self.used_bytes += size;
+        self.nursery_bytes += size;
+        let object_ref = self.new_ref(Entry::Young(self.nursery.len()));
+        self.nursery.push((object_ref, object));
+        object_ref
+    }
+    /// 释放老年代对象, 返回它占用的字节数
+    pub fn remove(&mut self, object_ref: ObjectRef) -> usize {
+        let Some(Entry::Old(object)) = self.slots[object_ref.index()].take() else {
+            panic!("object already freed or not tenured");
+        };
+        self.free.push(object_ref);
+        let size = object.size();
+        self.used_bytes -= size;
+        size
+    }
+    /// 对象已被回收时返回None
+    pub fn get(&self, object_ref: ObjectRef) -> Option<&HeapObject> {
+        match self.slots.get(object_ref.index())?.as_ref()? {
+            Entry::Old(object) => Some(object),
+            Entry::Young(index) => Some(&self.nursery[*index].1),
+        }
+    }
+    /// 所有存活对象的引用
+    pub fn refs(&self) -> Vec<ObjectRef> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.is_some())
+            .map(|(index, _)| ObjectRef::new(index as u32))
+            .collect()
+    }
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes
+    }
+    pub fn nursery_bytes(&self) -> usize {
+        self.nursery_bytes
+    }
+    pub fn is_young(&self, object_ref: ObjectRef) -> bool {
+        self.young_index(object_ref).is_some()
+    }
+    /// 新生代对象在from空间中的位置
+    pub fn young_index(&self, object_ref: ObjectRef) -> Option<usize> {
+        match self.slots[object_ref.index()] {
+            Some(Entry::Young(index)) => Some(index),
+            _ => None,
+        }
+    }
+    /// 写屏障: 老年代对象所在的卡置脏
+    pub fn dirty_card(&mut self, object_ref: ObjectRef) {
+        if !matches!(self.slots[object_ref.index()], Some(Entry::Old(_))) {
+            return;
+        }
+        let card = object_ref.index() >> CARD_SHIFT;
+        if self.cards.len() <= card {
+            self.cards.resize(card + 1, false);
+        }
+        self.cards[card] = true;
+    }
+    /// 清除卡表, 返回脏卡上的老年代对象
+    pub fn take_dirty_cards(&mut self) -> Vec<ObjectRef> {
+        let cards = std::mem::take(&mut self.cards);
+        let mut object_refs = Vec::new();
+        for card in (0..cards.len()).filter(|card| cards[*card]) {
+            let start = card << CARD_SHIFT;
+            let end = (start + (1 << CARD_SHIFT)).min(self.slots.len());
+            object_refs.extend(
+                (start..end)
+                    .filter(|index| matches!(self.slots[*index], Some(Entry::Old(_))))
+                    .map(|index| ObjectRef::new(index as u32)),
+            );
+        }
+        object_refs
+    }
+    /// 取出from空间, 新生代对象的表项在`set_old`或`set_nursery`之前保持不变
+    pub fn take_nursery(&mut self) -> Vec<(ObjectRef, HeapObject)> {
+        self.used_bytes -= self.nursery_bytes;
+        self.nursery_bytes = 0;
+        std::mem::take(&mut self.nursery)
+    }
+    /// 把对象放入老年代
+    pub fn set_old(&mut self, object_ref: ObjectRef, object: HeapObject) {
+        self.used_bytes += object.size();
+        self.slots[object_ref.index()] = Some(Entry::Old(object));
+    }
+    /// 以复制后的对象作为新的from空间
+    pub fn set_nursery(&mut self, nursery: Vec<(ObjectRef, HeapObject)>) {
+        for (index, (object_ref, object)) in nursery.iter().enumerate() {
+            let size = object.size();
+            self.used_bytes += size;
+            self.nursery_bytes += size;
+            self.slots[object_ref.index()] = Some(Entry::Young(index));
+        }
+        self.nursery = nursery;
+    }
+    /// 释放`take_nursery`之后没有被复制的对象的表项
+    pub fn free_young(&mut self, object_ref: ObjectRef) {
+        self.slots[object_ref.index()] = None;
+        self.free.push(object_ref);
+    }
+}
+
+impl Index<ObjectRef> for Objects {
+    type Output = HeapObject;
+
+    fn index(&self, object_ref: ObjectRef) -> &Self::Output {
+        match self.slots[object_ref.index()]
+            .as_ref()
+            .expect("dangling object reference")
+        {
+            Entry::Old(object) => object,
+            Entry::Young(index) => &self.nursery[*index].1,
+        }
+    }
+}
+
+impl IndexMut<ObjectRef> for Objects {
+    fn index_mut(&mut self, object_ref: ObjectRef) -> &mut Self::Output {
+        match self.slots[object_ref.index()]
+            .as_mut()
+            .expect("dangling object reference")
+        {
+            Entry::Old(object) => object,
+            Entry::Young(index) => &mut self.nursery[*index].1,
+        }
+    }
+}
+
+/// 普通类的实例数据, 字段按`Field::offset`存放, 全零即所有字段的零值
+#[derive(Debug, Clone)]
+pub struct Instance {
+    data: Box<[u8]>,
+}
+
+impl Instance {
+    pub fn new(size: usize) -> Self {
+        Self {
+            data: vec![0; size].into_boxed_slice(),
+        }
+    }
+    pub fn get(&self, field: &Field) -> Slot {
+        let offset = field.offset;
+        match field.descriptor.as_bytes()[0] {
+            b'B' => Slot::from(self.data[offset] as i8 as i32),
+            b'Z' => Slot::Bits32(self.data[offset] as u32),
+            b'C' => Slot::Bits32(u16::from_le_bytes(self.read(offset)) as u32),
+            b'S' => Slot::from(i16::from_le_bytes(self.read(offset)) as i32),
+            b'J' | b'D' => Slot::Bits64(u64::from_le_bytes(self.read(offset))),
+            // 引用存为下标加一, 0为null
+            b'L' | b'[' => Slot::Ref(self.reference_at(offset)),
+            _ => Slot::Bits32(u32::from_le_bytes(self.read(offset))),
+        }
+    }
+    /// byte, boolean, char和short字段按字段类型截断
+    pub fn set(&mut self, field: &Field, value: Slot) {
+        let offset = field.offset;
+        match (field.descriptor.as_bytes()[0], value) {
+            (b'B' | b'Z', value) => {
+                self.data[offset] = i32::from(value.narrow(&field.descriptor)) as u8
+            }
+            (b'C' | b'S', Slot::Bits32(bits)) => self.write(offset, (bits as u16).to_le_bytes()),
+            (_, Slot::Bits32(bits)) => self.write(offset, bits.to_le_bytes()),
+            (_, Slot::Bits64(bits)) => self.write(offset, bits.to_le_bytes()),
+            (_, Slot::Ref(object_ref)) => self.write(
+                offset,
+                object_ref
+                    .map_or(0, |object_ref| object_ref.index() as u32 + 1)
+                    .to_le_bytes(),
+            ),
+        }
+    }
+    /// `offset`处引用类型字段的值
+    pub fn reference_at(&self, offset: usize) -> Option<ObjectRef> {
+        u32::from_le_bytes(self.read(offset))
+            .checked_sub(1)
+            .map(ObjectRef::new)
+    }
+    fn read<const N: usize>(&self, offset: usize) -> [u8; N] {
+        self.data[offset..offset + N].try_into().unwrap()
+    }
+    fn write<const N: usize>(&mut self, offset: usize, bytes: [u8; N]) {
+        self.data[offset..offset + N].copy_from_slice(&bytes);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Array {
+    pub component: FieldType,
+    pub elements: Vec<Slot>,
+}
+
+/// 数组的最大长度, 与HotSpot相同为对象头留出空间
+pub const MAX_ARRAY_LENGTH: usize = i32::MAX as usize - 2;
+
+impl Array {
+    pub fn new(component: FieldType, length: usize) -> Result<Self, RuntimeError> {
+        if length > MAX_ARRAY_LENGTH {
+            return Err(RuntimeError::OutOfMemoryError(
+                "Requested array size exceeds VM limit".to_string(),
+            ));
+        }
+        let zero_value = match component {
+            FieldType::Long | FieldType::Double => Slot::Bits64(0),
+            FieldType::Object(_) | FieldType::Array(_) => Slot::Ref(None),
+            _ => Slot::Bits32(0),
+        };
+        let mut elements = vec![];
+        elements
+            .try_reserve_exact(length)
+            .map_err(|_| RuntimeError::OutOfMemoryError("Java heap space".to_string()))?;
+        elements.resize(length, zero_value);
+        Ok(Self {
+            component,
+            elements,
+        })
+    }
+    fn check_index(&self, index: i32) -> Result<usize, RuntimeError> {
+        if index < 0 || index as usize >= self.elements.len() {
+            return Err(RuntimeError::ArrayIndexOutOfBoundsException(format!(
+                "Index {} out of bounds for length {}",
+                index,
+                self.elements.len()
+            )));
+        }
+        Ok(index as usize)
+    }
+}
+
+/// 对象表, `ObjectRef`是对象在表中的下标. 由启动时选择的stop-the-world收集器回收
+pub struct Heap {
+    objects: RwLock<Objects>,
+    collector: Box<dyn GarbageCollector>,
+    // 字符串池
+    strings: Mutex<HashMap<JavaString, ObjectRef>>,
+    monitors: Monitors,
+    // 本地代码持有的全局引用, 删除后为None
+    global_refs: Mutex<Vec<Option<ObjectRef>>>,
+    options: GcOptions,
+    stats: Mutex<GcStats>,
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self::new(GcOptions::default())
+    }
+}
+
+impl Heap {
+    pub fn new(options: GcOptions) -> Self {
+        Self {
+            objects: Default::default(),
+            collector: options.collector(),
+            strings: Default::default(),
+            monitors: Default::default(),
+            global_refs: Default::default(),
+            options,
+            stats: Default::default(),
+        }
+    }
+    pub fn alloc(&self, object: Object) -> ObjectRef {
+        self.alloc_with_header(ObjectHeader::default(), object)
+    }
+    /// 按类的实例大小分配对象, 字段均为零值
+    pub fn alloc_instance(&self, klass: Arc<Klass>) -> ObjectRef {
+        let instance = Instance::new(klass.instance_size());
+        let header = ObjectHeader {
+            klass: Some(klass),
+            ..Default::default()
+        };
+        self.alloc_with_header(header, Object::Instance(instance))
+    }
+    /// 按数组类的元素类型分配数组, 元素均为零值
+    pub fn alloc_array(&self, klass: Arc<Klass>, length: usize) -> Result<ObjectRef, RuntimeError> {
+        let component = klass
+            .component_type()
+            .ok_or(RuntimeError::IllegalState)?
+            .clone();
+        let header = ObjectHeader {
+            klass: Some(klass),
+            ..Default::default()
+        };
+        Ok(self.alloc_with_header(header, Object::Array(Array::new(component, length)?)))
+    }
+    fn alloc_with_header(&self, header: ObjectHeader, object: Object) -> ObjectRef {
+        let mut objects = self.objects.write().unwrap();
+        self.collector
+            .allocate(&mut objects, HeapObject { header, object })
+    }
+    /// Object.clone的浅复制, 新对象有自己的identity hash
+    pub fn clone_object(&self, object_ref: ObjectRef) -> ObjectRef {
+        let mut objects = self.objects.write().unwrap();
+        let source = &objects[object_ref];
+        let copy = HeapObject {
+            header: ObjectHeader {
+                klass: source.header.klass.clone(),
+                ..Default::default()
+            },
+            object: source.object.clone(),
+        };
+        let clone = self.collector.allocate(&mut objects, copy);
+        // 复制的引用字段相当于一次赋值
+        self.collector.write_barrier(&mut objects, clone);
+        clone
+    }
+    pub fn get(&self, object_ref: ObjectRef) -> Object {
+        self.objects.read().unwrap()[object_ref].object.clone()
+    }
+    /// 不复制对象, 在读锁内访问
+    pub fn with_object<R>(&self, object_ref: ObjectRef, f: impl FnOnce(&Object) -> R) -> R {
+        f(&self.objects.read().unwrap()[object_ref].object)
+    }
+    /// 在读锁内访问对象头
+    pub fn with_header<R>(&self, object_ref: ObjectRef, f: impl FnOnce(&ObjectHeader) -> R) -> R {
+        f(&self.objects.read().unwrap()[object_ref].header)
+    }
+    /// 对象占用的字节数, 复制对象之前据此分配空间
+    pub fn object_bytes(&self, object_ref: ObjectRef) -> usize {
+        self.objects.read().unwrap()[object_ref].size()
+    }
+    /// 对象头中的类指针
+    pub fn klass(&self, object_ref: ObjectRef) -> Option<Arc<Klass>> {
+        self.with_header(object_ref, |header| header.klass().cloned())
+    }
+    pub fn identity_hash(&self, object_ref: ObjectRef) -> i32 {
+        self.with_header(object_ref, ObjectHeader::identity_hash)
+    }
+    /// getfield
+    pub fn get_field(
+        &self,
+        object: Option<ObjectRef>,
+        field: &Field,
+    ) -> Result<Slot, RuntimeError> {
+        let object = object.ok_or_else(|| {
+            RuntimeError::NullPointerException(format!(
+                "Cannot read field \"{}\" because value is null",
+                field.name
+            ))
+        })?;
+        match &self.objects.read().unwrap()[object].object {
+            Object::Instance(instance) => Ok(instance.get(field)),
+            _ => Err(RuntimeError::IllegalState),
+        }
+    }
+    /// putfield
+    pub fn put_field(
+        &self,
+        object: Option<ObjectRef>,
+        field: &Field,
+        value: Slot,
+    ) -> Result<(), RuntimeError> {
+        let object = object.ok_or_else(|| {
+            RuntimeError::NullPointerException(format!(
+                "Cannot assign field \"{}\" because value is null",
+                field.name
+            ))
+        })?;
+        let mut objects = self.objects.write().unwrap();
+        let is_reference = matches!(value, Slot::Ref(Some(_)));
+        match &mut objects[object].object {
+            Object::Instance(instance) => instance.set(field, value),
+            _ => return Err(RuntimeError::IllegalState),
+        }
+        if is_reference {
+            self.collector.write_barrier(&mut objects, object);
+        }
+        Ok(())
+    }
+    /// 字符串池中与`string`内容相同的字符串
+    pub fn interned(&self, string: &JavaString) -> Option<ObjectRef> {
+        self.strings.lock().unwrap().get(string).copied()
+    }
+    /// 相同内容的字符串返回同一个引用, ldc的字符串常量都在池中.
+    /// 不经过安全点, 执行中的线程应当使用`Vm::intern`
+    pub fn intern(&self, string: JavaString) -> ObjectRef {
+        let mut strings = self.strings.lock().unwrap();
+        if let Some(object_ref) = strings.get(&string) {
+            return *object_ref;
+        }
+        let object_ref = self.alloc(Object::String(string.clone()));
+        strings.insert(string, object_ref);
+        object_ref
+    }
+    /// `String.intern`: 池中没有相同内容的字符串时把`object_ref`自身放入池中
+    pub fn intern_object(&self, object_ref: ObjectRef) -> Result<ObjectRef, RuntimeError> {
+        let Object::String(string) = self.get(object_ref) else {
+            return Err(RuntimeError::IllegalState);
+        };
+        Ok(*self
+            .strings
+            .lock()
+            .unwrap()
+            .entry(string)
+            .or_insert(object_ref))
+    }
+    /// monitorenter, 其他线程持有锁时返回false, 由调用者阻塞
+    pub fn monitor_enter(&self, object_ref: ObjectRef, thread_id: u64) -> bool {
+        self.with_header(object_ref, |header| {
+            self.monitors
+                .try_enter(header.mark_word(), object_ref, thread_id)
+        })
+    }
+    /// monitorexit, 当前线程不是持有者时抛出IllegalMonitorStateException
+    pub fn monitor_exit(&self, object_ref: ObjectRef, thread_id: u64) -> Result<(), RuntimeError> {
+        self.with_header(object_ref, |header| {
+            self.monitors
+                .exit(header.mark_word(), object_ref, thread_id)
+        })
+    }
+    /// `Object.wait`之前完全释放锁, 返回进入的次数
+    pub fn monitor_exit_all(
+        &self,
+        object_ref: ObjectRef,
+        thread_id: u64,
+    ) -> Result<u64, RuntimeError> {
+        self.with_header(object_ref, |header| {
+            self.monitors
+                .exit_all(header.mark_word(), object_ref, thread_id)
+        })
+    }
+    /// 持有锁的线程id
+    pub fn monitor_owner(&self, object_ref: ObjectRef) -> Option<u64> {
+        self.with_header(object_ref, |header| {
+            self.monitors.owner(header.mark_word(), object_ref)
+        })
+    }
+    /// 对象的锁是否已膨胀为重量级锁
+    pub fn is_inflated(&self, object_ref: ObjectRef) -> bool {
+        self.with_header(object_ref, |header| {
+            Monitors::is_inflated(header.mark_word())
+        })
+    }
+    /// arraylength
+    pub fn array_length(&self, array: Option<ObjectRef>) -> Result<i32, RuntimeError> {
+        let array = array.ok_or_else(|| {
+            RuntimeError::NullPointerException(
+                "Cannot read the array length because value is null".to_string(),
+            )
+        })?;
+        match &self.objects.read().unwrap()[array].object {
+            Object::Array(array) => Ok(array.elements.len() as i32),
+            _ => Err(RuntimeError::IllegalState),
+        }
+    }
+    /// xaload
+    pub fn array_load(&self, array: Option<ObjectRef>, index: i32) -> Result<Slot, RuntimeError> {
+        let array = array.ok_or_else(|| {
+            RuntimeError::NullPointerException("Cannot load from null array".to_string())
+        })?;
+        match &self.objects.read().unwrap()[array].object {
+            Object::Array(array) => Ok(array.elements[array.check_index(index)?].clone()),
+            _ => Err(RuntimeError::IllegalState),
+        }
+    }
+    /// xastore, byte, boolean, char和short数组按元素类型截断
+    pub fn array_store(
+        &self,
+        array: Option<ObjectRef>,
+        index: i32,
+        value: Slot,
+    ) -> Result<(), RuntimeError> {
+        let array = array.ok_or_else(|| {
+            RuntimeError::NullPointerException("Cannot store to null array".to_string())
+        })?;
+        let mut objects = self.objects.write().unwrap();
+        let is_reference = matches!(value, Slot::Ref(Some(_)));
+        match &mut objects[array].object {
+            Object::Array(target) => {
+                let index = target.check_index(index)?;
+                let value = match (&target.component, value) {
+                    (FieldType::Byte, Slot::Bits32(bits)) => Slot::from(bits as i8 as i32),
+                    (FieldType::Boolean, Slot::Bits32(bits)) => Slot::Bits32(bits & 1),
+                    (FieldType::Char, Slot::Bits32(bits)) => Slot::Bits32(bits as u16 as u32),
+                    (FieldType::Short, Slot::Bits32(bits)) => Slot::from(bits as i16 as i32),
+                    (_, value) => value,
+                };
+                target.elements[index] = value;
+            }
+            _ => return Err(RuntimeError::IllegalState),
+        }
+        if is_reference {
+            self.collector.write_barrier(&mut objects, array);
+        }
+        Ok(())
+    }
+    /// 存活对象的数量
+    pub fn object_count(&self) -> usize {
+        self.objects.read().unwrap().len()
+    }
+    /// 存活对象占用的字节数
+    pub fn used_bytes(&self) -> usize {
+        self.objects.read().unwrap().used_bytes()
+    }
+    /// 对象是否在新生代中
+    pub fn is_young(&self, object_ref: ObjectRef) -> bool {
+        self.objects.read().unwrap().is_young(object_ref)
+    }
+    pub fn options(&self) -> &GcOptions {
+        &self.options
+    }
+    pub fn stats(&self) -> GcStats {
+        self.stats.lock().unwrap().clone()
+    }
+    /// 创建全局引用, 删除之前对象不会被回收
+    pub fn new_global_ref(&self, object_ref: ObjectRef) -> usize {
+        let mut global_refs = self.global_refs.lock().unwrap();
+        global_refs.push(Some(object_ref));
+        global_refs.len() - 1
+    }
+    pub fn delete_global_ref(&self, handle: usize) {
+        self.global_refs.lock().unwrap()[handle] = None;
+    }
+    /// 分配`size`字节之前的安全点. 收集器认为需要或压力测试时以`roots`回收,
+    /// 新生代回收后空间仍然不足时再进行完整回收, 仍然不足时抛出OutOfMemoryError.
+    /// 开启`-Xlog:gc`时日志写入`console`的标准错误
+    pub fn reserve(
+        &self,
+        size: usize,
+        console: &Console,
+        roots: impl FnOnce() -> Vec<ObjectRef>,
+    ) -> Result<(), RuntimeError> {
+        let fits = || self.used_bytes().saturating_add(size) <= self.options.max_heap;
+        let kind = self.collector.plan(
+            &self.objects.read().unwrap(),
+            size,
+            self.options.max_heap,
+            self.options.stress,
+        );
+        let Some(kind) = kind else {
+            return Ok(());
+        };
+        let cause = if self.options.stress {
+            GcCause::Stress
+        } else {
+            GcCause::AllocationFailure
+        };
+        let roots = roots();
+        if kind == GcKind::Young {
+            self.collect_with(GcKind::Young, cause, console, roots.clone());
+            if fits() {
+                return Ok(());
+            }
+        }
+        self.collect_with(GcKind::Full, cause, console, roots);
+        if !fits() {
+            return Err(RuntimeError::OutOfMemoryError(
+                "Java heap space".to_string(),
+            ));
+        }
+        Ok(())
+    }
+    /// 以`roots`以及字符串池和全局引用为根进行一次完整的回收
+    pub fn collect(&self, cause: GcCause, console: &Console, roots: Vec<ObjectRef>) -> Collected {
+        self.collect_with(GcKind::Full, cause, console, roots)
+    }
+    fn collect_with(
+        &self,
+        kind: GcKind,
+        cause: GcCause,
+        console: &Console,
+        mut roots: Vec<ObjectRef>,
+    ) -> Collected {
+        let start = Instant::now();
+        roots.extend(self.strings.lock().unwrap().values().copied());
+        roots.extend(self.global_refs.lock().unwrap().iter().flatten().copied());
+        let mut objects = self.objects.write().unwrap();
+        let before = objects.used_bytes();
+        let collected = self.collector.collect(&mut objects, kind, roots);
+        self.monitors.deflate_idle(|object_ref| {
+            objects
+                .get(object_ref)
+                .map(|object| object.header.mark_word())
+        });
+        let pause = start.elapsed();
+
+        let mut stats = self.stats.lock().unwrap();
+        if self.options.log {
+            let line = format!(
+                "[gc] GC({}) Pause {} ({}) {}K->{}K({}K) {:.3}ms\n",
+                stats.collections,
+                kind,
+                cause,
+                before >> 10,
+                objects.used_bytes() >> 10,
+                self.options.max_heap >> 10,
+                pause.as_secs_f64() * 1000.0
+            );
+            let _ = console.write(2, line.as_bytes());
+        }
+        stats.collections += 1;
+        if kind == GcKind::Young {
+            stats.young_collections += 1;
+        }
+        stats.freed_objects += collected.objects as u64;
+        stats.freed_bytes += collected.bytes as u64;
+        stats.total_pause += pause;
+        collected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        runtime::{
+            ClassLoader, ClassPath, Console, RuntimeError,
+            descriptor::FieldType,
+            gc::{CollectorKind, GcCause, GcKind, GcOptions},
+            heap::{Array, Heap, Object, instance_bytes},
+            slot::{ObjectRef, Slot},
+        },
+        test_context::TestContext,
+    };
+
+    #[test]
+    fn test_intern() {
+        let heap = Heap::default();
+        let hello = heap.intern("hello".into());
+        assert_eq!(heap.intern("hello".into()), hello);
+        assert_ne!(heap.intern("world".into()), hello);
+        assert_eq!(heap.object_count(), 2);
+        assert!(matches!(heap.get(hello), Object::String(string) if string == "hello"));
+    }
+
+    #[test]
+    fn test_intern_object() {
+        let heap = Heap::default();
+        let hello = heap.intern("hello".into());
+        let copy = heap.alloc(Object::String("hello".into()));
+        assert_eq!(heap.intern_object(copy).unwrap(), hello);
+        // 内容不在池中时放入自身, 之后ldc得到同一个对象
+        let world = heap.alloc(Object::String("world".into()));
+        assert_eq!(heap.intern_object(world).unwrap(), world);
+        assert_eq!(heap.intern("world".into()), world);
+        let array = heap.alloc(Object::Array(Array {
+            component: FieldType::Int,
+            elements: vec![],
+        }));
+        assert!(heap.intern_object(array).is_err());
+    }
+
+    #[test]
+    fn test_mark_sweep() {
+        let heap = Heap::default();
+        let string = |value: &str| heap.alloc(Object::String(value.into()));
+        let array = heap.alloc(Object::Array(
+            Array::new(FieldType::Object("java/lang/Object".into()), 1).unwrap(),
+        ));
+        let element = string("element");
+        heap.array_store(Some(array), 0, Slot::Ref(Some(element)))
+            .unwrap();
+        let garbage = string("garbage");
+        let pinned = string("pinned");
+        let handle = heap.new_global_ref(pinned);
+        let interned = heap.intern("interned".into());
+
+        let collected = heap.collect(GcCause::Explicit, &Console::default(), vec![array]);
+        assert_eq!(collected.objects, 1);
+        assert_eq!(heap.object_count(), 4);
+        assert!(matches!(heap.get(element), Object::String(value) if value == "element"));
+        assert!(matches!(heap.get(interned), Object::String(value) if value == "interned"));
+        // 释放的位置被重新使用
+        assert_eq!(string("reused"), garbage);
+
+        heap.delete_global_ref(handle);
+        let collected = heap.collect(GcCause::Explicit, &Console::default(), vec![]);
+        assert_eq!(collected.objects, 4);
+        assert_eq!(heap.object_count(), 1);
+        let stats = heap.stats();
+        assert_eq!(stats.collections, 2);
+        assert_eq!(stats.freed_objects, 5);
+        assert_eq!(stats.freed_bytes, collected.bytes as u64 + (16 + 7));
+    }
+
+    #[test]
+    fn test_mark_instance_fields() {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let class_loader = ClassLoader::new(class_path);
+        let klass = class_loader.load_class("GarbageNode").unwrap();
+        let next = klass.find_field("next", "LGarbageNode;").unwrap();
+        let heap = Heap::default();
+        let first = heap.alloc_instance(klass.clone());
+        let second = heap.alloc_instance(klass.clone());
+        heap.put_field(Some(first), &next, Slot::Ref(Some(second)))
+            .unwrap();
+        // 互相引用的垃圾
+        let cycle = heap.alloc_instance(klass.clone());
+        heap.put_field(Some(cycle), &next, Slot::Ref(Some(cycle)))
+            .unwrap();
+
+        let collected = heap.collect(GcCause::Explicit, &Console::default(), vec![first]);
+        assert_eq!(collected.objects, 1);
+        assert_eq!(
+            heap.get_field(Some(first), &next).unwrap(),
+            Slot::Ref(Some(second))
+        );
+        assert_eq!(heap.used_bytes(), 2 * instance_bytes(&klass));
+    }
+
+    fn generational_heap(tenuring_threshold: u8) -> Heap {
+        Heap::new(GcOptions {
+            collector: CollectorKind::Generational,
+            tenuring_threshold,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_copy_nursery() {
+        let heap = generational_heap(1);
+        let array = heap.alloc(Object::Array(
+            Array::new(FieldType::Object("java/lang/Object".into()), 1).unwrap(),
+        ));
+        let element = heap.alloc(Object::String("element".into()));
+        heap.array_store(Some(array), 0, Slot::Ref(Some(element)))
+            .unwrap();
+        let garbage = heap.alloc(Object::String("garbage".into()));
+        assert!(heap.is_young(array) && heap.is_young(garbage));
+
+        // 第一次存活留在新生代, 第二次晋升
+        let collected = heap.collect_with(
+            GcKind::Young,
+            GcCause::Explicit,
+            &Console::default(),
+            vec![array],
+        );
+        assert_eq!(collected.objects, 1);
+        assert!(heap.is_young(array) && heap.is_young(element));
+        heap.collect_with(
+            GcKind::Young,
+            GcCause::Explicit,
+            &Console::default(),
+            vec![array],
+        );
+        assert!(!heap.is_young(array) && !heap.is_young(element));
+        assert_eq!(
+            heap.array_load(Some(array), 0).unwrap(),
+            Slot::Ref(Some(element))
+        );
+        assert_eq!(heap.object_count(), 2);
+        let stats = heap.stats();
+        assert_eq!((stats.collections, stats.young_collections), (2, 2));
+    }
+
+    #[test]
+    fn test_card_table() {
+        let heap = generational_heap(0);
+        let holder = heap.alloc(Object::Array(
+            Array::new(FieldType::Object("java/lang/Object".into()), 2).unwrap(),
+        ));
+        heap.collect_with(
+            GcKind::Young,
+            GcCause::Explicit,
+            &Console::default(),
+            vec![holder],
+        );
+        assert!(!heap.is_young(holder));
+
+        // 只有老年代对象引用的新生代对象通过脏卡存活
+        let young = heap.alloc(Object::String("young".into()));
+        heap.array_store(Some(holder), 1, Slot::Ref(Some(young)))
+            .unwrap();
+        let collected = heap.collect_with(
+            GcKind::Young,
+            GcCause::Explicit,
+            &Console::default(),
+            vec![],
+        );
+        assert_eq!(collected.objects, 0);
+        assert!(matches!(heap.get(young), Object::String(value) if value == "young"));
+
+        // 完整回收时老年代对象也会被释放
+        let collected = heap.collect(GcCause::Explicit, &Console::default(), vec![]);
+        assert_eq!(collected.objects, 2);
+        assert_eq!(heap.used_bytes(), 0);
+    }
+
+    #[test]
+    fn test_array_load_store() {
+        let heap = Heap::default();
+        let array = Some(heap.alloc(Object::Array(Array::new(FieldType::Byte, 2).unwrap())));
+        heap.array_store(array, 1, Slot::from(0x1ff_i32)).unwrap();
+        assert_eq!(i32::from(heap.array_load(array, 1).unwrap()), -1);
+        assert_eq!(i32::from(heap.array_load(array, 0).unwrap()), 0);
+        let err = heap.array_load(array, 2).unwrap_err();
+        assert!(matches!(
+            err,
+            RuntimeError::ArrayIndexOutOfBoundsException(msg) if msg == "Index 2 out of bounds for length 2"
+        ));
+        let err = heap.array_store(None, 0, Slot::Bits32(0)).unwrap_err();
+        assert!(matches!(err, RuntimeError::NullPointerException(_)));
+    }
+
+    #[test]
+    fn test_instance_fields() {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let class_loader = ClassLoader::new(class_path);
+        let klass = class_loader.load_class("Fields").unwrap();
+        let heap = Heap::default();
+        let object_ref = heap.alloc_instance(klass.clone());
+        assert!(std::ptr::eq(
+            heap.klass(object_ref).unwrap().as_ref(),
+            klass.as_ref()
+        ));
+        let object = Some(object_ref);
+        // 沿父类链按名称查找字段
+        let field = |name: &str| {
+            let mut current = Some(&klass);
+            while let Some(class) = current {
+                if let Some(field) = class.fields().iter().find(|field| field.name == name) {
+                    return field.clone();
+                }
+                current = class.super_class();
+            }
+            panic!("no field {}", name)
+        };
+        // 新对象的字段都是零值
+        assert_eq!(
+            heap.get_field(object, &field("l")).unwrap(),
+            Slot::Bits64(0)
+        );
+        assert_eq!(
+            heap.get_field(object, &field("next")).unwrap(),
+            Slot::Ref(None)
+        );
+
+        for (name, value, expected) in [
+            ("b", Slot::from(0x1ff), Slot::from(-1)),
+            ("z", Slot::from(3), Slot::from(1)),
+            ("c", Slot::from(-1), Slot::from(0xffff)),
+            ("s", Slot::from(0x18000), Slot::from(-32768)),
+            ("i", Slot::from(-5), Slot::from(-5)),
+            ("f", Slot::from(1.5f32), Slot::from(1.5f32)),
+            ("l", Slot::from(-1i64 << 40), Slot::from(-1i64 << 40)),
+            ("d", Slot::from(0.25), Slot::from(0.25)),
+            ("next", Slot::Ref(object), Slot::Ref(object)),
+            (
+                "o",
+                Slot::Ref(Some(ObjectRef::new(0))),
+                Slot::Ref(Some(ObjectRef::new(0))),
+            ),
+        ] {
+            heap.put_field(object, &field(name), value).unwrap();
+            assert_eq!(
+                heap.get_field(object, &field(name)).unwrap(),
+                expected,
+                "{}",
+                name
+            );
+        }
+        // 相邻字段互不影响
+        assert_eq!(heap.get_field(object, &field("b")).unwrap(), Slot::from(-1));
+        assert_eq!(heap.get_field(object, &field("id")).unwrap(), Slot::from(0));
+
+        let err = heap.get_field(None, &field("i")).unwrap_err();
+        assert!(matches!(
+            err,
+            RuntimeError::NullPointerException(msg) if msg == "Cannot read field \"i\" because value is null"
+        ));
+        let err = heap
+            .put_field(None, &field("i"), Slot::from(1))
+            .unwrap_err();
+        assert!(matches!(err, RuntimeError::NullPointerException(_)));
+    }
+
+    #[test]
+    fn test_deflate_monitors() {
+        let heap = Heap::default();
+        let object = heap.alloc(Object::String("lock".into()));
+        let hash = heap.identity_hash(object);
+        // 重入次数超过轻量级锁的上限后膨胀
+        for _ in 0..300 {
+            assert!(heap.monitor_enter(object, 1));
+        }
+        assert!(heap.is_inflated(object));
+        assert_eq!(heap.monitor_owner(object), Some(1));
+        heap.collect(GcCause::Explicit, &Console::default(), vec![object]);
+        assert!(heap.is_inflated(object));
+        for _ in 0..300 {
+            heap.monitor_exit(object, 1).unwrap();
+        }
+        assert!(matches!(
+            heap.monitor_exit(object, 1),
+            Err(RuntimeError::IllegalMonitorStateException(_))
+        ));
+        // 空闲的重量级锁在GC时收缩, identity hash不变
+        heap.collect(GcCause::Explicit, &Console::default(), vec![object]);
+        assert!(!heap.is_inflated(object));
+        assert_eq!(heap.identity_hash(object), hash);
+        assert!(heap.monitor_enter(object, 2));
+        assert!(!heap.monitor_enter(object, 1));
+        assert_eq!(heap.monitor_owner(object), Some(2));
+    }
+
+    #[test]
+    fn test_identity_hash() {
+        let heap = Heap::default();
+        let first = heap.alloc(Object::String("a".into()));
+        let second = heap.alloc(Object::String("a".into()));
+        let hash = heap.identity_hash(first);
+        assert_ne!(hash, 0);
+        assert!(hash > 0);
+        assert_eq!(heap.identity_hash(first), hash);
+        assert_ne!(heap.identity_hash(second), hash);
+        assert!(heap.klass(first).is_none());
+    }
+}
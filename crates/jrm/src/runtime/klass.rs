@@ -0,0 +1,1101 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use crate::{
+    attributes::{Attribute, BootstrapMethod},
+    constant_pool::{Constant, ConstantPool},
+    instance_klass::{self, ClassAccessFlags, FieldAccessFlags, InstanceKlass, MethodAccessFlags},
+    runtime::{
+        Method, RuntimeConstantPool, RuntimeError,
+        descriptor::FieldType,
+        dispatch::{InlineCaches, select_method},
+        frame::{ExceptionHandler, LineNumber},
+        heap::{Heap, Object},
+        runtime_constant_pool::MethodRef,
+        slot::{ObjectRef, Slot},
+        string::JavaString,
+    },
+};
+
+/// JVMS 5.5 中类的初始化状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitState {
+    /// 已链接, 尚未初始化
+    Linked,
+    /// 正在由该id的线程初始化
+    BeingInitialized(u64),
+    Initialized,
+    Erroneous,
+}
+
+/// 类初始化过程中需要由执行初始化的线程完成的操作
+pub trait ClassInitializer {
+    /// 把ConstantValue属性中的字符串常量放入字符串池
+    fn intern(&mut self, string: JavaString) -> Result<ObjectRef, RuntimeError>;
+    /// 执行类的`<clinit>`
+    fn run_clinit(&mut self, klass: &Arc<Klass>, clinit: Arc<Method>) -> Result<(), RuntimeError>;
+    /// `klass`正在由其他线程初始化, 阻塞到初始化结束; 返回`Parked`时线程挂起, 恢复后重新请求初始化
+    fn wait(&mut self, klass: &Arc<Klass>) -> Result<(), RuntimeError>;
+}
+
+/// 方法区中已链接的类
+pub struct Klass {
+    name: String,
+    access_flags: ClassAccessFlags,
+    super_class: Option<Arc<Klass>>,
+    interfaces: Vec<Arc<Klass>>,
+    // NestHost属性指定的宿主类, 没有时为本类
+    nest_host: String,
+    fields: Vec<Arc<Field>>,
+    methods: Vec<Arc<Method>>,
+    // 父类的vtable在前, 覆盖父类方法的方法复用父类的槽位
+    vtable: Vec<Arc<Method>>,
+    // 实现的所有接口中每个方法选择出的实现, 链接时构建
+    itable: OnceLock<Vec<ItableEntry>>,
+    // 包含父类字段在内的实例数据字节数
+    instance_size: usize,
+    // 引用类型实例字段(包括父类的)的偏移, GC据此遍历实例引用的对象
+    reference_offsets: Vec<usize>,
+    static_slot_count: usize,
+    statics: Mutex<Vec<Slot>>,
+    // ConstantValue为字符串的静态字段在静态字段表中的下标和字符串常量的索引, 初始化时赋值
+    string_constants: Vec<(usize, u16)>,
+    init_state: Mutex<InitState>,
+    // 对应的java.lang.Class对象
+    mirror: OnceLock<ObjectRef>,
+    constant_pool: Arc<RuntimeConstantPool>,
+    kind: KlassKind,
+}
+
+enum KlassKind {
+    /// 由class文件定义的类或接口
+    Instance(InstanceKlass),
+    /// 由类加载器按需创建的数组类, 元素为引用类型时带有元素的类
+    Array {
+        component: FieldType,
+        component_class: Option<Arc<Klass>>,
+    },
+}
+
+/// 一个接口的方法表, 下标为方法的`itable_index`
+struct ItableEntry {
+    interface: Arc<Klass>,
+    methods: Vec<Result<Arc<Method>, RuntimeError>>,
+}
+
+/// 运行时的字段, `offset`为实例数据中的字节偏移, 静态字段为静态字段表中的下标
+#[derive(Debug)]
+pub struct Field {
+    pub name: String,
+    pub descriptor: String,
+    pub access_flags: FieldAccessFlags,
+    pub offset: usize,
+}
+
+impl Field {
+    pub fn is_static(&self) -> bool {
+        self.access_flags.contains(FieldAccessFlags::STATIC)
+    }
+    pub fn is_final(&self) -> bool {
+        self.access_flags.contains(FieldAccessFlags::FINAL)
+    }
+}
+
+impl Debug for Klass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Klass({})", self.name)
+    }
+}
+
+impl Klass {
+    pub fn new(
+        instance_klass: InstanceKlass,
+        super_class: Option<Arc<Klass>>,
+        interfaces: Vec<Arc<Klass>>,
+    ) -> Result<Self, RuntimeError> {
+        let name = instance_klass
+            .this_class_name()
+            .map_err(|err| RuntimeError::ClassFormatError(err.to_string()))?;
+        let raw_constant_pool = instance_klass.get_constant_pool();
+        let constant_pool = Arc::new(RuntimeConstantPool::new(
+            name.clone(),
+            raw_constant_pool.clone(),
+        ));
+
+        let nest_host = instance_klass
+            .attributes()
+            .iter()
+            .find_map(|attr| match attr {
+                Attribute::NestHost(attr) => {
+                    Some(raw_constant_pool.get_class_name(attr.host_class_index()))
+                }
+                _ => None,
+            })
+            .transpose()
+            .map_err(class_format_error(&name))?
+            .unwrap_or_else(|| name.clone());
+        let source_file: Option<Arc<str>> = instance_klass
+            .attributes()
+            .iter()
+            .find_map(|attr| match attr {
+                Attribute::SourceFile(attr) => {
+                    Some(raw_constant_pool.get_utf8_string(attr.sourcefile_index()))
+                }
+                _ => None,
+            })
+            .transpose()
+            .map_err(class_format_error(&name))?
+            .map(Arc::from);
+        let signatures = instance_klass
+            .fields()
+            .iter()
+            .map(|field| field_signature(&raw_constant_pool, field))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(class_format_error(&name))?;
+        let (instance_offsets, instance_size) = layout_fields(
+            instance_klass.fields(),
+            &signatures,
+            super_class
+                .as_ref()
+                .map_or(0, |super_class| super_class.instance_size),
+        );
+        // 准备阶段: 静态字段置零值, 带ConstantValue的静态字段直接赋值
+        let mut statics = vec![];
+        let mut string_constants = vec![];
+        let mut reference_offsets = super_class
+            .as_ref()
+            .map_or(vec![], |super_class| super_class.reference_offsets.clone());
+        let fields = instance_klass
+            .fields()
+            .iter()
+            .zip(signatures)
+            .zip(instance_offsets)
+            .map(|((field, (name, descriptor)), instance_offset)| {
+                if let Some(offset) = instance_offset
+                    && matches!(descriptor.as_bytes()[0], b'L' | b'[')
+                {
+                    reference_offsets.push(offset);
+                }
+                let offset = instance_offset.unwrap_or_else(|| {
+                    let value = match constant_value(&raw_constant_pool, field) {
+                        Some(ConstantValue::Slot(value)) => value,
+                        Some(ConstantValue::String(index)) => {
+                            string_constants.push((statics.len(), index));
+                            Slot::Ref(None)
+                        }
+                        None => Slot::zero_value(&descriptor),
+                    };
+                    statics.push(value);
+                    statics.len() - 1
+                });
+                Arc::new(Field {
+                    name,
+                    descriptor,
+                    access_flags: field.access_flags,
+                    offset,
+                })
+            })
+            .collect();
+        let mut methods: Vec<Method> = instance_klass
+            .methods()
+            .iter()
+            .map(|method| {
+                build_method(
+                    &raw_constant_pool,
+                    constant_pool.clone(),
+                    source_file.clone(),
+                    method,
+                )
+            })
+            .collect::<anyhow::Result<_>>()
+            .map_err(class_format_error(&name))?;
+        let is_interface = instance_klass
+            .access_flags()
+            .contains(ClassAccessFlags::INTERFACE);
+        let super_vtable = match &super_class {
+            Some(super_class) if !is_interface => super_class.vtable.as_slice(),
+            _ => &[],
+        };
+        let vtable_slots = if is_interface {
+            for (index, method) in methods.iter_mut().enumerate() {
+                method.itable_index = Some(index);
+            }
+            vec![]
+        } else {
+            assign_vtable_slots(super_vtable, package_name(&name), &mut methods)
+        };
+        let methods: Vec<Arc<Method>> = methods.into_iter().map(Arc::new).collect();
+        let mut vtable = super_vtable.to_vec();
+        for (slot, position) in vtable_slots {
+            let method = methods[position].clone();
+            match vtable.get_mut(slot) {
+                Some(inherited) => *inherited = method,
+                None => vtable.push(method),
+            }
+        }
+        Ok(Self {
+            name,
+            access_flags: instance_klass.access_flags(),
+            super_class,
+            interfaces,
+            nest_host,
+            fields,
+            methods,
+            vtable,
+            itable: OnceLock::new(),
+            instance_size,
+            reference_offsets,
+            static_slot_count: statics.len(),
+            statics: Mutex::new(statics),
+            string_constants,
+            init_state: Mutex::new(InitState::Linked),
+            mirror: OnceLock::new(),
+            constant_pool,
+            kind: KlassKind::Instance(instance_klass),
+        })
+    }
+    /// JVMS 5.3.3, 数组类继承Object并实现Cloneable和Serializable, 没有自己的字段和方法
+    pub fn new_array(
+        name: String,
+        component_class: Option<Arc<Klass>>,
+        object: Arc<Klass>,
+        interfaces: Vec<Arc<Klass>>,
+    ) -> Result<Self, RuntimeError> {
+        let FieldType::Array(component) = FieldType::parse(&name)? else {
+            return Err(RuntimeError::IllegalState);
+        };
+        // 元素类型为基本类型或public类时, 数组类为public
+        let is_public = component_class
+            .as_ref()
+            .is_none_or(|class| class.access_flags.contains(ClassAccessFlags::PUBLIC));
+        let mut access_flags = ClassAccessFlags::FINAL | ClassAccessFlags::ABSTRACT;
+        if is_public {
+            access_flags |= ClassAccessFlags::PUBLIC;
+        }
+        let constant_pool = Arc::new(RuntimeConstantPool::new(
+            name.clone(),
+            Arc::new(ConstantPool::default()),
+        ));
+        Ok(Self {
+            nest_host: name.clone(),
+            name,
+            access_flags,
+            vtable: object.vtable.clone(),
+            super_class: Some(object),
+            interfaces,
+            fields: vec![],
+            methods: vec![],
+            itable: OnceLock::new(),
+            instance_size: 0,
+            reference_offsets: vec![],
+            static_slot_count: 0,
+            statics: Mutex::new(vec![]),
+            string_constants: vec![],
+            init_state: Mutex::new(InitState::Linked),
+            mirror: OnceLock::new(),
+            constant_pool,
+            kind: KlassKind::Array {
+                component: *component,
+                component_class,
+            },
+        })
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn access_flags(&self) -> ClassAccessFlags {
+        self.access_flags
+    }
+    pub fn is_interface(&self) -> bool {
+        self.access_flags.contains(ClassAccessFlags::INTERFACE)
+    }
+    pub fn package_name(&self) -> &str {
+        package_name(&self.name)
+    }
+    /// JVMS 5.4.4, 同一个nest的类可以互相访问private成员
+    pub fn is_nestmate_of(&self, other: &Klass) -> bool {
+        self.nest_host == other.nest_host
+    }
+    /// Java SE 8及以后的class文件总是视为设置了ACC_SUPER
+    pub fn is_super(&self) -> bool {
+        match &self.kind {
+            KlassKind::Instance(instance_klass) => {
+                instance_klass.major_version() >= 52
+                    || self.access_flags.contains(ClassAccessFlags::SUPER)
+            }
+            KlassKind::Array { .. } => true,
+        }
+    }
+    pub fn super_class(&self) -> Option<&Arc<Klass>> {
+        self.super_class.as_ref()
+    }
+    pub fn interfaces(&self) -> &[Arc<Klass>] {
+        &self.interfaces
+    }
+    pub fn fields(&self) -> &[Arc<Field>] {
+        &self.fields
+    }
+    pub fn methods(&self) -> &[Arc<Method>] {
+        &self.methods
+    }
+    pub fn vtable(&self) -> &[Arc<Method>] {
+        &self.vtable
+    }
+    /// 链接阶段构建itable, 接口没有itable
+    pub fn link(self: &Arc<Self>) {
+        if self.is_interface() {
+            return;
+        }
+        self.itable.get_or_init(|| {
+            let mut interfaces = vec![];
+            let mut current = Some(self);
+            while let Some(klass) = current {
+                klass.collect_superinterfaces(&mut interfaces);
+                current = klass.super_class();
+            }
+            interfaces
+                .into_iter()
+                .map(|interface| {
+                    let methods = interface
+                        .methods
+                        .iter()
+                        .map(|method| {
+                            if method.is_static || method.is_private() {
+                                return Err(RuntimeError::IllegalState);
+                            }
+                            let method_ref = MethodRef {
+                                class: interface.clone(),
+                                klass: interface.clone(),
+                                method: method.clone(),
+                            };
+                            select_method(self, &method_ref)
+                        })
+                        .collect();
+                    ItableEntry { interface, methods }
+                })
+                .collect()
+        });
+    }
+    /// 接口`interface`中下标为`index`的方法在本类中的实现
+    pub fn itable_method(
+        &self,
+        interface: &Klass,
+        index: usize,
+    ) -> Result<Arc<Method>, RuntimeError> {
+        let entry = self
+            .itable
+            .get()
+            .ok_or(RuntimeError::IllegalState)?
+            .iter()
+            .find(|entry| std::ptr::eq(entry.interface.as_ref(), interface))
+            .ok_or_else(|| {
+                RuntimeError::IncompatibleClassChangeError(format!(
+                    "Class {} does not implement the requested interface {}",
+                    self.name,
+                    interface.name()
+                ))
+            })?;
+        entry.methods[index].clone()
+    }
+    pub fn instance_size(&self) -> usize {
+        self.instance_size
+    }
+    pub fn reference_offsets(&self) -> &[usize] {
+        &self.reference_offsets
+    }
+    pub fn static_slot_count(&self) -> usize {
+        self.static_slot_count
+    }
+    pub fn get_static(&self, slot: usize) -> Slot {
+        self.statics.lock().unwrap()[slot].clone()
+    }
+    pub fn set_static(&self, slot: usize, value: Slot) {
+        self.statics.lock().unwrap()[slot] = value;
+    }
+    pub fn mirror(self: &Arc<Self>, heap: &Heap) -> ObjectRef {
+        *self
+            .mirror
+            .get_or_init(|| heap.alloc(Object::Class(self.clone())))
+    }
+    /// 静态字段, `java.lang.Class`对象和ldc得到的对象, 类不会被卸载, 它们都是GC根
+    pub fn push_roots(&self, roots: &mut Vec<ObjectRef>) {
+        roots.extend(
+            self.statics
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|slot| match slot {
+                    Slot::Ref(object_ref) => *object_ref,
+                    _ => None,
+                }),
+        );
+        roots.extend(self.mirror.get().copied());
+        self.constant_pool.push_roots(roots);
+    }
+    pub fn init_state(&self) -> InitState {
+        *self.init_state.lock().unwrap()
+    }
+    /// JVMS 5.5, `initializer`负责放入字符串常量和执行类的`<clinit>`
+    pub fn initialize(
+        self: &Arc<Self>,
+        thread_id: u64,
+        initializer: &mut impl ClassInitializer,
+    ) -> Result<(), RuntimeError> {
+        loop {
+            let mut state = self.init_state.lock().unwrap();
+            match *state {
+                // 等待时不能持有状态锁, 其他线程需要修改状态
+                InitState::BeingInitialized(id) if id != thread_id => {
+                    drop(state);
+                    initializer.wait(self)?;
+                }
+                // 同一线程的递归请求直接返回
+                InitState::BeingInitialized(_) | InitState::Initialized => return Ok(()),
+                InitState::Erroneous => {
+                    return Err(RuntimeError::NoClassDefFoundError(format!(
+                        "Could not initialize class {}",
+                        self.name
+                    )));
+                }
+                InitState::Linked => {
+                    *state = InitState::BeingInitialized(thread_id);
+                    break;
+                }
+            }
+        }
+
+        let result = self
+            .initialize_string_constants(initializer)
+            .and_then(|_| self.initialize_supers(thread_id, initializer))
+            .and_then(|_| match self.find_method("<clinit>", "()V") {
+                Some(clinit) => initializer
+                    .run_clinit(self, clinit)
+                    .map_err(|err| match err {
+                        RuntimeError::ExceptionInInitializerError(_) => err,
+                        err if err.is_error() => err,
+                        err => RuntimeError::ExceptionInInitializerError(err.to_string()),
+                    }),
+                None => Ok(()),
+            });
+        let mut state = self.init_state.lock().unwrap();
+        *state = match result {
+            Ok(_) => InitState::Initialized,
+            // 线程在等待父类或父接口时挂起, 还没有执行<clinit>, 恢复后重新初始化
+            Err(RuntimeError::Parked) => InitState::Linked,
+            Err(_) => InitState::Erroneous,
+        };
+        result
+    }
+    /// JVMS 5.5第6步, 在初始化父类之前为ConstantValue是字符串的静态字段赋值
+    fn initialize_string_constants(
+        &self,
+        initializer: &mut impl ClassInitializer,
+    ) -> Result<(), RuntimeError> {
+        for (slot, index) in &self.string_constants {
+            let string = self.constant_pool.resolve_string(*index)?;
+            let object_ref = initializer.intern(string)?;
+            self.set_static(*slot, Slot::Ref(Some(object_ref)));
+        }
+        Ok(())
+    }
+    /// 类需要先初始化父类, 以及声明了非抽象非静态方法的父接口; 接口不触发父接口的初始化
+    fn initialize_supers(
+        &self,
+        thread_id: u64,
+        initializer: &mut impl ClassInitializer,
+    ) -> Result<(), RuntimeError> {
+        if self.is_interface() {
+            return Ok(());
+        }
+        if let Some(super_class) = &self.super_class {
+            super_class.initialize(thread_id, initializer)?;
+        }
+        let mut interfaces = vec![];
+        self.collect_superinterfaces(&mut interfaces);
+        for interface in interfaces {
+            let declares_default = interface.methods.iter().any(|method| {
+                !method
+                    .access_flags
+                    .intersects(MethodAccessFlags::ABSTRACT | MethodAccessFlags::STATIC)
+            });
+            if declares_default {
+                interface.initialize(thread_id, initializer)?;
+            }
+        }
+        Ok(())
+    }
+    fn collect_superinterfaces(&self, interfaces: &mut Vec<Arc<Klass>>) {
+        for interface in &self.interfaces {
+            if !interfaces.iter().any(|other| Arc::ptr_eq(other, interface)) {
+                interfaces.push(interface.clone());
+                interface.collect_superinterfaces(interfaces);
+            }
+        }
+    }
+    /// 数组类没有class文件
+    pub fn instance_klass(&self) -> Option<&InstanceKlass> {
+        match &self.kind {
+            KlassKind::Instance(instance_klass) => Some(instance_klass),
+            KlassKind::Array { .. } => None,
+        }
+    }
+    /// BootstrapMethods属性中的第`index`个引导方法
+    pub fn bootstrap_method(&self, index: u16) -> Option<&BootstrapMethod> {
+        self.instance_klass()?
+            .attributes()
+            .iter()
+            .find_map(|attr| match attr {
+                Attribute::BootstrapMethods(attr) => attr.bootstrap_methods.get(index as usize),
+                _ => None,
+            })
+    }
+    pub fn is_array(&self) -> bool {
+        matches!(self.kind, KlassKind::Array { .. })
+    }
+    /// 数组类的元素类型
+    pub fn component_type(&self) -> Option<&FieldType> {
+        match &self.kind {
+            KlassKind::Array { component, .. } => Some(component),
+            KlassKind::Instance(_) => None,
+        }
+    }
+    /// 元素为引用类型的数组类的元素类
+    pub fn component_class(&self) -> Option<&Arc<Klass>> {
+        match &self.kind {
+            KlassKind::Array {
+                component_class, ..
+            } => component_class.as_ref(),
+            KlassKind::Instance(_) => None,
+        }
+    }
+    pub fn constant_pool(&self) -> &Arc<RuntimeConstantPool> {
+        &self.constant_pool
+    }
+    /// 只查找本类声明的方法
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Option<Arc<Method>> {
+        self.methods
+            .iter()
+            .find(|method| method.name == name && method.descriptor == descriptor)
+            .cloned()
+    }
+    /// 只查找本类声明的字段
+    pub fn find_field(&self, name: &str, descriptor: &str) -> Option<Arc<Field>> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name && field.descriptor == descriptor)
+            .cloned()
+    }
+    /// 本类是否为`other`本身, 或者继承/实现了`other`.
+    /// 元素为引用类型的数组之间按元素类判断(JVMS checkcast)
+    pub fn is_subclass_of(&self, other: &Klass) -> bool {
+        if std::ptr::eq(self, other) {
+            return true;
+        }
+        if let (Some(component), Some(other_component)) =
+            (self.component_class(), other.component_class())
+        {
+            return component.is_subclass_of(other_component);
+        }
+        if let Some(super_class) = &self.super_class
+            && super_class.is_subclass_of(other)
+        {
+            return true;
+        }
+        self.interfaces
+            .iter()
+            .any(|interface| interface.is_subclass_of(other))
+    }
+    /// JVMS 5.4.3.2, 依次查找本类, 直接父接口, 父类
+    pub fn lookup_field(
+        self: &Arc<Self>,
+        name: &str,
+        descriptor: &str,
+    ) -> Option<(Arc<Klass>, Arc<Field>)> {
+        if let Some(field) = self.find_field(name, descriptor) {
+            return Some((self.clone(), field));
+        }
+        self.interfaces
+            .iter()
+            .find_map(|interface| interface.lookup_field(name, descriptor))
+            .or_else(|| {
+                self.super_class
+                    .as_ref()
+                    .and_then(|super_class| super_class.lookup_field(name, descriptor))
+            })
+    }
+    /// 沿父类链查找方法
+    pub fn lookup_method_in_supers(
+        self: &Arc<Self>,
+        name: &str,
+        descriptor: &str,
+    ) -> Option<(Arc<Klass>, Arc<Method>)> {
+        let mut current = Some(self);
+        while let Some(klass) = current {
+            if let Some(method) = klass.find_method(name, descriptor) {
+                return Some((klass.clone(), method));
+            }
+            current = klass.super_class.as_ref();
+        }
+        None
+    }
+    /// 所有父接口中声明的非private, 非static的同名方法里,
+    /// 不被其他候选方法的声明接口所继承的那些(JVMS 5.4.3.3)
+    pub fn maximally_specific_methods(
+        self: &Arc<Self>,
+        name: &str,
+        descriptor: &str,
+    ) -> Vec<(Arc<Klass>, Arc<Method>)> {
+        let mut candidates: Vec<(Arc<Klass>, Arc<Method>)> = vec![];
+        self.collect_superinterface_methods(name, descriptor, &mut candidates);
+        candidates
+            .iter()
+            .filter(|(klass, _)| {
+                !candidates
+                    .iter()
+                    .any(|(other, _)| !Arc::ptr_eq(klass, other) && other.is_subclass_of(klass))
+            })
+            .cloned()
+            .collect()
+    }
+    fn collect_superinterface_methods(
+        self: &Arc<Self>,
+        name: &str,
+        descriptor: &str,
+        candidates: &mut Vec<(Arc<Klass>, Arc<Method>)>,
+    ) {
+        for interface in &self.interfaces {
+            if let Some(method) = interface.find_method(name, descriptor)
+                && !method
+                    .access_flags
+                    .intersects(MethodAccessFlags::PRIVATE | MethodAccessFlags::STATIC)
+                && !candidates
+                    .iter()
+                    .any(|(klass, _)| Arc::ptr_eq(klass, interface))
+            {
+                candidates.push((interface.clone(), method));
+            }
+            interface.collect_superinterface_methods(name, descriptor, candidates);
+        }
+        if let Some(super_class) = &self.super_class {
+            super_class.collect_superinterface_methods(name, descriptor, candidates);
+        }
+    }
+}
+
+/// 运行时包名, 只有一个类加载器时即为类名中最后一个`/`之前的部分
+fn package_name(class_name: &str) -> &str {
+    class_name
+        .rsplit_once('/')
+        .map_or("", |(package, _)| package)
+}
+
+/// 为类中可被覆盖的方法分配vtable槽位, 返回(槽位, 方法下标)列表.
+/// 覆盖父类方法时复用其所有被覆盖的槽位; 不同包中的包私有方法不被覆盖, 分配新槽位
+fn assign_vtable_slots(
+    super_vtable: &[Arc<Method>],
+    package: &str,
+    methods: &mut [Method],
+) -> Vec<(usize, usize)> {
+    let mut slots = vec![];
+    let mut vtable_len = super_vtable.len();
+    for (position, method) in methods.iter_mut().enumerate() {
+        if method.is_static || method.is_private() || method.name.starts_with('<') {
+            continue;
+        }
+        let mut vtable_index = None;
+        for (slot, inherited) in super_vtable.iter().enumerate() {
+            let overridable = inherited
+                .access_flags
+                .intersects(MethodAccessFlags::PUBLIC | MethodAccessFlags::PROTECTED)
+                || package_name(inherited.constant_pool.class_name()) == package;
+            if inherited.name == method.name
+                && inherited.descriptor == method.descriptor
+                && overridable
+            {
+                vtable_index.get_or_insert(slot);
+                slots.push((slot, position));
+            }
+        }
+        method.vtable_index = Some(vtable_index.unwrap_or_else(|| {
+            slots.push((vtable_len, position));
+            vtable_len += 1;
+            vtable_len - 1
+        }));
+    }
+    slots
+}
+
+/// 字段在实例数据中占用的字节数, 引用为32位的对象下标
+fn field_size(descriptor: &str) -> usize {
+    match descriptor.as_bytes().first() {
+        Some(b'J' | b'D') => 8,
+        Some(b'C' | b'S') => 2,
+        Some(b'B' | b'Z') => 1,
+        _ => 4,
+    }
+}
+
+/// 类文件中的错误转换为ClassFormatError, 消息以类名开头
+fn class_format_error(name: &str) -> impl Fn(anyhow::Error) -> RuntimeError {
+    move |err| RuntimeError::ClassFormatError(format!("{}: {}", name, err))
+}
+
+/// 字段的名称和描述符, 索引来自类文件, 描述符必须合法
+fn field_signature(
+    constant_pool: &ConstantPool,
+    field: &instance_klass::Field,
+) -> anyhow::Result<(String, String)> {
+    let name = constant_pool.get_utf8_string(field.name_index)?;
+    let descriptor = constant_pool.get_utf8_string(field.descriptor_index)?;
+    if FieldType::parse(&descriptor).is_err() {
+        anyhow::bail!("invalid descriptor {:?} of field {}", descriptor, name);
+    }
+    Ok((name, descriptor))
+}
+
+/// 计算实例字段的布局, 返回每个字段的偏移(静态字段为None)和实例数据的大小.
+/// 本类的字段排在父类字段之后, 按大小降序排列, 每个字段按自身大小对齐
+fn layout_fields(
+    fields: &[instance_klass::Field],
+    signatures: &[(String, String)],
+    super_size: usize,
+) -> (Vec<Option<usize>>, usize) {
+    let mut instance_fields: Vec<(usize, usize)> = fields
+        .iter()
+        .zip(signatures)
+        .enumerate()
+        .filter(|(_, (field, _))| !field.access_flags.contains(FieldAccessFlags::STATIC))
+        .map(|(index, (_, (_, descriptor)))| (index, field_size(descriptor)))
+        .collect();
+    instance_fields.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    let mut offsets = vec![None; fields.len()];
+    let mut size = super_size;
+    for (index, field_size) in instance_fields {
+        let offset = size.next_multiple_of(field_size);
+        offsets[index] = Some(offset);
+        size = offset + field_size;
+    }
+    (offsets, size)
+}
+
+/// 静态字段ConstantValue属性的值, 字符串常量需要初始化时放入字符串池
+enum ConstantValue {
+    Slot(Slot),
+    String(u16),
+}
+
+fn constant_value(
+    constant_pool: &ConstantPool,
+    field: &instance_klass::Field,
+) -> Option<ConstantValue> {
+    let index = field.attributes.iter().find_map(|attr| match attr {
+        Attribute::ConstantValue(attr) => Some(attr.constantvalue_index()),
+        _ => None,
+    })?;
+    let value = match constant_pool.0.get(index as usize)? {
+        Constant::Integer(integer) => Slot::Bits32(integer.bytes),
+        Constant::Float(float) => Slot::Bits32(float.bytes),
+        Constant::Long(long) => {
+            Slot::Bits64(((long.high_bytes as u64) << 32) | long.low_bytes as u64)
+        }
+        Constant::Double(double) => {
+            Slot::Bits64(((double.high_bytes as u64) << 32) | double.low_bytes as u64)
+        }
+        Constant::String(_) => return Some(ConstantValue::String(index)),
+        _ => return None,
+    };
+    Some(ConstantValue::Slot(value))
+}
+
+fn build_method(
+    raw_constant_pool: &ConstantPool,
+    constant_pool: Arc<RuntimeConstantPool>,
+    source_file: Option<Arc<str>>,
+    method: &instance_klass::Method,
+) -> anyhow::Result<Method> {
+    let mut result = Method {
+        name: raw_constant_pool.get_utf8_string(method.name_index)?,
+        descriptor: raw_constant_pool.get_utf8_string(method.descriptor_index)?,
+        is_static: method.access_flags.contains(MethodAccessFlags::STATIC),
+        access_flags: method.access_flags,
+        constant_pool,
+        source_file,
+        ..Default::default()
+    };
+    // native和abstract方法没有Code属性
+    for attr in &method.attributes {
+        if let Attribute::Code(code_attr) = attr {
+            result.max_locals = code_attr.max_locals;
+            result.max_stack = code_attr.max_stack;
+            result.code = code_attr.code.clone();
+            result.exception_table = code_attr
+                .exception_table
+                .iter()
+                .map(|entry| ExceptionHandler {
+                    start_pc: entry.start_pc,
+                    end_pc: entry.end_pc,
+                    handler_pc: entry.handler_pc,
+                    catch_type: entry.catch_type,
+                })
+                .collect();
+            for attr in &code_attr.attributes {
+                if let Attribute::LineNumberTable(table) = attr {
+                    result
+                        .line_numbers
+                        .extend(table.line_number_table.iter().map(|entry| LineNumber {
+                            start_pc: entry.start_pc,
+                            line_number: entry.line_number,
+                        }));
+                }
+            }
+        }
+    }
+    result.inline_caches = InlineCaches::new(&result.code);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rstest::{fixture, rstest};
+
+    use crate::{
+        runtime::{
+            ClassLoader, ClassPath, InitState, Klass, Method, RuntimeError,
+            klass::ClassInitializer,
+            slot::{ObjectRef, Slot},
+            string::JavaString,
+        },
+        test_context::TestContext,
+    };
+
+    #[fixture]
+    fn class_loader() -> ClassLoader {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        ClassLoader::new(class_path)
+    }
+
+    /// 只执行`<clinit>`的初始化器, 测试用的类没有字符串常量
+    struct Clinit<F>(F);
+
+    impl<F> ClassInitializer for Clinit<F>
+    where
+        F: FnMut(&Arc<Klass>) -> Result<(), RuntimeError>,
+    {
+        fn intern(&mut self, _: JavaString) -> Result<ObjectRef, RuntimeError> {
+            Err(RuntimeError::IllegalState)
+        }
+        fn run_clinit(&mut self, klass: &Arc<Klass>, _: Arc<Method>) -> Result<(), RuntimeError> {
+            (self.0)(klass)
+        }
+        fn wait(&mut self, _: &Arc<Klass>) -> Result<(), RuntimeError> {
+            Err(RuntimeError::Deadlock)
+        }
+    }
+
+    fn static_value(klass: &Klass, name: &str, descriptor: &str) -> Slot {
+        klass.get_static(klass.find_field(name, descriptor).unwrap().offset)
+    }
+
+    #[rstest]
+    fn test_prepare_statics(class_loader: ClassLoader) {
+        let klass = class_loader.load_class("InitConstants").unwrap();
+        assert_eq!(klass.static_slot_count(), 7);
+        assert_eq!(i32::from(static_value(&klass, "INT", "I")), 42);
+        assert_eq!(i64::from(static_value(&klass, "LONG", "J")), 1 << 40);
+        assert_eq!(f32::from(static_value(&klass, "FLOAT", "F")), 2.5);
+        assert_eq!(f64::from(static_value(&klass, "DOUBLE", "D")), -1.5);
+        assert_eq!(i32::from(static_value(&klass, "counter", "I")), 0);
+        assert!(matches!(
+            static_value(&klass, "ref", "Ljava/lang/Object;"),
+            Slot::Ref(None)
+        ));
+        // 字符串常量在初始化时才放入字符串池
+        assert!(matches!(
+            static_value(&klass, "STRING", "Ljava/lang/String;"),
+            Slot::Ref(None)
+        ));
+        assert_eq!(klass.init_state(), InitState::Linked);
+    }
+
+    #[rstest]
+    fn test_initialize_order(class_loader: ClassLoader) {
+        let child = class_loader.load_class("InitChild").unwrap();
+        let mut order = vec![];
+        child
+            .initialize(
+                0,
+                &mut Clinit(|klass: &Arc<Klass>| {
+                    order.push(klass.name().to_string());
+                    Ok(())
+                }),
+            )
+            .unwrap();
+        // java/lang/Object没有<clinit>, 没有默认方法的InitPlain不会被初始化
+        assert_eq!(order, ["InitParent", "InitDefault", "InitChild"]);
+        let object = class_loader.load_class("java/lang/Object").unwrap();
+        assert_eq!(object.init_state(), InitState::Initialized);
+        let plain = class_loader.load_class("InitPlain").unwrap();
+        assert_eq!(plain.init_state(), InitState::Linked);
+        assert_eq!(child.init_state(), InitState::Initialized);
+
+        // 已初始化的类不再执行<clinit>
+        child
+            .initialize(0, &mut Clinit(|_: &Arc<Klass>| panic!("initialized twice")))
+            .unwrap();
+    }
+
+    #[rstest]
+    fn test_initialize_recursive(class_loader: ClassLoader) {
+        let klass = class_loader.load_class("InitParent").unwrap();
+        let mut count = 0;
+        klass
+            .initialize(
+                0,
+                &mut Clinit(|klass: &Arc<Klass>| {
+                    count += 1;
+                    assert_eq!(klass.init_state(), InitState::BeingInitialized(0));
+                    // 同一线程递归请求初始化时直接返回
+                    klass.initialize(0, &mut Clinit(|_: &Arc<Klass>| panic!("recursive clinit")))
+                }),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[rstest]
+    fn test_initialize_erroneous(class_loader: ClassLoader) {
+        let klass = class_loader.load_class("InitParent").unwrap();
+        let err = klass
+            .initialize(
+                0,
+                &mut Clinit(|_: &Arc<Klass>| Err(RuntimeError::IllegalState)),
+            )
+            .unwrap_err();
+        assert!(matches!(err, RuntimeError::ExceptionInInitializerError(_)));
+        assert_eq!(klass.init_state(), InitState::Erroneous);
+
+        let err = klass
+            .initialize(0, &mut Clinit(|_: &Arc<Klass>| Ok(())))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RuntimeError::NoClassDefFoundError(msg) if msg == "Could not initialize class InitParent"
+        ));
+    }
+
+    fn vtable_index(klass: &Klass, name: &str, descriptor: &str) -> usize {
+        klass
+            .find_method(name, descriptor)
+            .unwrap()
+            .vtable_index
+            .unwrap()
+    }
+
+    #[rstest]
+    fn test_vtable_override(class_loader: ClassLoader) {
+        let base = class_loader.load_class("InvokeBase").unwrap();
+        let derived = class_loader.load_class("InvokeDerived").unwrap();
+        let index = vtable_index(&base, "value", "()I");
+        assert_eq!(vtable_index(&derived, "value", "()I"), index);
+        assert_eq!(derived.vtable().len(), base.vtable().len());
+        assert!(Arc::ptr_eq(
+            &derived.vtable()[index],
+            &derived.find_method("value", "()I").unwrap()
+        ));
+        // private和static方法不进入vtable
+        let secret = base.find_method("secret", "()I").unwrap();
+        assert_eq!(secret.vtable_index, None);
+        let object = class_loader.load_class("java/lang/Object").unwrap();
+        assert!(
+            object
+                .vtable()
+                .iter()
+                .zip(base.vtable())
+                .all(|(inherited, method)| Arc::ptr_eq(inherited, method))
+        );
+    }
+
+    #[rstest]
+    fn test_vtable_package_private(class_loader: ClassLoader) {
+        let a = class_loader.load_class("dispatch/a/A").unwrap();
+        let index = vtable_index(&a, "m", "()I");
+        // 同包的子类复用槽位
+        let b = class_loader.load_class("dispatch/a/B").unwrap();
+        assert_eq!(vtable_index(&b, "m", "()I"), index);
+        // 不同包的子类不能覆盖包私有方法, 分配新槽位
+        let c = class_loader.load_class("dispatch/b/C").unwrap();
+        assert_eq!(vtable_index(&c, "m", "()I"), a.vtable().len());
+        assert!(Arc::ptr_eq(&c.vtable()[index], &a.vtable()[index]));
+        // 经由public的B.m间接覆盖
+        let d = class_loader.load_class("dispatch/b/D").unwrap();
+        assert_eq!(vtable_index(&d, "m", "()I"), index);
+        assert_eq!(d.vtable()[index].constant_pool.class_name(), "dispatch/b/D");
+    }
+
+    #[rstest]
+    fn test_itable(class_loader: ClassLoader) {
+        let iface = class_loader.load_class("InvokeIface").unwrap();
+        let index = |name: &str| {
+            iface
+                .find_method(name, "()I")
+                .unwrap()
+                .itable_index
+                .unwrap()
+        };
+        let implementation = class_loader.load_class("InvokeImpl").unwrap();
+        let hello = implementation
+            .itable_method(&iface, index("hello"))
+            .unwrap();
+        assert_eq!(hello.constant_pool.class_name(), "InvokeIface");
+        let abs = implementation.itable_method(&iface, index("abs")).unwrap();
+        assert_eq!(abs.constant_pool.class_name(), "InvokeImpl");
+        // 抽象类中没有实现
+        let abstract_class = class_loader.load_class("InvokeAbstract").unwrap();
+        assert!(matches!(
+            abstract_class.itable_method(&iface, index("abs")),
+            Err(RuntimeError::AbstractMethodError(_))
+        ));
+        let base = class_loader.load_class("InvokeBase").unwrap();
+        assert!(matches!(
+            base.itable_method(&iface, index("hello")),
+            Err(RuntimeError::IncompatibleClassChangeError(_))
+        ));
+    }
+
+    fn field_offset(klass: &Klass, name: &str) -> usize {
+        klass
+            .fields()
+            .iter()
+            .find(|field| field.name == name)
+            .unwrap()
+            .offset
+    }
+
+    #[rstest]
+    fn test_field_layout(class_loader: ClassLoader) {
+        let object = class_loader.load_class("java/lang/Object").unwrap();
+        assert_eq!(object.instance_size(), 0);
+        // 按大小降序: l, o, id, b
+        let base = class_loader.load_class("FieldsBase").unwrap();
+        let offsets = ["l", "o", "id", "b"].map(|name| field_offset(&base, name));
+        assert_eq!(offsets, [0, 8, 12, 16]);
+        assert_eq!(base.instance_size(), 17);
+        // 父类字段在前, d对齐到8
+        let fields = class_loader.load_class("Fields").unwrap();
+        let offsets =
+            ["d", "i", "f", "next", "c", "s", "z"].map(|name| field_offset(&fields, name));
+        assert_eq!(offsets, [24, 32, 36, 40, 44, 46, 48]);
+        assert_eq!(fields.instance_size(), 49);
+        // 引用类型字段o和next
+        assert_eq!(base.reference_offsets(), [8]);
+        assert_eq!(fields.reference_offsets(), [8, 40]);
+        // 静态字段为静态字段表中的下标
+        assert_eq!(field_offset(&fields, "total"), 1);
+        assert_eq!(i64::from(static_value(&fields, "total", "J")), 0);
+    }
+}
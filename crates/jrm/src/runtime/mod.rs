@@ -1,11 +1,164 @@
+mod bootstrap;
+mod class_loader;
+mod console;
+mod descriptor;
+mod dispatch;
 mod frame;
+mod gc;
+mod heap;
+mod klass;
+mod monitor;
+mod native;
+mod operand;
+mod runtime_constant_pool;
+mod scheduler;
 mod slot;
+mod stack_trace;
+mod string;
 mod thread;
+mod vm;
 
+pub use class_loader::{BootClassPath, ClassLoader, ClassPath, ClassPathEntry};
+pub use console::Console;
 pub use frame::Method;
+pub use gc::{CollectorKind, GcOptions};
+pub use klass::{InitState, Klass};
+pub use runtime_constant_pool::RuntimeConstantPool;
+pub use vm::Vm;
 
-#[derive(Debug, thiserror::Error)]
+use crate::runtime::slot::ObjectRef;
+
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum RuntimeError {
     #[error("illegal state")]
     IllegalState,
+    /// 线程在阻塞点挂起, 交还给调度器. 不是Java异常, 恢复后重新执行当前指令
+    #[error("thread parked")]
+    Parked,
+    /// 所有线程都在等待, 且没有会超时的等待
+    #[error("deadlock: all threads are blocked")]
+    Deadlock,
+    #[error("java.lang.NoClassDefFoundError: {0}")]
+    NoClassDefFoundError(String),
+    #[error("java.lang.ClassFormatError: {0}")]
+    ClassFormatError(String),
+    #[error("java.lang.UnsupportedClassVersionError: {0}")]
+    UnsupportedClassVersionError(String),
+    #[error("java.lang.ClassCircularityError: {0}")]
+    ClassCircularityError(String),
+    #[error("java.lang.IncompatibleClassChangeError: {0}")]
+    IncompatibleClassChangeError(String),
+    #[error("java.lang.NoSuchFieldError: {0}")]
+    NoSuchFieldError(String),
+    #[error("java.lang.NoSuchMethodError: {0}")]
+    NoSuchMethodError(String),
+    #[error("java.lang.AbstractMethodError: {0}")]
+    AbstractMethodError(String),
+    #[error("java.lang.IllegalAccessError: {0}")]
+    IllegalAccessError(String),
+    #[error("java.lang.UnsatisfiedLinkError: {0}")]
+    UnsatisfiedLinkError(String),
+    #[error("java.lang.InstantiationError: {0}")]
+    InstantiationError(String),
+    #[error("java.lang.ExceptionInInitializerError: {0}")]
+    ExceptionInInitializerError(String),
+    #[error("java.lang.ArithmeticException: {0}")]
+    ArithmeticException(String),
+    #[error("java.lang.NullPointerException: {0}")]
+    NullPointerException(String),
+    #[error("java.lang.IndexOutOfBoundsException: {0}")]
+    IndexOutOfBoundsException(String),
+    #[error("java.lang.ArrayIndexOutOfBoundsException: {0}")]
+    ArrayIndexOutOfBoundsException(String),
+    #[error("java.lang.ArrayStoreException: {0}")]
+    ArrayStoreException(String),
+    #[error("java.lang.NegativeArraySizeException: {0}")]
+    NegativeArraySizeException(String),
+    #[error("java.lang.ClassCastException: {0}")]
+    ClassCastException(String),
+    #[error("java.lang.IllegalMonitorStateException: {0}")]
+    IllegalMonitorStateException(String),
+    #[error("java.lang.BootstrapMethodError: {0}")]
+    BootstrapMethodError(String),
+    #[error("java.lang.VerifyError: {0}")]
+    VerifyError(String),
+    #[error("java.lang.StackOverflowError")]
+    StackOverflowError,
+    #[error("java.lang.OutOfMemoryError: {0}")]
+    OutOfMemoryError(String),
+    #[error("java.lang.VerifyError: illegal opcode {opcode:#04x} at pc {pc} in {method}")]
+    IllegalOpcode { opcode: u8, pc: u16, method: String },
+    /// 抛出的Java异常对象, `description`为类名和detailMessage
+    #[error("{description}")]
+    Throwable {
+        object: ObjectRef,
+        description: String,
+        is_error: bool,
+    },
+}
+
+impl RuntimeError {
+    /// 是否为`java.lang.Error`的子类
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Self::NoClassDefFoundError(_)
+                | Self::ClassFormatError(_)
+                | Self::UnsupportedClassVersionError(_)
+                | Self::ClassCircularityError(_)
+                | Self::IncompatibleClassChangeError(_)
+                | Self::NoSuchFieldError(_)
+                | Self::NoSuchMethodError(_)
+                | Self::AbstractMethodError(_)
+                | Self::IllegalAccessError(_)
+                | Self::UnsatisfiedLinkError(_)
+                | Self::InstantiationError(_)
+                | Self::ExceptionInInitializerError(_)
+                | Self::BootstrapMethodError(_)
+                | Self::VerifyError(_)
+                | Self::StackOverflowError
+                | Self::OutOfMemoryError(_)
+                | Self::IllegalOpcode { .. }
+                | Self::Throwable { is_error: true, .. }
+        )
+    }
+    /// 对应的Java异常类, 虚拟机内部错误和已经是Java对象的异常没有
+    pub fn class_name(&self) -> Option<&'static str> {
+        let name = match self {
+            Self::IllegalState | Self::Parked | Self::Deadlock | Self::Throwable { .. } => {
+                return None;
+            }
+            Self::NoClassDefFoundError(_) => "java/lang/NoClassDefFoundError",
+            Self::ClassFormatError(_) => "java/lang/ClassFormatError",
+            Self::UnsupportedClassVersionError(_) => "java/lang/UnsupportedClassVersionError",
+            Self::ClassCircularityError(_) => "java/lang/ClassCircularityError",
+            Self::IncompatibleClassChangeError(_) => "java/lang/IncompatibleClassChangeError",
+            Self::NoSuchFieldError(_) => "java/lang/NoSuchFieldError",
+            Self::NoSuchMethodError(_) => "java/lang/NoSuchMethodError",
+            Self::AbstractMethodError(_) => "java/lang/AbstractMethodError",
+            Self::IllegalAccessError(_) => "java/lang/IllegalAccessError",
+            Self::UnsatisfiedLinkError(_) => "java/lang/UnsatisfiedLinkError",
+            Self::InstantiationError(_) => "java/lang/InstantiationError",
+            Self::ExceptionInInitializerError(_) => "java/lang/ExceptionInInitializerError",
+            Self::ArithmeticException(_) => "java/lang/ArithmeticException",
+            Self::NullPointerException(_) => "java/lang/NullPointerException",
+            Self::IndexOutOfBoundsException(_) => "java/lang/IndexOutOfBoundsException",
+            Self::ArrayIndexOutOfBoundsException(_) => "java/lang/ArrayIndexOutOfBoundsException",
+            Self::ArrayStoreException(_) => "java/lang/ArrayStoreException",
+            Self::NegativeArraySizeException(_) => "java/lang/NegativeArraySizeException",
+            Self::ClassCastException(_) => "java/lang/ClassCastException",
+            Self::IllegalMonitorStateException(_) => "java/lang/IllegalMonitorStateException",
+            Self::BootstrapMethodError(_) => "java/lang/BootstrapMethodError",
+            Self::VerifyError(_) | Self::IllegalOpcode { .. } => "java/lang/VerifyError",
+            Self::StackOverflowError => "java/lang/StackOverflowError",
+            Self::OutOfMemoryError(_) => "java/lang/OutOfMemoryError",
+        };
+        Some(name)
+    }
+    /// 异常的detailMessage, 即显示内容中类名之后的部分
+    pub fn message(&self) -> Option<String> {
+        self.to_string()
+            .split_once(": ")
+            .map(|(_, message)| message.to_string())
+    }
 }
@@ -0,0 +1,339 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::runtime::{RuntimeError, slot::ObjectRef};
+
+/// mark word中锁的位置: 37位表示已膨胀为重量级锁, 38-45位为轻量级锁的重入次数,
+/// 46位以上为持有轻量级锁的线程id加一, 0表示未加锁
+const INFLATED_BIT: u64 = 1 << 37;
+const COUNT_SHIFT: u32 = 38;
+const MAX_THIN_COUNT: u64 = 0xff;
+const OWNER_SHIFT: u32 = 46;
+const MAX_THIN_OWNER: u64 = u64::MAX >> OWNER_SHIFT;
+const LOCK_MASK: u64 = u64::MAX << 37;
+
+fn thin_owner(mark: u64) -> u64 {
+    mark >> OWNER_SHIFT
+}
+
+fn thin_count(mark: u64) -> u64 {
+    (mark >> COUNT_SHIFT) & MAX_THIN_COUNT
+}
+
+fn with_thin_lock(mark: u64, owner: u64, count: u64) -> u64 {
+    mark & !LOCK_MASK | owner << OWNER_SHIFT | count << COUNT_SHIFT
+}
+
+fn not_owner() -> RuntimeError {
+    RuntimeError::IllegalMonitorStateException("current thread is not owner".to_string())
+}
+
+#[derive(Default)]
+struct MonitorState {
+    owner: Option<u64>,
+    /// 持有者进入的次数
+    count: u64,
+}
+
+/// 膨胀后的重量级锁. 线程由调度器切换, 竞争时不在这里等待
+#[derive(Default)]
+pub struct Monitor {
+    state: Mutex<MonitorState>,
+}
+
+impl Monitor {
+    fn try_enter(&self, thread_id: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.owner.is_some_and(|owner| owner != thread_id) {
+            return false;
+        }
+        state.owner = Some(thread_id);
+        state.count += 1;
+        true
+    }
+    fn exit(&self, thread_id: u64) -> Result<(), RuntimeError> {
+        let mut state = self.state.lock().unwrap();
+        if state.owner != Some(thread_id) {
+            return Err(not_owner());
+        }
+        state.count -= 1;
+        if state.count == 0 {
+            state.owner = None;
+        }
+        Ok(())
+    }
+    fn exit_all(&self, thread_id: u64) -> Result<u64, RuntimeError> {
+        let mut state = self.state.lock().unwrap();
+        if state.owner != Some(thread_id) {
+            return Err(not_owner());
+        }
+        state.owner = None;
+        Ok(std::mem::take(&mut state.count))
+    }
+    fn owner(&self) -> Option<u64> {
+        self.state.lock().unwrap().owner
+    }
+}
+
+/// 对象的锁先以轻量级锁记录在对象头中, 出现竞争或重入次数溢出时膨胀为`Monitor`.
+/// 对象头中只记录是否已膨胀, 对象到`Monitor`的映射保存在这里
+#[derive(Default)]
+pub struct Monitors {
+    table: Mutex<HashMap<ObjectRef, Arc<Monitor>>>,
+}
+
+impl Monitors {
+    /// 尝试获得`object_ref`的锁, `mark`为它的mark word. 其他线程持有时返回false
+    pub fn try_enter(&self, mark: &AtomicU64, object_ref: ObjectRef, thread_id: u64) -> bool {
+        let owner = thread_id + 1;
+        loop {
+            let current = mark.load(Ordering::Acquire);
+            if current & INFLATED_BIT != 0 {
+                return self.inflate(mark, object_ref).try_enter(thread_id);
+            }
+            let next = match (thin_owner(current), thin_count(current)) {
+                (0, _) if owner <= MAX_THIN_OWNER => with_thin_lock(current, owner, 0),
+                (current_owner, count) if current_owner == owner && count < MAX_THIN_COUNT => {
+                    with_thin_lock(current, owner, count + 1)
+                }
+                // 其他线程持有, 或者重入次数已满
+                _ => return self.inflate(mark, object_ref).try_enter(thread_id),
+            };
+            if mark
+                .compare_exchange(current, next, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return true;
+            }
+        }
+    }
+    /// 把轻量级锁转移到新的`Monitor`中, 返回对象的重量级锁
+    fn inflate(&self, mark: &AtomicU64, object_ref: ObjectRef) -> Arc<Monitor> {
+        let mut table = self.table.lock().unwrap();
+        loop {
+            let current = mark.load(Ordering::Acquire);
+            if current & INFLATED_BIT != 0 {
+                return table[&object_ref].clone();
+            }
+            let monitor = Arc::new(Monitor::default());
+            {
+                let mut state = monitor.state.lock().unwrap();
+                if thin_owner(current) != 0 {
+                    state.owner = Some(thin_owner(current) - 1);
+                    state.count = thin_count(current) + 1;
+                }
+            }
+            let inflated = current & !LOCK_MASK | INFLATED_BIT;
+            // 持有者同时解锁时重试
+            if mark
+                .compare_exchange(current, inflated, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                table.insert(object_ref, monitor.clone());
+                return monitor;
+            }
+        }
+    }
+    /// 释放一次锁, 当前线程不是持有者时抛出IllegalMonitorStateException
+    pub fn exit(
+        &self,
+        mark: &AtomicU64,
+        object_ref: ObjectRef,
+        thread_id: u64,
+    ) -> Result<(), RuntimeError> {
+        let owner = thread_id + 1;
+        loop {
+            let current = mark.load(Ordering::Acquire);
+            if current & INFLATED_BIT != 0 {
+                let monitor = self.table.lock().unwrap()[&object_ref].clone();
+                return monitor.exit(thread_id);
+            }
+            if thin_owner(current) != owner {
+                return Err(not_owner());
+            }
+            let next = match thin_count(current) {
+                0 => current & !LOCK_MASK,
+                count => with_thin_lock(current, owner, count - 1),
+            };
+            if mark
+                .compare_exchange(current, next, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return Ok(());
+            }
+        }
+    }
+    /// `Object.wait`完全释放锁, 返回进入的次数
+    pub fn exit_all(
+        &self,
+        mark: &AtomicU64,
+        object_ref: ObjectRef,
+        thread_id: u64,
+    ) -> Result<u64, RuntimeError> {
+        let owner = thread_id + 1;
+        loop {
+            let current = mark.load(Ordering::Acquire);
+            if current & INFLATED_BIT != 0 {
+                let monitor = self.table.lock().unwrap()[&object_ref].clone();
+                return monitor.exit_all(thread_id);
+            }
+            if thin_owner(current) != owner {
+                return Err(not_owner());
+            }
+            if mark
+                .compare_exchange(
+                    current,
+                    current & !LOCK_MASK,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+                .is_ok()
+            {
+                return Ok(thin_count(current) + 1);
+            }
+        }
+    }
+    /// 持有锁的线程
+    pub fn owner(&self, mark: &AtomicU64, object_ref: ObjectRef) -> Option<u64> {
+        let current = mark.load(Ordering::Acquire);
+        if current & INFLATED_BIT != 0 {
+            return self.table.lock().unwrap()[&object_ref].owner();
+        }
+        thin_owner(current).checked_sub(1)
+    }
+    pub fn is_inflated(mark: &AtomicU64) -> bool {
+        mark.load(Ordering::Acquire) & INFLATED_BIT != 0
+    }
+    /// GC时收缩空闲的重量级锁. `mark_of`给出存活对象的mark word, 已回收的对象返回None
+    pub fn deflate_idle<'a>(&self, mark_of: impl Fn(ObjectRef) -> Option<&'a AtomicU64>) {
+        self.table
+            .lock()
+            .unwrap()
+            .retain(|object_ref, monitor| match mark_of(*object_ref) {
+                Some(mark) if monitor.owner().is_none() => {
+                    mark.fetch_and(!LOCK_MASK, Ordering::AcqRel);
+                    false
+                }
+                Some(_) => true,
+                None => false,
+            });
+    }
+    /// 重量级锁的数量
+    #[cfg(test)]
+    pub fn inflated_count(&self) -> usize {
+        self.table.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use rstest::rstest;
+
+    use crate::runtime::{
+        RuntimeError,
+        monitor::{MAX_THIN_COUNT, Monitors},
+        slot::ObjectRef,
+    };
+
+    fn enter(monitors: &Monitors, mark: &AtomicU64, thread_id: u64) {
+        assert!(monitors.try_enter(mark, ObjectRef::new(0), thread_id));
+    }
+
+    #[test]
+    fn test_thin_lock() {
+        let monitors = Monitors::default();
+        // identity hash和GC的位不受影响
+        let mark = AtomicU64::new(0x1234);
+        let object = ObjectRef::new(0);
+        enter(&monitors, &mark, 1);
+        enter(&monitors, &mark, 1);
+        assert_eq!(monitors.owner(&mark, object), Some(1));
+        assert_ne!(monitors.owner(&mark, object), Some(2));
+        assert!(matches!(
+            monitors.exit(&mark, object, 2),
+            Err(RuntimeError::IllegalMonitorStateException(_))
+        ));
+        monitors.exit(&mark, object, 1).unwrap();
+        assert_eq!(monitors.owner(&mark, object), Some(1));
+        monitors.exit(&mark, object, 1).unwrap();
+        assert_ne!(monitors.owner(&mark, object), Some(1));
+        assert_eq!(mark.load(Ordering::Acquire), 0x1234);
+        assert!(monitors.exit(&mark, object, 1).is_err());
+        assert_eq!(monitors.inflated_count(), 0);
+    }
+
+    #[test]
+    fn test_inflate_on_recursion() {
+        let monitors = Monitors::default();
+        let mark = AtomicU64::new(0);
+        let object = ObjectRef::new(0);
+        let count = MAX_THIN_COUNT + 2;
+        for _ in 0..count {
+            enter(&monitors, &mark, 3);
+        }
+        assert!(Monitors::is_inflated(&mark));
+        for _ in 0..count {
+            assert_eq!(monitors.owner(&mark, object), Some(3));
+            monitors.exit(&mark, object, 3).unwrap();
+        }
+        assert_ne!(monitors.owner(&mark, object), Some(3));
+        assert!(monitors.exit(&mark, object, 3).is_err());
+        monitors.deflate_idle(|_| Some(&mark));
+        assert!(!Monitors::is_inflated(&mark));
+        assert_eq!(monitors.inflated_count(), 0);
+    }
+
+    #[test]
+    fn test_inflate_on_contention() {
+        let monitors = Monitors::default();
+        let mark = AtomicU64::new(0);
+        let object = ObjectRef::new(0);
+        enter(&monitors, &mark, 1);
+        assert!(!monitors.try_enter(&mark, object, 2));
+        assert!(Monitors::is_inflated(&mark));
+        assert_eq!(monitors.owner(&mark, object), Some(1));
+        // 持有中的锁不能收缩
+        monitors.deflate_idle(|_| Some(&mark));
+        assert!(Monitors::is_inflated(&mark));
+        monitors.exit(&mark, object, 1).unwrap();
+        enter(&monitors, &mark, 2);
+        assert!(!monitors.try_enter(&mark, object, 1));
+        monitors.exit(&mark, object, 2).unwrap();
+        monitors.deflate_idle(|_| Some(&mark));
+        assert!(!Monitors::is_inflated(&mark));
+        // 对象已回收时直接删除
+        for _ in 0..MAX_THIN_COUNT + 2 {
+            enter(&monitors, &mark, 1);
+        }
+        assert_eq!(monitors.inflated_count(), 1);
+        monitors.deflate_idle(|_| None);
+        assert_eq!(monitors.inflated_count(), 0);
+    }
+
+    #[rstest]
+    #[case(3)]
+    #[case(MAX_THIN_COUNT + 3)]
+    fn test_exit_all(#[case] count: u64) {
+        let monitors = Monitors::default();
+        let mark = AtomicU64::new(0x1234);
+        let object = ObjectRef::new(0);
+        assert!(monitors.exit_all(&mark, object, 1).is_err());
+        for _ in 0..count {
+            enter(&monitors, &mark, 1);
+        }
+        assert_eq!(monitors.exit_all(&mark, object, 1).unwrap(), count);
+        assert_eq!(monitors.owner(&mark, object), None);
+        enter(&monitors, &mark, 2);
+        assert!(monitors.exit_all(&mark, object, 1).is_err());
+        assert_eq!(monitors.exit_all(&mark, object, 2).unwrap(), 1);
+        monitors.deflate_idle(|_| Some(&mark));
+        assert_eq!(mark.load(Ordering::Acquire), 0x1234);
+    }
+}
@@ -0,0 +1,33 @@
+use jrm_macro::natives;
+
+use crate::runtime::{
+    RuntimeError,
+    native::{NativeEnv, NativeRegistry},
+    slot::Slot,
+};
+
+pub fn register(registry: &NativeRegistry) {
+    natives! { registry;
+        "java/io/PrintStream" {
+            write "(Ljava/lang/String;Z)V" => print_stream_write,
+        }
+    }
+}
+
+/// 写入`fd`字段对应的控制台, 和JDK的PrintStream一样忽略IO错误
+fn print_stream_write(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = env.this();
+    let value = env.string_arg(1)?;
+    let new_line: i32 = env.arg(2);
+    let klass = env.thread().object_class(this)?;
+    let (_, field) = klass
+        .lookup_field("fd", "I")
+        .ok_or_else(|| RuntimeError::NoSuchFieldError("java/io/PrintStream.fd".to_string()))?;
+    let fd: i32 = env.heap().get_field(Some(this), &field)?.into();
+    let mut bytes = value.to_string().into_bytes();
+    if new_line != 0 {
+        bytes.push(b'\n');
+    }
+    let _ = env.vm().console().write(fd, &bytes);
+    Ok(None)
+}
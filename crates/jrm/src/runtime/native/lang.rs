@@ -0,0 +1,222 @@
+use std::sync::Arc;
+
+use jrm_macro::natives;
+
+use crate::runtime::{
+    Klass, RuntimeError,
+    descriptor::FieldType,
+    gc::GcCause,
+    heap::Object,
+    native::{NativeEnv, NativeRegistry},
+    slot::{ObjectRef, Slot},
+};
+
+pub fn register(registry: &NativeRegistry) {
+    natives! { registry;
+        "java/lang/Object" {
+            getClass "()Ljava/lang/Class;" => object_get_class,
+            hashCode "()I" => object_hash_code,
+            clone "()Ljava/lang/Object;" => object_clone,
+        }
+        "java/lang/Class" {
+            getName "()Ljava/lang/String;" => class_get_name,
+            isInterface "()Z" => class_is_interface,
+            isArray "()Z" => class_is_array,
+        }
+        "java/lang/System" {
+            currentTimeMillis "()J" => system_current_time_millis,
+            nanoTime "()J" => system_nano_time,
+            arraycopy "(Ljava/lang/Object;ILjava/lang/Object;II)V" => system_arraycopy,
+            identityHashCode "(Ljava/lang/Object;)I" => system_identity_hash_code,
+            gc "()V" => system_gc,
+        }
+    }
+}
+
+fn object_get_class(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = env.this();
+    let class = env.thread().object_class(this)?;
+    Ok(Some(Slot::Ref(Some(class.mirror(env.heap())))))
+}
+
+fn object_hash_code(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(env.heap().identity_hash(env.this()))))
+}
+
+/// 浅复制, 数组总是可以复制, 其他对象要实现`Cloneable`
+fn object_clone(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = env.this();
+    let class = env.thread().object_class(this)?;
+    let cloneable = env.vm().class_loader().load_class("java/lang/Cloneable")?;
+    if !class.is_array() && !class.is_subclass_of(&cloneable) {
+        let name = class.name().replace('/', ".");
+        return Err(env.throw_new("java/lang/CloneNotSupportedException", &name));
+    }
+    env.reserve(env.heap().object_bytes(this))?;
+    Ok(Some(Slot::Ref(Some(env.heap().clone_object(this)))))
+}
+
+/// `this`是`java.lang.Class`对象时对应的类
+fn this_class(env: &NativeEnv) -> Result<Arc<Klass>, RuntimeError> {
+    match env.heap().get(env.this()) {
+        Object::Class(klass) => Ok(klass),
+        _ => Err(RuntimeError::IllegalState),
+    }
+}
+
+fn class_get_name(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let name = this_class(env)?.name().replace('/', ".");
+    Ok(Some(env.new_string(name)?))
+}
+
+fn class_is_interface(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(this_class(env)?.is_interface())))
+}
+
+fn class_is_array(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(this_class(env)?.is_array())))
+}
+
+/// 与`Thread.sleep`一致, 从虚拟机启动时的墙上时间开始按虚拟时钟前进
+fn system_current_time_millis(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(env.vm().scheduler().current_time_millis())))
+}
+
+/// 调度器的虚拟时钟, 起点为虚拟机启动
+fn system_nano_time(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(env.vm().scheduler().now() as i64)))
+}
+
+fn system_identity_hash_code(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let hash = match env.arg::<Option<ObjectRef>>(0) {
+        Some(object) => env.heap().identity_hash(object),
+        None => 0,
+    };
+    Ok(Some(Slot::from(hash)))
+}
+
+fn system_gc(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let roots = env.thread().roots();
+    env.vm().collect_garbage(GcCause::Explicit, roots);
+    Ok(None)
+}
+
+/// 异常消息中数组的类型, 如`int[3]`和`object array[3]`
+fn array_type_name(component: &FieldType) -> &'static str {
+    match component {
+        FieldType::Byte => "byte",
+        FieldType::Char => "char",
+        FieldType::Double => "double",
+        FieldType::Float => "float",
+        FieldType::Int => "int",
+        FieldType::Long => "long",
+        FieldType::Short => "short",
+        FieldType::Boolean => "boolean",
+        FieldType::Object(_) | FieldType::Array(_) => "object array",
+    }
+}
+
+/// 先检查类型和范围, 再逐个元素复制. 源和目标相同时按先读后写处理重叠
+fn system_arraycopy(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let src: Option<ObjectRef> = env.arg(0);
+    let src_pos: i32 = env.arg(1);
+    let dest: Option<ObjectRef> = env.arg(2);
+    let dest_pos: i32 = env.arg(3);
+    let length: i32 = env.arg(4);
+    let src = src.ok_or_else(|| RuntimeError::NullPointerException("src is null".to_string()))?;
+    let dest =
+        dest.ok_or_else(|| RuntimeError::NullPointerException("dest is null".to_string()))?;
+    let src_class = env.thread().object_class(src)?;
+    let dest_class = env.thread().object_class(dest)?;
+    let not_array = |kind: &str, class: &Klass| {
+        RuntimeError::ArrayStoreException(format!(
+            "arraycopy: {} type {} is not an array",
+            kind,
+            class.name().replace('/', ".")
+        ))
+    };
+    let src_component = src_class
+        .component_type()
+        .ok_or_else(|| not_array("source", &src_class))?;
+    let dest_component = dest_class
+        .component_type()
+        .ok_or_else(|| not_array("destination", &dest_class))?;
+    if src_component != dest_component
+        && !(src_component.is_reference() && dest_component.is_reference())
+    {
+        return Err(RuntimeError::ArrayStoreException(format!(
+            "arraycopy: type mismatch: can not copy {}[] into {}[]",
+            array_type_name(src_component),
+            array_type_name(dest_component)
+        )));
+    }
+    let src_length = env.heap().array_length(Some(src))?;
+    let dest_length = env.heap().array_length(Some(dest))?;
+    let out_of_bounds =
+        |message: String| Err(RuntimeError::ArrayIndexOutOfBoundsException(message));
+    let src_type = array_type_name(src_component);
+    let dest_type = array_type_name(dest_component);
+    if length < 0 {
+        return out_of_bounds(format!("arraycopy: length {} is negative", length));
+    }
+    if src_pos < 0 {
+        return out_of_bounds(format!(
+            "arraycopy: source index {} out of bounds for {}[{}]",
+            src_pos, src_type, src_length
+        ));
+    }
+    if dest_pos < 0 {
+        return out_of_bounds(format!(
+            "arraycopy: destination index {} out of bounds for {}[{}]",
+            dest_pos, dest_type, dest_length
+        ));
+    }
+    if src_pos as i64 + length as i64 > src_length as i64 {
+        return out_of_bounds(format!(
+            "arraycopy: last source index {} out of bounds for {}[{}]",
+            src_pos as i64 + length as i64,
+            src_type,
+            src_length
+        ));
+    }
+    if dest_pos as i64 + length as i64 > dest_length as i64 {
+        return out_of_bounds(format!(
+            "arraycopy: last destination index {} out of bounds for {}[{}]",
+            dest_pos as i64 + length as i64,
+            dest_type,
+            dest_length
+        ));
+    }
+    let elements = env.heap().with_object(src, |object| match object {
+        Object::Array(array) => {
+            array.elements[src_pos as usize..(src_pos + length) as usize].to_vec()
+        }
+        _ => vec![],
+    });
+    // 元素类型不兼容时逐个检查, 之前的元素已经复制
+    let check_class = match (src_class.component_class(), dest_class.component_class()) {
+        (Some(src_class), Some(dest_class)) if !src_class.is_subclass_of(dest_class) => {
+            Some(dest_class.clone())
+        }
+        _ => None,
+    };
+    for (index, element) in elements.into_iter().enumerate() {
+        if let (Some(dest_class), Slot::Ref(Some(object))) = (&check_class, &element)
+            && !env
+                .thread()
+                .object_class(*object)?
+                .is_subclass_of(dest_class)
+        {
+            return Err(RuntimeError::ArrayStoreException(format!(
+                "arraycopy: element type mismatch: can not cast one of the elements of {}[] to the type of the destination array, {}",
+                src_class
+                    .component_class()
+                    .map_or(String::new(), |class| class.name().replace('/', ".")),
+                dest_class.name().replace('/', ".")
+            )));
+        }
+        env.heap()
+            .array_store(Some(dest), dest_pos + index as i32, element)?;
+    }
+    Ok(None)
+}
@@ -0,0 +1,252 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::runtime::{
+    RuntimeError, Vm,
+    heap::{Heap, Object, string_bytes},
+    slot::{ObjectRef, Slot},
+    string::JavaString,
+    thread::Thread,
+};
+
+mod io;
+mod lang;
+mod number;
+mod string;
+mod thread;
+mod throwable;
+
+/// Rust实现的本地方法, 返回值为None表示void
+pub type NativeMethod = fn(&mut NativeEnv) -> Result<Option<Slot>, RuntimeError>;
+
+/// 本地方法的执行环境, 在调用者的栈帧上执行
+pub struct NativeEnv<'a> {
+    thread: &'a mut Thread,
+    /// 按声明顺序, 实例方法的第一个参数为this. long和double各占一个
+    args: Vec<Slot>,
+}
+
+impl<'a> NativeEnv<'a> {
+    pub fn new(thread: &'a mut Thread, args: Vec<Slot>) -> Self {
+        Self { thread, args }
+    }
+    pub fn thread(&mut self) -> &mut Thread {
+        self.thread
+    }
+    pub fn vm(&self) -> &Arc<Vm> {
+        self.thread.vm()
+    }
+    pub fn heap(&self) -> &Heap {
+        self.thread.vm().heap()
+    }
+    pub fn args(&self) -> &[Slot] {
+        &self.args
+    }
+    /// 本地方法挂起线程时, 参数要放回操作数栈
+    pub fn into_args(self) -> Vec<Slot> {
+        self.args
+    }
+    /// 第`index`个参数
+    pub fn arg<T: From<Slot>>(&self, index: usize) -> T {
+        self.args[index].clone().into()
+    }
+    /// 实例方法的接收者, invokevirtual已经检查过null
+    pub fn this(&self) -> ObjectRef {
+        self.arg::<Option<ObjectRef>>(0).expect("null receiver")
+    }
+    /// `java.lang.String`对象的内容
+    pub fn string(&self, object: ObjectRef) -> Result<JavaString, RuntimeError> {
+        match self.heap().get(object) {
+            Object::String(value) => Ok(value),
+            _ => Err(RuntimeError::IllegalState),
+        }
+    }
+    /// 第`index`个参数是字符串, 为null时抛出NullPointerException
+    pub fn string_arg(&self, index: usize) -> Result<JavaString, RuntimeError> {
+        let object = self.arg::<Option<ObjectRef>>(index).ok_or_else(|| {
+            RuntimeError::NullPointerException(format!("Argument {} is null", index))
+        })?;
+        self.string(object)
+    }
+    /// 分配`size`字节之前的安全点, 已经从操作数栈弹出的参数也作为根
+    pub fn reserve(&self, size: usize) -> Result<(), RuntimeError> {
+        self.vm().reserve(size, || {
+            let mut roots = self.thread.roots();
+            roots.extend(self.args.iter().filter_map(|arg| match arg {
+                Slot::Ref(object_ref) => *object_ref,
+                _ => None,
+            }));
+            roots
+        })
+    }
+    /// 在堆中创建字符串, 作为返回值
+    pub fn new_string(&self, value: impl Into<JavaString>) -> Result<Slot, RuntimeError> {
+        let value = value.into();
+        self.reserve(string_bytes(&value))?;
+        Ok(Slot::Ref(Some(self.heap().alloc(Object::String(value)))))
+    }
+    /// 创建`class_name`的实例作为异常抛出, 用法为`return Err(env.throw_new(..))`
+    pub fn throw_new(&mut self, class_name: &str, message: &str) -> RuntimeError {
+        match self.thread.new_throwable(class_name, Some(message)) {
+            Ok(throwable) => self.thread.thrown(throwable),
+            Err(err) => err,
+        }
+    }
+}
+
+/// 以(类名, 方法名, 描述符)为键的本地方法表, 可以在运行时登记
+pub struct NativeRegistry {
+    methods: RwLock<HashMap<String, NativeMethod>>,
+}
+
+impl Default for NativeRegistry {
+    /// 包括虚拟机内建的本地方法
+    fn default() -> Self {
+        let registry = Self {
+            methods: Default::default(),
+        };
+        throwable::register(&registry);
+        lang::register(&registry);
+        string::register(&registry);
+        number::register(&registry);
+        io::register(&registry);
+        thread::register(&registry);
+        registry
+    }
+}
+
+impl NativeRegistry {
+    /// 已经存在时替换原来的实现
+    pub fn register(&self, class_name: &str, name: &str, descriptor: &str, native: NativeMethod) {
+        self.methods
+            .write()
+            .unwrap()
+            .insert(native_key(class_name, name, descriptor), native);
+    }
+    pub fn lookup(&self, class_name: &str, name: &str, descriptor: &str) -> Option<NativeMethod> {
+        self.methods
+            .read()
+            .unwrap()
+            .get(&native_key(class_name, name, descriptor))
+            .copied()
+    }
+}
+
+fn native_key(class_name: &str, name: &str, descriptor: &str) -> String {
+    format!("{}.{}{}", class_name, name, descriptor)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use jrm_macro::natives;
+    use rstest::{fixture, rstest};
+
+    use crate::{
+        runtime::{
+            ClassLoader, ClassPath, Method, RuntimeError, Vm,
+            heap::Object,
+            native::NativeEnv,
+            slot::{ObjectRef, Slot},
+            thread::Thread,
+        },
+        test_context::TestContext,
+    };
+
+    fn add(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+        let a: i32 = env.arg(0);
+        let b: i32 = env.arg(1);
+        Ok(Some(Slot::from(a + b)))
+    }
+
+    fn scale(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+        let value: i64 = env.arg(0);
+        let factor: f64 = env.arg(1);
+        Ok(Some(Slot::from((value as f64 * factor) as i64)))
+    }
+
+    fn length(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+        let array: Option<ObjectRef> = env.arg(0);
+        Ok(Some(Slot::from(env.heap().array_length(array)?)))
+    }
+
+    fn fail(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+        let message: Option<ObjectRef> = env.arg(0);
+        let Object::String(message) = env.heap().get(message.unwrap()) else {
+            return Err(RuntimeError::IllegalState);
+        };
+        Err(env.throw_new("java/lang/RuntimeException", &message.to_string()))
+    }
+
+    #[fixture]
+    fn thread() -> Thread {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let vm = Vm::new(ClassLoader::new(class_path));
+        natives! { vm.natives();
+            "Natives" {
+                add "(II)I" => add,
+                scale "(JD)J" => scale,
+                length "([Ljava/lang/Object;)I" => length,
+                fail "(Ljava/lang/String;)V" => fail,
+            }
+        }
+        let klass = vm.class_loader().load_class("Natives").unwrap();
+        let method = Method {
+            name: "test".to_string(),
+            constant_pool: klass.constant_pool().clone(),
+            ..Default::default()
+        };
+        Thread::new(0, Arc::new(method), Arc::new(vm))
+    }
+
+    fn call(thread: &mut Thread, name: &str) -> Result<Option<Slot>, RuntimeError> {
+        let klass = thread.vm().class_loader().load_class("Natives").unwrap();
+        let method = klass.find_method(name, "()I").or_else(|| {
+            klass
+                .methods()
+                .iter()
+                .find(|method| method.name == name)
+                .cloned()
+        });
+        thread.call(method.unwrap(), vec![])
+    }
+
+    #[rstest]
+    #[case("callAdd", Slot::from(42))]
+    #[case("callScale", Slot::from(1_500_000_000_000i64))]
+    #[case("callLength", Slot::from(7))]
+    #[case("catchFail", Slot::from(1))]
+    fn test_call_native(mut thread: Thread, #[case] name: &str, #[case] expected: Slot) {
+        assert_eq!(call(&mut thread, name).unwrap(), Some(expected));
+    }
+
+    #[rstest]
+    #[case("uncaughtFail", "java.lang.RuntimeException: boom")]
+    #[case("callMissing", "java.lang.UnsatisfiedLinkError: Natives.missing()I")]
+    fn test_native_throw(mut thread: Thread, #[case] name: &str, #[case] expected: &str) {
+        let err = call(&mut thread, name).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry = super::NativeRegistry::default();
+        assert!(
+            registry
+                .lookup("java/lang/Throwable", "getStackTraceDepth", "()I")
+                .is_some()
+        );
+        assert!(registry.lookup("Natives", "add", "(II)I").is_none());
+        natives! { registry;
+            "Natives" {
+                add "(II)I" => add,
+            }
+        }
+        assert!(registry.lookup("Natives", "add", "(II)I").is_some());
+        assert!(registry.lookup("Natives", "add", "(JJ)J").is_none());
+    }
+}
@@ -0,0 +1,284 @@
+use jrm_macro::natives;
+
+use crate::runtime::{
+    RuntimeError,
+    native::{NativeEnv, NativeRegistry},
+    slot::Slot,
+};
+
+pub fn register(registry: &NativeRegistry) {
+    natives! { registry;
+        "java/lang/Double" {
+            parseDouble "(Ljava/lang/String;)D" => double_parse_double,
+            toString "(D)Ljava/lang/String;" => double_to_string,
+            doubleToRawLongBits "(D)J" => double_to_raw_long_bits,
+            longBitsToDouble "(J)D" => double_long_bits_to_double,
+        }
+        "java/lang/Float" {
+            toString "(F)Ljava/lang/String;" => float_to_string,
+            floatToRawIntBits "(F)I" => float_to_raw_int_bits,
+            intBitsToFloat "(I)F" => float_int_bits_to_float,
+        }
+        "java/lang/Math" {
+            sqrt "(D)D" => math_sqrt,
+            cbrt "(D)D" => math_cbrt,
+            pow "(DD)D" => math_pow,
+            exp "(D)D" => math_exp,
+            log "(D)D" => math_log,
+            log10 "(D)D" => math_log10,
+            sin "(D)D" => math_sin,
+            cos "(D)D" => math_cos,
+            tan "(D)D" => math_tan,
+            asin "(D)D" => math_asin,
+            acos "(D)D" => math_acos,
+            atan "(D)D" => math_atan,
+            atan2 "(DD)D" => math_atan2,
+            hypot "(DD)D" => math_hypot,
+            floor "(D)D" => math_floor,
+            ceil "(D)D" => math_ceil,
+            rint "(D)D" => math_rint,
+        }
+    }
+}
+
+/// `Double.toString`的格式: 10^-3 <= |d| < 10^7时为小数, 否则为科学计数法如`1.0E10`.
+/// `sci`为Rust `{:e}`格式的最短表示
+fn java_decimal(negative: bool, sci: &str) -> String {
+    let (mantissa, exponent) = sci.split_once('e').unwrap_or((sci, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let digits: String = mantissa.chars().filter(|ch| *ch != '.').collect();
+    let mut result = String::new();
+    if negative {
+        result.push('-');
+    }
+    if (-3..7).contains(&exponent) {
+        if exponent >= 0 {
+            let point = exponent as usize + 1;
+            if digits.len() <= point {
+                result.push_str(&digits);
+                result.push_str(&"0".repeat(point - digits.len()));
+                result.push_str(".0");
+            } else {
+                result.push_str(&digits[..point]);
+                result.push('.');
+                result.push_str(&digits[point..]);
+            }
+        } else {
+            result.push_str("0.");
+            result.push_str(&"0".repeat((-exponent - 1) as usize));
+            result.push_str(&digits);
+        }
+    } else {
+        result.push_str(&digits[..1]);
+        result.push('.');
+        result.push_str(if digits.len() > 1 { &digits[1..] } else { "0" });
+        result.push('E');
+        result.push_str(&exponent.to_string());
+    }
+    result
+}
+
+pub fn java_double_to_string(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if value == 0.0 {
+        return if value.is_sign_negative() {
+            "-0.0"
+        } else {
+            "0.0"
+        }
+        .to_string();
+    }
+    java_decimal(value < 0.0, &format!("{:e}", value.abs()))
+}
+
+pub fn java_float_to_string(value: f32) -> String {
+    if value.is_nan() || value.is_infinite() || value == 0.0 {
+        return java_double_to_string(value as f64);
+    }
+    java_decimal(value < 0.0, &format!("{:e}", value.abs()))
+}
+
+/// `Double.parseDouble`: 忽略首尾空白, 允许类型后缀`d`和`f`. 不支持十六进制
+pub fn java_parse_double(value: &str) -> Option<f64> {
+    let value = value.trim_matches(|ch| ch <= ' ');
+    let (negative, unsigned) = match value.as_bytes().first() {
+        Some(b'-') => (true, &value[1..]),
+        Some(b'+') => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let magnitude = match unsigned {
+        "NaN" => f64::NAN,
+        "Infinity" => f64::INFINITY,
+        _ => {
+            let number = unsigned
+                .strip_suffix(['d', 'D', 'f', 'F'])
+                .unwrap_or(unsigned);
+            // Rust还接受inf和nan等写法
+            if number.is_empty()
+                || !number
+                    .chars()
+                    .all(|ch| ch.is_ascii_digit() || matches!(ch, '.' | 'e' | 'E' | '+' | '-'))
+            {
+                return None;
+            }
+            number.parse().ok()?
+        }
+    };
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+fn double_parse_double(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let value = env.string_arg(0)?.to_string();
+    match java_parse_double(&value) {
+        Some(parsed) => Ok(Some(Slot::from(parsed))),
+        None => {
+            let message = if value.trim_matches(|ch| ch <= ' ').is_empty() {
+                "empty String".to_string()
+            } else {
+                format!("For input string: \"{}\"", value)
+            };
+            Err(env.throw_new("java/lang/NumberFormatException", &message))
+        }
+    }
+}
+
+fn double_to_string(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let value: f64 = env.arg(0);
+    Ok(Some(env.new_string(java_double_to_string(value))?))
+}
+
+fn double_to_raw_long_bits(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let value: f64 = env.arg(0);
+    Ok(Some(Slot::from(value.to_bits() as i64)))
+}
+
+fn double_long_bits_to_double(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let bits: i64 = env.arg(0);
+    Ok(Some(Slot::from(f64::from_bits(bits as u64))))
+}
+
+fn float_to_string(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let value: f32 = env.arg(0);
+    Ok(Some(env.new_string(java_float_to_string(value))?))
+}
+
+fn float_to_raw_int_bits(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let value: f32 = env.arg(0);
+    Ok(Some(Slot::from(value.to_bits() as i32)))
+}
+
+fn float_int_bits_to_float(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let bits: i32 = env.arg(0);
+    Ok(Some(Slot::from(f32::from_bits(bits as u32))))
+}
+
+/// 单参数的`Math`方法
+macro_rules! math_unary {
+    ($($name:ident => $function:expr),* $(,)?) => {
+        $(
+            fn $name(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+                let a: f64 = env.arg(0);
+                Ok(Some(Slot::from($function(a))))
+            }
+        )*
+    };
+}
+
+math_unary! {
+    math_sqrt => f64::sqrt,
+    math_cbrt => f64::cbrt,
+    math_exp => f64::exp,
+    math_log => f64::ln,
+    math_log10 => f64::log10,
+    math_sin => f64::sin,
+    math_cos => f64::cos,
+    math_tan => f64::tan,
+    math_asin => f64::asin,
+    math_acos => f64::acos,
+    math_atan => f64::atan,
+    math_floor => f64::floor,
+    math_ceil => f64::ceil,
+    math_rint => f64::round_ties_even,
+}
+
+/// Java规定`pow(1.0, NaN)`和`pow(±1.0, ±Infinity)`为NaN, Rust的`powf`为1.0
+fn math_pow(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let a: f64 = env.arg(0);
+    let b: f64 = env.arg(1);
+    let result = if b.is_nan() || (a.abs() == 1.0 && b.is_infinite()) {
+        f64::NAN
+    } else {
+        a.powf(b)
+    };
+    Ok(Some(Slot::from(result)))
+}
+
+fn math_atan2(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let y: f64 = env.arg(0);
+    let x: f64 = env.arg(1);
+    Ok(Some(Slot::from(y.atan2(x))))
+}
+
+fn math_hypot(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let x: f64 = env.arg(0);
+    let y: f64 = env.arg(1);
+    Ok(Some(Slot::from(x.hypot(y))))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::runtime::native::number::{
+        java_double_to_string, java_float_to_string, java_parse_double,
+    };
+
+    #[rstest]
+    #[case(1.0, "1.0")]
+    #[case(-1.5, "-1.5")]
+    #[case(0.0, "0.0")]
+    #[case(-0.0, "-0.0")]
+    #[case(100.0, "100.0")]
+    #[case(0.1, "0.1")]
+    #[case(0.001, "0.001")]
+    #[case(0.0001, "1.0E-4")]
+    #[case(1234567.0, "1234567.0")]
+    #[case(1e7, "1.0E7")]
+    #[case(12345678.9, "1.23456789E7")]
+    #[case(1.0 / 3.0, "0.3333333333333333")]
+    #[case(f64::MAX, "1.7976931348623157E308")]
+    #[case(f64::MIN_POSITIVE, "2.2250738585072014E-308")]
+    #[case(f64::NAN, "NaN")]
+    #[case(f64::NEG_INFINITY, "-Infinity")]
+    fn test_double_to_string(#[case] value: f64, #[case] expected: &str) {
+        assert_eq!(java_double_to_string(value), expected);
+    }
+
+    #[rstest]
+    #[case(1.1, "1.1")]
+    #[case(0.1, "0.1")]
+    #[case(3.4028235e38, "3.4028235E38")]
+    #[case(1e-5, "1.0E-5")]
+    fn test_float_to_string(#[case] value: f32, #[case] expected: &str) {
+        assert_eq!(java_float_to_string(value), expected);
+    }
+
+    #[rstest]
+    #[case("1.5", Some(1.5))]
+    #[case("  -2e3 ", Some(-2000.0))]
+    #[case("10d", Some(10.0))]
+    #[case(".5f", Some(0.5))]
+    #[case("-Infinity", Some(f64::NEG_INFINITY))]
+    #[case("inf", None)]
+    #[case("1.2.3", None)]
+    #[case("", None)]
+    #[case("abc", None)]
+    fn test_parse_double(#[case] value: &str, #[case] expected: Option<f64>) {
+        assert_eq!(java_parse_double(value), expected);
+    }
+}
@@ -0,0 +1,409 @@
+use jrm_macro::natives;
+
+use crate::runtime::{
+    RuntimeError,
+    heap::Object,
+    native::{NativeEnv, NativeRegistry},
+    slot::{ObjectRef, Slot},
+    string::JavaString,
+};
+
+pub fn register(registry: &NativeRegistry) {
+    natives! { registry;
+        "java/lang/String" {
+            length "()I" => string_length,
+            charAt "(I)C" => string_char_at,
+            getChars "(II[CI)V" => string_get_chars,
+            equals "(Ljava/lang/Object;)Z" => string_equals,
+            equalsIgnoreCase "(Ljava/lang/String;)Z" => string_equals_ignore_case,
+            hashCode "()I" => string_hash_code,
+            compareTo "(Ljava/lang/String;)I" => string_compare_to,
+            indexOf "(I)I" => string_index_of_char,
+            indexOf "(Ljava/lang/String;)I" => string_index_of,
+            startsWith "(Ljava/lang/String;)Z" => string_starts_with,
+            endsWith "(Ljava/lang/String;)Z" => string_ends_with,
+            substring "(II)Ljava/lang/String;" => string_substring,
+            concat "(Ljava/lang/String;)Ljava/lang/String;" => string_concat,
+            trim "()Ljava/lang/String;" => string_trim,
+            toUpperCase "()Ljava/lang/String;" => string_to_upper_case,
+            toLowerCase "()Ljava/lang/String;" => string_to_lower_case,
+            intern "()Ljava/lang/String;" => string_intern,
+            valueOf "([CII)Ljava/lang/String;" => string_value_of_chars,
+        }
+        "java/lang/Character" {
+            isDigit "(C)Z" => character_is_digit,
+            isLetter "(C)Z" => character_is_letter,
+            isWhitespace "(C)Z" => character_is_whitespace,
+            isUpperCase "(C)Z" => character_is_upper_case,
+            isLowerCase "(C)Z" => character_is_lower_case,
+            toUpperCase "(C)C" => character_to_upper_case,
+            toLowerCase "(C)C" => character_to_lower_case,
+        }
+    }
+}
+
+fn this_string(env: &NativeEnv) -> Result<JavaString, RuntimeError> {
+    env.string(env.this())
+}
+
+fn string_index_out_of_bounds(env: &mut NativeEnv, message: String) -> RuntimeError {
+    env.throw_new("java/lang/StringIndexOutOfBoundsException", &message)
+}
+
+fn string_length(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(this_string(env)?.len() as i32)))
+}
+
+fn string_char_at(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let index: i32 = env.arg(1);
+    let this = this_string(env)?;
+    match usize::try_from(index)
+        .ok()
+        .and_then(|index| this.char_at(index))
+    {
+        Some(unit) => Ok(Some(Slot::Bits32(unit as u32))),
+        None => Err(string_index_out_of_bounds(
+            env,
+            format!("Index {} out of bounds for length {}", index, this.len()),
+        )),
+    }
+}
+
+/// `0 <= begin <= end <= length`时返回代码单元的范围
+fn check_range(begin: i32, end: i32, length: usize) -> Option<(usize, usize)> {
+    let begin = usize::try_from(begin).ok()?;
+    let end = usize::try_from(end).ok()?;
+    (begin <= end && end <= length).then_some((begin, end))
+}
+
+fn string_get_chars(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let src_begin: i32 = env.arg(1);
+    let src_end: i32 = env.arg(2);
+    let dst: Option<ObjectRef> = env.arg(3);
+    let dst_begin: i32 = env.arg(4);
+    let units = this_string(env)?.to_utf16();
+    let Some((begin, end)) = check_range(src_begin, src_end, units.len()) else {
+        return Err(string_index_out_of_bounds(
+            env,
+            format!(
+                "begin {}, end {}, length {}",
+                src_begin,
+                src_end,
+                units.len()
+            ),
+        ));
+    };
+    let dst_length = env.heap().array_length(dst)?;
+    let count = (end - begin) as i32;
+    if dst_begin < 0 || dst_begin as i64 + count as i64 > dst_length as i64 {
+        return Err(RuntimeError::ArrayIndexOutOfBoundsException(format!(
+            "Range [{}, {} + {}) out of bounds for length {}",
+            dst_begin, dst_begin, count, dst_length
+        )));
+    }
+    for (index, unit) in units[begin..end].iter().enumerate() {
+        env.heap()
+            .array_store(dst, dst_begin + index as i32, Slot::Bits32(*unit as u32))?;
+    }
+    Ok(None)
+}
+
+/// 同一个对象或者内容相同
+fn string_equals(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = env.this();
+    let equals = match env.arg::<Option<ObjectRef>>(1) {
+        Some(other) if other == this => true,
+        Some(other) => {
+            let this = this_string(env)?;
+            env.heap().with_object(
+                other,
+                |object| matches!(object, Object::String(other) if *other == this),
+            )
+        }
+        None => false,
+    };
+    Ok(Some(Slot::from(equals)))
+}
+
+fn string_equals_ignore_case(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = this_string(env)?;
+    let equals = match env.arg::<Option<ObjectRef>>(1) {
+        Some(other) => {
+            let other = env.string(other)?;
+            this.len() == other.len()
+                && this.units().zip(other.units()).all(|(a, b)| {
+                    a == b
+                        || to_upper_case(a) == to_upper_case(b)
+                        || to_lower_case(a) == to_lower_case(b)
+                })
+        }
+        None => false,
+    };
+    Ok(Some(Slot::from(equals)))
+}
+
+fn string_hash_code(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(this_string(env)?.hash_code())))
+}
+
+/// 第一个不同的代码单元之差, 一个是另一个的前缀时为长度之差
+fn string_compare_to(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = this_string(env)?;
+    let other = env.string_arg(1)?;
+    let difference = this
+        .units()
+        .zip(other.units())
+        .find(|(a, b)| a != b)
+        .map_or(this.len() as i32 - other.len() as i32, |(a, b)| {
+            a as i32 - b as i32
+        });
+    Ok(Some(Slot::from(difference)))
+}
+
+/// `ch`是代码点, 增补字符按代理对查找
+fn string_index_of_char(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let ch: i32 = env.arg(1);
+    let units = this_string(env)?.to_utf16();
+    let needle = match u32::try_from(ch) {
+        Ok(code_point @ 0..=0xffff) => vec![code_point as u16],
+        Ok(code_point) => match char::from_u32(code_point) {
+            Some(ch) => ch.encode_utf16(&mut [0; 2]).to_vec(),
+            None => return Ok(Some(Slot::from(-1))),
+        },
+        Err(_) => return Ok(Some(Slot::from(-1))),
+    };
+    Ok(Some(Slot::from(index_of(&units, &needle))))
+}
+
+fn string_index_of(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let units = this_string(env)?.to_utf16();
+    let needle = env.string_arg(1)?.to_utf16();
+    Ok(Some(Slot::from(index_of(&units, &needle))))
+}
+
+fn index_of(units: &[u16], needle: &[u16]) -> i32 {
+    if needle.is_empty() {
+        return 0;
+    }
+    units
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map_or(-1, |index| index as i32)
+}
+
+fn string_starts_with(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = this_string(env)?.to_utf16();
+    let prefix = env.string_arg(1)?.to_utf16();
+    Ok(Some(Slot::from(this.starts_with(&prefix))))
+}
+
+fn string_ends_with(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = this_string(env)?.to_utf16();
+    let suffix = env.string_arg(1)?.to_utf16();
+    Ok(Some(Slot::from(this.ends_with(&suffix))))
+}
+
+fn string_substring(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let begin_index: i32 = env.arg(1);
+    let end_index: i32 = env.arg(2);
+    let units = this_string(env)?.to_utf16();
+    let Some((begin, end)) = check_range(begin_index, end_index, units.len()) else {
+        return Err(string_index_out_of_bounds(
+            env,
+            format!(
+                "begin {}, end {}, length {}",
+                begin_index,
+                end_index,
+                units.len()
+            ),
+        ));
+    };
+    if begin == 0 && end == units.len() {
+        return Ok(Some(Slot::Ref(Some(env.this()))));
+    }
+    Ok(Some(
+        env.new_string(JavaString::from_utf16(&units[begin..end]))?,
+    ))
+}
+
+fn string_concat(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = this_string(env)?;
+    let other = env.string_arg(1)?;
+    if other.is_empty() {
+        return Ok(Some(Slot::Ref(Some(env.this()))));
+    }
+    let units: Vec<u16> = this.units().chain(other.units()).collect();
+    Ok(Some(env.new_string(JavaString::from_utf16(&units))?))
+}
+
+/// 去掉首尾不大于空格的字符, 没有变化时返回自身
+fn string_trim(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let units = this_string(env)?.to_utf16();
+    let begin = units
+        .iter()
+        .position(|unit| *unit > 0x20)
+        .unwrap_or(units.len());
+    let end = units
+        .iter()
+        .rposition(|unit| *unit > 0x20)
+        .map_or(begin, |index| index + 1);
+    if begin == 0 && end == units.len() {
+        return Ok(Some(Slot::Ref(Some(env.this()))));
+    }
+    Ok(Some(
+        env.new_string(JavaString::from_utf16(&units[begin..end]))?,
+    ))
+}
+
+/// 按代码点转换, 结果可能变长(如`ß`转为`SS`). 单独的代理项不变
+fn map_string<I: Iterator<Item = char>>(string: &JavaString, map: fn(char) -> I) -> JavaString {
+    let mut units = vec![];
+    for ch in char::decode_utf16(string.units()) {
+        match ch {
+            Ok(ch) => map(ch).for_each(|ch| units.extend(ch.encode_utf16(&mut [0; 2]).iter())),
+            Err(err) => units.push(err.unpaired_surrogate()),
+        }
+    }
+    JavaString::from_utf16(&units)
+}
+
+fn string_to_upper_case(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = this_string(env)?;
+    Ok(Some(env.new_string(map_string(&this, char::to_uppercase))?))
+}
+
+fn string_to_lower_case(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = this_string(env)?;
+    Ok(Some(env.new_string(map_string(&this, char::to_lowercase))?))
+}
+
+fn string_intern(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let interned = env.heap().intern_object(env.this())?;
+    Ok(Some(Slot::Ref(Some(interned))))
+}
+
+fn string_value_of_chars(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let data: Option<ObjectRef> = env.arg(0);
+    let offset: i32 = env.arg(1);
+    let count: i32 = env.arg(2);
+    let length = env.heap().array_length(data)?;
+    if offset < 0 || count < 0 || offset as i64 + count as i64 > length as i64 {
+        return Err(string_index_out_of_bounds(
+            env,
+            format!("offset {}, count {}, length {}", offset, count, length),
+        ));
+    }
+    let units: Vec<u16> = env
+        .heap()
+        .with_object(data.unwrap(), |object| match object {
+            Object::Array(array) => array.elements[offset as usize..(offset + count) as usize]
+                .iter()
+                .map(|unit| i32::from(unit.clone()) as u16)
+                .collect(),
+            _ => vec![],
+        });
+    Ok(Some(env.new_string(JavaString::from_utf16(&units))?))
+}
+
+/// 按代码单元转换, 结果不是单个BMP字符时保持不变
+fn to_upper_case(unit: u16) -> u16 {
+    map_case(unit, char::to_uppercase)
+}
+
+fn to_lower_case(unit: u16) -> u16 {
+    map_case(unit, char::to_lowercase)
+}
+
+fn map_case<I: Iterator<Item = char>>(unit: u16, map: impl FnOnce(char) -> I) -> u16 {
+    let Some(ch) = char::from_u32(unit as u32) else {
+        return unit;
+    };
+    let mut mapped = map(ch);
+    match (mapped.next(), mapped.next()) {
+        (Some(mapped), None) if (mapped as u32) <= 0xffff => mapped as u16,
+        _ => unit,
+    }
+}
+
+/// char参数, 不是有效字符(代理项)时为None
+fn char_arg(env: &NativeEnv) -> Option<char> {
+    char::from_u32(env.arg::<i32>(0) as u16 as u32)
+}
+
+fn character_is_digit(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(
+        char_arg(env).is_some_and(char::is_numeric),
+    )))
+}
+
+fn character_is_letter(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(
+        char_arg(env).is_some_and(char::is_alphabetic),
+    )))
+}
+
+/// Java的空白不包括不换行空格
+fn character_is_whitespace(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let is_whitespace = char_arg(env).is_some_and(|ch| {
+        ch.is_whitespace() && !matches!(ch, '\u{00a0}' | '\u{2007}' | '\u{202f}' | '\u{0085}')
+            || matches!(ch, '\u{001c}'..='\u{001f}')
+    });
+    Ok(Some(Slot::from(is_whitespace)))
+}
+
+fn character_is_upper_case(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(
+        char_arg(env).is_some_and(char::is_uppercase),
+    )))
+}
+
+fn character_is_lower_case(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    Ok(Some(Slot::from(
+        char_arg(env).is_some_and(char::is_lowercase),
+    )))
+}
+
+fn character_to_upper_case(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let unit = env.arg::<i32>(0) as u16;
+    Ok(Some(Slot::Bits32(to_upper_case(unit) as u32)))
+}
+
+fn character_to_lower_case(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let unit = env.arg::<i32>(0) as u16;
+    Ok(Some(Slot::Bits32(to_lower_case(unit) as u32)))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::runtime::{
+        native::string::{map_string, to_lower_case, to_upper_case},
+        string::JavaString,
+    };
+
+    #[rstest]
+    #[case("hello", "HELLO")]
+    #[case("straße", "STRASSE")]
+    #[case("\u{10428}", "\u{10400}")]
+    fn test_map_string(#[case] value: &str, #[case] expected: &str) {
+        let upper = map_string(&JavaString::from(value), char::to_uppercase);
+        assert_eq!(upper, expected);
+    }
+
+    #[test]
+    fn test_map_lone_surrogate() {
+        let string = JavaString::from_utf16(&[0x61, 0xd800]);
+        let upper = map_string(&string, char::to_uppercase);
+        assert_eq!(upper.to_utf16(), [0x41, 0xd800]);
+    }
+
+    #[test]
+    fn test_case_mapping() {
+        assert_eq!(to_upper_case('a' as u16), 'A' as u16);
+        assert_eq!(to_lower_case('Ä' as u16), 'ä' as u16);
+        // ß的大写是两个字符, 按单个char转换时不变
+        assert_eq!(to_upper_case('ß' as u16), 'ß' as u16);
+        assert_eq!(to_upper_case(0xd83d), 0xd83d);
+    }
+}
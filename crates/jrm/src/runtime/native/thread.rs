@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use jrm_macro::natives;
+
+use crate::runtime::{
+    RuntimeError,
+    heap::{Object, instance_bytes, string_bytes},
+    klass::Field,
+    native::{NativeEnv, NativeRegistry},
+    scheduler::Blocker,
+    slot::{ObjectRef, Slot},
+    string::JavaString,
+    thread::Thread,
+};
+
+pub fn register(registry: &NativeRegistry) {
+    natives! { registry;
+        "java/lang/Thread" {
+            currentThread "()Ljava/lang/Thread;" => thread_current_thread,
+            r#yield "()V" => thread_yield,
+            sleep "(J)V" => thread_sleep,
+            start0 "()V" => thread_start0,
+            isAlive "()Z" => thread_is_alive,
+        }
+        "java/lang/Object" {
+            wait "(J)V" => object_wait,
+            notify "()V" => object_notify,
+            notifyAll "()V" => object_notify_all,
+        }
+    }
+}
+
+/// `java.lang.Thread`的实例字段
+fn thread_field(env: &NativeEnv, name: &str, descriptor: &str) -> Result<Arc<Field>, RuntimeError> {
+    let klass = env.vm().class_loader().load_class("java/lang/Thread")?;
+    klass
+        .lookup_field(name, descriptor)
+        .map(|(_, field)| field)
+        .ok_or_else(|| RuntimeError::NoSuchFieldError(format!("java/lang/Thread.{}", name)))
+}
+
+/// 毫秒数转换为虚拟时钟上的截止时刻
+fn deadline(env: &NativeEnv, millis: i64) -> u64 {
+    let nanos = (millis as u64).saturating_mul(1_000_000);
+    env.vm().scheduler().now().saturating_add(nanos)
+}
+
+fn check_timeout(env: &mut NativeEnv, millis: i64) -> Result<(), RuntimeError> {
+    if millis < 0 {
+        return Err(env.throw_new(
+            "java/lang/IllegalArgumentException",
+            "timeout value is negative",
+        ));
+    }
+    Ok(())
+}
+
+/// 主线程和不由`Thread.start`创建的线程在第一次调用时创建`Thread`对象
+fn thread_current_thread(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let id = env.thread().id();
+    if let Some(java_thread) = env.vm().scheduler().java_thread(id) {
+        return Ok(Some(Slot::Ref(Some(java_thread))));
+    }
+    let klass = env.vm().class_loader().load_class("java/lang/Thread")?;
+    env.thread().initialize_class(&klass)?;
+    let (name_field, tid_field) = (
+        thread_field(env, "name", "Ljava/lang/String;")?,
+        thread_field(env, "tid", "J")?,
+    );
+    let name = JavaString::from("main");
+    env.reserve(instance_bytes(&klass) + string_bytes(&name))?;
+    let object = env.heap().alloc_instance(klass);
+    let name = env.heap().alloc(Object::String(name));
+    env.heap()
+        .put_field(Some(object), &name_field, Slot::Ref(Some(name)))?;
+    env.heap()
+        .put_field(Some(object), &tid_field, Slot::from(id as i64))?;
+    env.vm().scheduler().set_java_thread(id, object);
+    Ok(Some(Slot::Ref(Some(object))))
+}
+
+fn thread_yield(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    env.thread().yield_now();
+    Ok(None)
+}
+
+/// 在虚拟时钟上睡眠, 挂起后重新执行时沿用原来的截止时刻
+fn thread_sleep(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let millis: i64 = env.arg(0);
+    check_timeout(env, millis)?;
+    let id = env.thread().id();
+    let deadline = match env.vm().scheduler().take_resumed(id) {
+        Some(Blocker::Sleep(deadline)) => deadline,
+        _ => deadline(env, millis),
+    };
+    while env.vm().scheduler().now() < deadline {
+        env.thread().park(Blocker::Sleep(deadline))?;
+    }
+    Ok(None)
+}
+
+/// 创建执行`run()`的虚拟机线程, 交给调度器
+fn thread_start0(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = env.this();
+    let class = env.thread().object_class(this)?;
+    let (_, run) = class
+        .lookup_method_in_supers("run", "()V")
+        .ok_or_else(|| RuntimeError::NoSuchMethodError(format!("{}.run()V", class.name())))?;
+    let (tid_field, daemon_field) = (
+        thread_field(env, "tid", "J")?,
+        thread_field(env, "daemon", "Z")?,
+    );
+    let vm = env.vm().clone();
+    let id = vm.scheduler().next_id();
+    vm.heap()
+        .put_field(Some(this), &tid_field, Slot::from(id as i64))?;
+    let daemon: i32 = vm.heap().get_field(Some(this), &daemon_field)?.into();
+    let mut thread = Thread::new(id, run, vm.clone());
+    thread
+        .current_frame_mut()
+        .set_args(vec![Slot::Ref(Some(this))]);
+    vm.scheduler().spawn(thread, Some(this), daemon != 0);
+    Ok(None)
+}
+
+fn thread_is_alive(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let tid_field = thread_field(env, "tid", "J")?;
+    let tid: i64 = env.heap().get_field(Some(env.this()), &tid_field)?.into();
+    let alive = tid != 0 && env.vm().scheduler().is_alive(tid as u64);
+    Ok(Some(Slot::from(alive)))
+}
+
+/// 完全释放锁后进入等待集合, 被notify或超时后重新获得锁并恢复进入次数.
+/// 挂起后重新执行时按调度器记录的等待状态继续
+fn object_wait(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = env.this();
+    let millis: i64 = env.arg(1);
+    check_timeout(env, millis)?;
+    let id = env.thread().id();
+    let vm = env.vm().clone();
+    let scheduler = vm.scheduler();
+    if !scheduler.is_waiting(id) {
+        let count = vm.heap().monitor_exit_all(this, id)?;
+        let deadline = (millis > 0).then(|| deadline(env, millis));
+        scheduler.begin_wait(id, this, count, deadline);
+    }
+    while !scheduler.is_wait_over(id) {
+        if let Err(err) = env.thread().park(Blocker::Wait(this)) {
+            // 挂起之外的错误使等待无法结束, 离开等待集合
+            if !matches!(err, RuntimeError::Parked) {
+                scheduler.end_wait(id);
+            }
+            return Err(err);
+        }
+    }
+    env.thread().enter_monitor(this)?;
+    for _ in 1..scheduler.end_wait(id) {
+        vm.heap().monitor_enter(this, id);
+    }
+    Ok(None)
+}
+
+fn notify(env: &mut NativeEnv, all: bool) -> Result<Option<Slot>, RuntimeError> {
+    let this: ObjectRef = env.this();
+    let id = env.thread().id();
+    if env.heap().monitor_owner(this) != Some(id) {
+        return Err(RuntimeError::IllegalMonitorStateException(
+            "current thread is not owner".to_string(),
+        ));
+    }
+    env.vm().scheduler().notify(this, all);
+    Ok(None)
+}
+
+fn object_notify(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    notify(env, false)
+}
+
+fn object_notify_all(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    notify(env, true)
+}
@@ -0,0 +1,86 @@
+use jrm_macro::natives;
+
+use crate::runtime::{
+    RuntimeError,
+    heap::{Object, instance_bytes, string_bytes},
+    native::{NativeEnv, NativeRegistry},
+    slot::{ObjectRef, Slot},
+    stack_trace::{StackTraceElement, backtrace},
+    string::JavaString,
+};
+
+pub fn register(registry: &NativeRegistry) {
+    natives! { registry;
+        "java/lang/Throwable" {
+            fillInStackTrace "(I)Ljava/lang/Throwable;" => throwable_fill_in_stack_trace,
+            getStackTraceDepth "()I" => throwable_get_stack_trace_depth,
+            getStackTraceElement "(I)Ljava/lang/StackTraceElement;" => throwable_get_stack_trace_element,
+        }
+    }
+}
+
+fn throwable_fill_in_stack_trace(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let this = env.this();
+    env.thread().fill_in_stack_trace(this)?;
+    Ok(Some(Slot::Ref(Some(this))))
+}
+
+fn throwable_get_stack_trace_depth(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let depth = backtrace(env.heap(), env.this()).map_or(0, |elements| elements.len());
+    Ok(Some(Slot::from(depth as i32)))
+}
+
+fn throwable_get_stack_trace_element(env: &mut NativeEnv) -> Result<Option<Slot>, RuntimeError> {
+    let index: i32 = env.arg(1);
+    let elements = backtrace(env.heap(), env.this()).unwrap_or_default();
+    let element = usize::try_from(index)
+        .ok()
+        .and_then(|index| elements.get(index))
+        .ok_or_else(|| {
+            RuntimeError::IndexOutOfBoundsException(format!(
+                "Index {} out of bounds for length {}",
+                index,
+                elements.len()
+            ))
+        })?;
+    let object = new_stack_trace_element(env, element)?;
+    Ok(Some(Slot::Ref(Some(object))))
+}
+
+/// 不执行构造方法, 直接设置`java.lang.StackTraceElement`的字段
+fn new_stack_trace_element(
+    env: &mut NativeEnv,
+    element: &StackTraceElement,
+) -> Result<ObjectRef, RuntimeError> {
+    let klass = env
+        .vm()
+        .class_loader()
+        .load_class("java/lang/StackTraceElement")?;
+    env.thread().initialize_class(&klass)?;
+    let strings = [
+        Some(element.class_name.as_str()),
+        Some(element.method_name.as_str()),
+        element.file_name.as_deref(),
+    ]
+    .map(|value| value.map(JavaString::from));
+    // 实例和字段引用的字符串一起分配, 中间不经过安全点
+    let size = strings.iter().flatten().map(string_bytes).sum::<usize>();
+    env.reserve(instance_bytes(&klass) + size)?;
+    let heap = env.heap();
+    let object = heap.alloc_instance(klass.clone());
+    let [class_name, method_name, file_name] =
+        strings.map(|value| Slot::Ref(value.map(|value| heap.alloc(Object::String(value)))));
+    let values = [
+        ("declaringClass", "Ljava/lang/String;", class_name),
+        ("methodName", "Ljava/lang/String;", method_name),
+        ("fileName", "Ljava/lang/String;", file_name),
+        ("lineNumber", "I", Slot::from(element.line_number)),
+    ];
+    for (name, descriptor, value) in values {
+        let (_, field) = klass.lookup_field(name, descriptor).ok_or_else(|| {
+            RuntimeError::NoSuchFieldError(format!("java/lang/StackTraceElement.{}", name))
+        })?;
+        heap.put_field(Some(object), &field, value)?;
+    }
+    Ok(object)
+}
@@ -0,0 +1,37 @@
+/// 紧跟在opcode之后的操作数, 按大端序读取
+pub trait Operand: Sized {
+    /// 操作数占用的字节数
+    const SIZE: u16;
+    fn read(code: &[u8], pos: usize) -> Self;
+}
+
+macro_rules! impl_operand {
+    ($($ty: ty),*) => {
+        $(
+            impl Operand for $ty {
+                const SIZE: u16 = size_of::<$ty>() as u16;
+                fn read(code: &[u8], pos: usize) -> Self {
+                    let bytes = &code[pos..pos + size_of::<$ty>()];
+                    <$ty>::from_be_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_operand!(u8, i8, u16, i16, i32);
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::operand::Operand;
+
+    #[test]
+    fn test_read_operand() {
+        let code = [0xff, 0xfe, 0x80, 0x00, 0x00, 0x01];
+        assert_eq!(u8::read(&code, 0), 0xff);
+        assert_eq!(i8::read(&code, 0), -1);
+        assert_eq!(u16::read(&code, 0), 0xfffe);
+        assert_eq!(i16::read(&code, 2), i16::MIN);
+        assert_eq!(i32::read(&code, 2), i32::MIN + 1);
+    }
+}
@@ -0,0 +1,645 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, OnceLock},
+};
+
+use crate::{
+    constant_pool::{Constant, ConstantDynamic, ConstantPool},
+    instance_klass::{FieldAccessFlags, MethodAccessFlags},
+    runtime::{
+        ClassLoader, Klass, Method, RuntimeError, Vm,
+        descriptor::{FieldType, MethodDescriptor},
+        heap::{HEADER_SIZE, Object},
+        klass::Field,
+        slot::{ObjectRef, Slot},
+        string::JavaString,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct FieldRef {
+    /// 声明该字段的类
+    pub klass: Arc<Klass>,
+    pub field: Arc<Field>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodRef {
+    /// 符号引用中的类
+    pub class: Arc<Klass>,
+    /// 声明该方法的类
+    pub klass: Arc<Klass>,
+    pub method: Arc<Method>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodHandleRef {
+    pub reference_kind: u8,
+    pub member: Box<Resolved>,
+}
+
+/// 解析后的常量
+#[derive(Debug, Clone)]
+pub enum Resolved {
+    Class(Arc<Klass>),
+    Field(FieldRef),
+    Method(MethodRef),
+    String(JavaString),
+    MethodType(Arc<str>),
+    MethodHandle(MethodHandleRef),
+    /// 引导方法得到的动态常量, 已经转换为常量的字段类型
+    Dynamic(Slot),
+}
+
+/// 每个类一份的运行时常量池, 符号引用在第一次使用时解析并缓存, 解析失败同样缓存
+#[derive(Default)]
+pub struct RuntimeConstantPool {
+    class_name: String,
+    constant_pool: Arc<ConstantPool>,
+    resolved: Vec<OnceLock<Result<Resolved, RuntimeError>>>,
+    // ldc得到的对象, 同一常量每次得到同一个引用
+    objects: Vec<OnceLock<ObjectRef>>,
+}
+
+impl Debug for RuntimeConstantPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RuntimeConstantPool({})", self.class_name)
+    }
+}
+
+impl RuntimeConstantPool {
+    pub fn new(class_name: String, constant_pool: Arc<ConstantPool>) -> Self {
+        let resolved = (0..constant_pool.0.len())
+            .map(|_| OnceLock::new())
+            .collect();
+        let objects = (0..constant_pool.0.len())
+            .map(|_| OnceLock::new())
+            .collect();
+        Self {
+            class_name,
+            constant_pool,
+            resolved,
+            objects,
+        }
+    }
+    pub fn class_name(&self) -> &str {
+        &self.class_name
+    }
+    /// ldc得到的对象, 以及动态常量的值和解析失败时引导方法抛出的Error
+    pub fn push_roots(&self, roots: &mut Vec<ObjectRef>) {
+        roots.extend(self.objects.iter().filter_map(OnceLock::get).copied());
+        roots.extend(self.resolved.iter().filter_map(|cell| match cell.get()? {
+            Ok(Resolved::Dynamic(Slot::Ref(object_ref))) => *object_ref,
+            Err(RuntimeError::Throwable { object, .. }) => Some(*object),
+            _ => None,
+        }));
+    }
+    pub fn get(&self, index: u16) -> Result<&Constant, RuntimeError> {
+        self.constant_pool
+            .0
+            .get(index as usize)
+            .ok_or_else(|| self.invalid_constant(index, "constant"))
+    }
+    pub fn resolve_class(
+        &self,
+        index: u16,
+        class_loader: &ClassLoader,
+    ) -> Result<Arc<Klass>, RuntimeError> {
+        match self.resolve(index, class_loader)? {
+            Resolved::Class(klass) => Ok(klass),
+            _ => Err(self.invalid_constant(index, "Class")),
+        }
+    }
+    pub fn resolve_field(
+        &self,
+        index: u16,
+        class_loader: &ClassLoader,
+    ) -> Result<FieldRef, RuntimeError> {
+        match self.resolve(index, class_loader)? {
+            Resolved::Field(field_ref) => Ok(field_ref),
+            _ => Err(self.invalid_constant(index, "FieldRef")),
+        }
+    }
+    /// 解析MethodRef或InterfaceMethodRef
+    pub fn resolve_method(
+        &self,
+        index: u16,
+        class_loader: &ClassLoader,
+    ) -> Result<MethodRef, RuntimeError> {
+        match self.resolve(index, class_loader)? {
+            Resolved::Method(method_ref) => Ok(method_ref),
+            _ => Err(self.invalid_constant(index, "MethodRef")),
+        }
+    }
+    pub fn resolve_string(&self, index: u16) -> Result<JavaString, RuntimeError> {
+        let Constant::String(string) = self.get(index)? else {
+            return Err(self.invalid_constant(index, "String"));
+        };
+        let cell = &self.resolved[index as usize];
+        let resolved = cell.get_or_init(|| self.resolve_string_uncached(string.string_index));
+        match resolved.clone()? {
+            Resolved::String(string) => Ok(string),
+            _ => Err(self.invalid_constant(index, "String")),
+        }
+    }
+    pub fn resolve(
+        &self,
+        index: u16,
+        class_loader: &ClassLoader,
+    ) -> Result<Resolved, RuntimeError> {
+        let cell = self
+            .resolved
+            .get(index as usize)
+            .ok_or_else(|| self.invalid_constant(index, "constant"))?;
+        cell.get_or_init(|| self.resolve_uncached(index, class_loader))
+            .clone()
+    }
+    /// 动态常量由`bootstrap`执行引导方法得到, 与其他常量一样缓存结果.
+    /// 引导方法执行时不持有缓存, 其中再次解析同一常量时各自执行, 先完成的结果生效
+    pub fn resolve_dynamic(
+        &self,
+        index: u16,
+        bootstrap: impl FnOnce(&ConstantDynamic) -> Result<Slot, RuntimeError>,
+    ) -> Result<Slot, RuntimeError> {
+        let Constant::Dynamic(dynamic) = self.get(index)? else {
+            return Err(self.invalid_constant(index, "Dynamic"));
+        };
+        let cell = &self.resolved[index as usize];
+        let resolved = match cell.get() {
+            Some(resolved) => resolved,
+            None => match bootstrap(dynamic) {
+                // 挂起不是解析的结果, 恢复后重新解析
+                Err(RuntimeError::Parked) => return Err(RuntimeError::Parked),
+                result => cell.get_or_init(|| result.map(Resolved::Dynamic)),
+            },
+        };
+        match resolved.clone()? {
+            Resolved::Dynamic(value) => Ok(value),
+            _ => Err(self.invalid_constant(index, "Dynamic")),
+        }
+    }
+    /// 解析String, Class, MethodType和MethodHandle常量得到的对象,
+    /// 分配对象之前经过安全点, `thread_roots`为当前线程的根
+    pub fn resolve_object(
+        &self,
+        index: u16,
+        vm: &Vm,
+        thread_roots: impl FnOnce() -> Vec<ObjectRef>,
+    ) -> Result<ObjectRef, RuntimeError> {
+        if let Some(object_ref) = self.objects.get(index as usize).and_then(OnceLock::get) {
+            return Ok(*object_ref);
+        }
+        let heap = vm.heap();
+        let object_ref = match self.resolve(index, vm.class_loader())? {
+            Resolved::String(string) => vm.intern(string, thread_roots)?,
+            Resolved::Class(klass) => {
+                vm.reserve(HEADER_SIZE, thread_roots)?;
+                klass.mirror(heap)
+            }
+            Resolved::MethodType(descriptor) => {
+                vm.reserve(HEADER_SIZE, thread_roots)?;
+                heap.alloc(Object::MethodType(descriptor))
+            }
+            Resolved::MethodHandle(method_handle) => {
+                vm.reserve(HEADER_SIZE, thread_roots)?;
+                heap.alloc(Object::MethodHandle(method_handle))
+            }
+            Resolved::Field(_) | Resolved::Method(_) | Resolved::Dynamic(_) => {
+                return Err(self.invalid_constant(index, "loadable constant"));
+            }
+        };
+        Ok(*self.objects[index as usize].get_or_init(|| object_ref))
+    }
+    #[cfg(test)]
+    pub fn is_resolved(&self, index: u16) -> bool {
+        self.resolved[index as usize].get().is_some()
+    }
+    fn resolve_uncached(
+        &self,
+        index: u16,
+        class_loader: &ClassLoader,
+    ) -> Result<Resolved, RuntimeError> {
+        let resolved = match self.get(index)? {
+            Constant::Class(class) => {
+                let name = self.utf8(class.name_index)?;
+                Resolved::Class(class_loader.load_class(&name)?)
+            }
+            Constant::String(string) => self.resolve_string_uncached(string.string_index)?,
+            Constant::FieldRef(field_ref) => {
+                let class = self.resolve_class(field_ref.class_index, class_loader)?;
+                let (name, descriptor) = self.name_and_type(field_ref.name_and_type_index)?;
+                let (klass, field) = class.lookup_field(&name, &descriptor).ok_or_else(|| {
+                    RuntimeError::NoSuchFieldError(format!("{}.{}", class.name(), name))
+                })?;
+                let field_ref = FieldRef { klass, field };
+                let accessor = class_loader.load_class(&self.class_name)?;
+                check_field_access(&accessor, &field_ref)?;
+                Resolved::Field(field_ref)
+            }
+            Constant::MethodRef(method_ref) => {
+                let class = self.resolve_class(method_ref.class_index, class_loader)?;
+                let (name, descriptor) = self.name_and_type(method_ref.name_and_type_index)?;
+                let method_ref = resolve_class_method(class, &name, &descriptor)?;
+                let accessor = class_loader.load_class(&self.class_name)?;
+                check_method_access(&accessor, &method_ref)?;
+                Resolved::Method(method_ref)
+            }
+            Constant::InterfaceMethodRef(method_ref) => {
+                let class = self.resolve_class(method_ref.class_index, class_loader)?;
+                let (name, descriptor) = self.name_and_type(method_ref.name_and_type_index)?;
+                let method_ref = resolve_interface_method(class, &name, &descriptor)?;
+                let accessor = class_loader.load_class(&self.class_name)?;
+                check_method_access(&accessor, &method_ref)?;
+                Resolved::Method(method_ref)
+            }
+            Constant::MethodType(method_type) => {
+                let descriptor = self.utf8(method_type.descriptor_index)?;
+                let method_descriptor = MethodDescriptor::parse(&descriptor)?;
+                let types = method_descriptor
+                    .parameters
+                    .iter()
+                    .chain(method_descriptor.return_type.iter());
+                resolve_type_names(types, class_loader)?;
+                Resolved::MethodType(descriptor.into())
+            }
+            Constant::MethodHandle(method_handle) => {
+                let reference_index = method_handle.reference_index;
+                let member = match (method_handle.reference_kind, self.get(reference_index)?) {
+                    (1..=4, Constant::FieldRef(_))
+                    | (5 | 8, Constant::MethodRef(_))
+                    | (6 | 7, Constant::MethodRef(_) | Constant::InterfaceMethodRef(_))
+                    | (9, Constant::InterfaceMethodRef(_)) => {
+                        self.resolve(reference_index, class_loader)?
+                    }
+                    _ => return Err(self.invalid_constant(index, "MethodHandle")),
+                };
+                Resolved::MethodHandle(MethodHandleRef {
+                    reference_kind: method_handle.reference_kind,
+                    member: Box::new(member),
+                })
+            }
+            _ => return Err(self.invalid_constant(index, "symbolic reference")),
+        };
+        Ok(resolved)
+    }
+    /// 字符串常量按modified UTF-8解码, 可以包含\0和单独的代理项
+    fn resolve_string_uncached(&self, string_index: u16) -> Result<Resolved, RuntimeError> {
+        let Constant::Utf8(utf8) = self.get(string_index)? else {
+            return Err(self.invalid_constant(string_index, "Utf8"));
+        };
+        let string = JavaString::from_modified_utf8(&utf8.bytes).ok_or_else(|| {
+            RuntimeError::ClassFormatError(format!(
+                "Illegal UTF8 string in constant pool in class file {}",
+                self.class_name
+            ))
+        })?;
+        Ok(Resolved::String(string))
+    }
+    fn utf8(&self, index: u16) -> Result<String, RuntimeError> {
+        match self.get(index)? {
+            Constant::Utf8(utf8) => Ok(String::from(utf8.clone())),
+            _ => Err(self.invalid_constant(index, "Utf8")),
+        }
+    }
+    pub fn name_and_type(&self, index: u16) -> Result<(String, String), RuntimeError> {
+        match self.get(index)? {
+            Constant::NameAndType(name_and_type) => Ok((
+                self.utf8(name_and_type.name_index)?,
+                self.utf8(name_and_type.descriptor_index)?,
+            )),
+            _ => Err(self.invalid_constant(index, "NameAndType")),
+        }
+    }
+    fn invalid_constant(&self, index: u16, expected: &str) -> RuntimeError {
+        RuntimeError::ClassFormatError(format!(
+            "{}: constant #{} is not a valid {}",
+            self.class_name, index, expected
+        ))
+    }
+}
+
+fn resolve_type_names<'a>(
+    types: impl Iterator<Item = &'a FieldType>,
+    class_loader: &ClassLoader,
+) -> Result<(), RuntimeError> {
+    for field_type in types {
+        if let Some(class_name) = field_type.class_name() {
+            class_loader.load_class(class_name)?;
+        }
+    }
+    Ok(())
+}
+
+/// JVMS 5.4.3.3
+fn resolve_class_method(
+    class: Arc<Klass>,
+    name: &str,
+    descriptor: &str,
+) -> Result<MethodRef, RuntimeError> {
+    if class.is_interface() {
+        return Err(RuntimeError::IncompatibleClassChangeError(format!(
+            "found interface {}, but class was expected",
+            class.name()
+        )));
+    }
+    let found = class
+        .lookup_method_in_supers(name, descriptor)
+        .or_else(|| select_maximally_specific(&class, name, descriptor));
+    to_method_ref(class, found, name, descriptor)
+}
+
+/// JVMS 5.4.3.4
+fn resolve_interface_method(
+    class: Arc<Klass>,
+    name: &str,
+    descriptor: &str,
+) -> Result<MethodRef, RuntimeError> {
+    if !class.is_interface() {
+        return Err(RuntimeError::IncompatibleClassChangeError(format!(
+            "found class {}, but interface was expected",
+            class.name()
+        )));
+    }
+    let found = class
+        .find_method(name, descriptor)
+        .map(|method| (class.clone(), method))
+        .or_else(|| {
+            // 接口的父类总是java/lang/Object
+            let object = class.super_class()?;
+            object
+                .find_method(name, descriptor)
+                .filter(|method| {
+                    method.access_flags.contains(MethodAccessFlags::PUBLIC)
+                        && !method.access_flags.contains(MethodAccessFlags::STATIC)
+                })
+                .map(|method| (object.clone(), method))
+        })
+        .or_else(|| select_maximally_specific(&class, name, descriptor));
+    to_method_ref(class, found, name, descriptor)
+}
+
+/// 恰好有一个非抽象的最具体方法时选择它, 否则任选其一
+fn select_maximally_specific(
+    class: &Arc<Klass>,
+    name: &str,
+    descriptor: &str,
+) -> Option<(Arc<Klass>, Arc<Method>)> {
+    let candidates = class.maximally_specific_methods(name, descriptor);
+    let mut non_abstract = candidates
+        .iter()
+        .filter(|(_, method)| !method.access_flags.contains(MethodAccessFlags::ABSTRACT));
+    if let (Some(only), None) = (non_abstract.next(), non_abstract.next()) {
+        return Some(only.clone());
+    }
+    candidates.into_iter().next()
+}
+
+fn to_method_ref(
+    class: Arc<Klass>,
+    found: Option<(Arc<Klass>, Arc<Method>)>,
+    name: &str,
+    descriptor: &str,
+) -> Result<MethodRef, RuntimeError> {
+    let (klass, method) = found.ok_or_else(|| {
+        RuntimeError::NoSuchMethodError(format!("{}.{}{}", class.name(), name, descriptor))
+    })?;
+    Ok(MethodRef {
+        class,
+        klass,
+        method,
+    })
+}
+
+/// JVMS 5.4.4, `accessor`能否访问`klass`中声明的成员
+fn is_accessible(
+    accessor: &Klass,
+    klass: &Klass,
+    is_public: bool,
+    is_private: bool,
+    is_protected: bool,
+) -> bool {
+    let same_package = accessor.package_name() == klass.package_name();
+    if is_public {
+        true
+    } else if is_private {
+        accessor.is_nestmate_of(klass)
+    } else if is_protected {
+        same_package || accessor.is_subclass_of(klass)
+    } else {
+        same_package
+    }
+}
+
+/// `accessor`能否访问解析得到的方法
+fn check_method_access(accessor: &Klass, method_ref: &MethodRef) -> Result<(), RuntimeError> {
+    let flags = method_ref.method.access_flags;
+    let klass = &method_ref.klass;
+    if is_accessible(
+        accessor,
+        klass,
+        flags.contains(MethodAccessFlags::PUBLIC),
+        flags.contains(MethodAccessFlags::PRIVATE),
+        flags.contains(MethodAccessFlags::PROTECTED),
+    ) {
+        return Ok(());
+    }
+    Err(RuntimeError::IllegalAccessError(format!(
+        "class {} tried to access method {}.{}{}",
+        accessor.name(),
+        klass.name(),
+        method_ref.method.name,
+        method_ref.method.descriptor
+    )))
+}
+
+/// `accessor`能否访问解析得到的字段
+fn check_field_access(accessor: &Klass, field_ref: &FieldRef) -> Result<(), RuntimeError> {
+    let flags = field_ref.field.access_flags;
+    let klass = &field_ref.klass;
+    if is_accessible(
+        accessor,
+        klass,
+        flags.contains(FieldAccessFlags::PUBLIC),
+        flags.contains(FieldAccessFlags::PRIVATE),
+        flags.contains(FieldAccessFlags::PROTECTED),
+    ) {
+        return Ok(());
+    }
+    Err(RuntimeError::IllegalAccessError(format!(
+        "class {} tried to access field {}.{}",
+        accessor.name(),
+        klass.name(),
+        field_ref.field.name
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::{fixture, rstest};
+
+    use crate::{
+        constant_pool::Constant,
+        runtime::{
+            ClassLoader, ClassPath, ClassPathEntry, RuntimeConstantPool, RuntimeError,
+            runtime_constant_pool::{MethodRef, Resolved, check_method_access},
+        },
+        test_context::TestContext,
+    };
+
+    #[fixture]
+    fn class_loader() -> ClassLoader {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        ClassLoader::new(class_path)
+    }
+
+    /// 按名称查找FieldRef/MethodRef/InterfaceMethodRef常量的索引
+    fn find_ref(constant_pool: &RuntimeConstantPool, class_name: &str, name: &str) -> u16 {
+        (1..u16::MAX)
+            .find(|index| {
+                let (class_index, name_and_type_index) = match constant_pool.get(*index) {
+                    Ok(Constant::FieldRef(r)) => (r.class_index, r.name_and_type_index),
+                    Ok(Constant::MethodRef(r)) => (r.class_index, r.name_and_type_index),
+                    Ok(Constant::InterfaceMethodRef(r)) => (r.class_index, r.name_and_type_index),
+                    Ok(_) => return false,
+                    Err(_) => panic!("{}.{} not found", class_name, name),
+                };
+                let Ok(Constant::Class(class)) = constant_pool.get(class_index) else {
+                    return false;
+                };
+                let Ok((ref_name, _)) = constant_pool.name_and_type(name_and_type_index) else {
+                    return false;
+                };
+                constant_pool.utf8(class.name_index).unwrap() == class_name && ref_name == name
+            })
+            .unwrap()
+    }
+
+    #[rstest]
+    fn test_resolve_class_and_string(class_loader: ClassLoader) {
+        let klass = class_loader.load_class("Simple1Impl").unwrap();
+        let constant_pool = klass.constant_pool();
+        // #23 = Class Simple1Impl, #21 = String hello!
+        assert!(!constant_pool.is_resolved(23));
+        let resolved = constant_pool.resolve_class(23, &class_loader).unwrap();
+        assert_eq!(resolved.name(), "Simple1Impl");
+        assert!(constant_pool.is_resolved(23));
+        assert_eq!(constant_pool.resolve_string(21).unwrap(), "hello!");
+        assert!(matches!(
+            constant_pool.resolve(21, &class_loader).unwrap(),
+            Resolved::String(string) if string == "hello!"
+        ));
+    }
+
+    /// 隐藏内建类库中的`java/lang/System`
+    struct WithoutSystem;
+
+    impl ClassPathEntry for WithoutSystem {
+        fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+            (name != "java/lang/System")
+                .then(|| TestContext.read_class(name))
+                .flatten()
+        }
+    }
+
+    #[test]
+    fn test_resolve_failure_cached() {
+        let mut class_path = ClassPath::default();
+        class_path.push(WithoutSystem);
+        let class_loader = ClassLoader::new(class_path);
+        let klass = class_loader.load_class("Simple1Impl").unwrap();
+        let constant_pool = klass.constant_pool();
+        let index = find_ref(constant_pool, "java/lang/System", "out");
+        let err = constant_pool
+            .resolve_field(index, &class_loader)
+            .unwrap_err();
+        assert!(
+            matches!(err, RuntimeError::NoClassDefFoundError(name) if name == "java/lang/System")
+        );
+        assert!(constant_pool.is_resolved(index));
+        let err = constant_pool
+            .resolve_field(index, &class_loader)
+            .unwrap_err();
+        assert!(matches!(err, RuntimeError::NoClassDefFoundError(_)));
+    }
+
+    #[rstest]
+    fn test_resolve_field(class_loader: ClassLoader) {
+        let klass = class_loader.load_class("Child").unwrap();
+        let constant_pool = klass.constant_pool();
+
+        let index = find_ref(constant_pool, "Child", "parentField");
+        let field_ref = constant_pool.resolve_field(index, &class_loader).unwrap();
+        assert_eq!(field_ref.klass.name(), "Parent");
+        assert_eq!(field_ref.field.offset, 0);
+
+        let index = find_ref(constant_pool, "Child", "childField");
+        let field_ref = constant_pool.resolve_field(index, &class_loader).unwrap();
+        assert_eq!(field_ref.klass.name(), "Child");
+        assert_eq!(field_ref.field.offset, 4);
+
+        let index = find_ref(constant_pool, "Child", "counter");
+        let field_ref = constant_pool.resolve_field(index, &class_loader).unwrap();
+        assert_eq!(field_ref.klass.name(), "Parent");
+        assert!(field_ref.field.is_static());
+    }
+
+    #[rstest]
+    fn test_resolve_method(class_loader: ClassLoader) {
+        let klass = class_loader.load_class("Child").unwrap();
+        let constant_pool = klass.constant_pool();
+
+        let index = find_ref(constant_pool, "Parent", "<init>");
+        let method_ref = constant_pool.resolve_method(index, &class_loader).unwrap();
+        assert_eq!(method_ref.klass.name(), "Parent");
+        assert_eq!(method_ref.method.descriptor, "()V");
+
+        // 默认方法通过类的符号引用解析
+        let index = find_ref(constant_pool, "Child", "greet");
+        let method_ref = constant_pool.resolve_method(index, &class_loader).unwrap();
+        assert_eq!(method_ref.class.name(), "Child");
+        assert_eq!(method_ref.klass.name(), "Greeter");
+
+        let index = find_ref(constant_pool, "Greeter", "greet");
+        assert!(matches!(
+            constant_pool.get(index),
+            Ok(Constant::InterfaceMethodRef(_))
+        ));
+        let method_ref = constant_pool.resolve_method(index, &class_loader).unwrap();
+        assert_eq!(method_ref.klass.name(), "Greeter");
+    }
+
+    #[rstest]
+    #[case::nest_host("InvokeBase", true)]
+    #[case::nest_member("InvokeBase$Nested", true)]
+    #[case::other_class("Invoke", false)]
+    #[case::subclass("InvokeDerived", false)]
+    fn test_private_access(
+        class_loader: ClassLoader,
+        #[case] accessor: &str,
+        #[case] accessible: bool,
+    ) {
+        let class = class_loader.load_class("InvokeBase").unwrap();
+        let method = class.find_method("secret", "()I").unwrap();
+        let method_ref = MethodRef {
+            class: class.clone(),
+            klass: class,
+            method,
+        };
+        let accessor = class_loader.load_class(accessor).unwrap();
+        let result = check_method_access(&accessor, &method_ref);
+        assert_eq!(result.is_ok(), accessible);
+        if !accessible {
+            assert!(matches!(result, Err(RuntimeError::IllegalAccessError(_))));
+        }
+    }
+
+    #[rstest]
+    fn test_resolve_type_mismatch(class_loader: ClassLoader) {
+        let klass = class_loader.load_class("Simple1Impl").unwrap();
+        let constant_pool = klass.constant_pool();
+        // #21 = String
+        let err = constant_pool.resolve_class(21, &class_loader).unwrap_err();
+        assert!(matches!(err, RuntimeError::ClassFormatError(_)));
+    }
+}
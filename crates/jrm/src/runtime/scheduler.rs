@@ -0,0 +1,516 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::runtime::{
+    InitState, Klass, RuntimeError, Vm,
+    heap::Object,
+    slot::{ObjectRef, Slot},
+    thread::{Thread, ThreadState},
+};
+
+/// 每个时间片最多执行的指令数
+const TIME_SLICE: u32 = 1000;
+/// 虚拟时钟上每条指令经过的纳秒数
+const INSTRUCTION_NANOS: u64 = 100;
+pub const MAIN_THREAD_ID: u64 = 1;
+
+/// 线程阻塞的原因, 条件满足后线程可以继续
+#[derive(Debug, Clone)]
+pub enum Blocker {
+    /// 等待其他线程释放对象的锁
+    Monitor(ObjectRef),
+    /// `Object.wait`, 被notify或超时后结束
+    Wait(ObjectRef),
+    /// `Thread.sleep`, 虚拟时钟到达该时刻后结束
+    Sleep(u64),
+    /// 等待其他线程完成类的初始化
+    Initialization(Arc<Klass>),
+}
+
+/// `Object.wait`释放的锁和等待的状态
+struct WaitState {
+    object: ObjectRef,
+    /// 释放前进入锁的次数
+    count: u64,
+    deadline: Option<u64>,
+    notified: bool,
+    /// 进入等待集合的顺序, notify按此顺序唤醒
+    seq: u64,
+}
+
+struct ThreadInfo {
+    state: ThreadState,
+    /// `java.lang.Thread`对象, 主线程在第一次调用`currentThread`时创建
+    java_thread: Option<ObjectRef>,
+    daemon: bool,
+    /// 在Rust栈上阻塞时栈帧中的引用
+    parked_roots: Vec<ObjectRef>,
+    wait: Option<WaitState>,
+    /// 挂起的线程由该原因恢复, 重新执行的指令据此继续
+    resumed: Option<Blocker>,
+}
+
+impl Default for ThreadInfo {
+    fn default() -> Self {
+        Self {
+            state: ThreadState::Running,
+            java_thread: None,
+            daemon: false,
+            parked_roots: vec![],
+            wait: None,
+            resumed: None,
+        }
+    }
+}
+
+struct SchedulerState {
+    next_id: u64,
+    /// 虚拟时钟, 单位为纳秒
+    clock: u64,
+    wait_seq: u64,
+    /// 挂起的线程, 按轮转顺序
+    queue: VecDeque<Thread>,
+    threads: BTreeMap<u64, ThreadInfo>,
+    main_result: Option<Result<(), RuntimeError>>,
+}
+
+impl SchedulerState {
+    fn is_ready(&self, id: u64, vm: &Vm) -> bool {
+        let Some(info) = self.threads.get(&id) else {
+            return true;
+        };
+        let ThreadState::Blocked(blocker) = &info.state else {
+            return true;
+        };
+        match blocker {
+            Blocker::Monitor(object) => vm.heap().monitor_owner(*object).is_none(),
+            Blocker::Wait(_) => info.wait.as_ref().is_none_or(|wait| self.is_over(wait)),
+            Blocker::Sleep(deadline) => self.clock >= *deadline,
+            Blocker::Initialization(klass) => {
+                !matches!(klass.init_state(), InitState::BeingInitialized(owner) if owner != id)
+            }
+        }
+    }
+    fn is_over(&self, wait: &WaitState) -> bool {
+        wait.notified || wait.deadline.is_some_and(|deadline| self.clock >= deadline)
+    }
+    /// 阻塞的线程中最早到达的时刻
+    fn next_deadline(&self) -> Option<u64> {
+        self.threads
+            .values()
+            .filter_map(|info| match &info.state {
+                ThreadState::Blocked(Blocker::Sleep(deadline)) => Some(*deadline),
+                ThreadState::Blocked(Blocker::Wait(_)) => {
+                    info.wait.as_ref().and_then(|wait| wait.deadline)
+                }
+                _ => None,
+            })
+            .filter(|deadline| *deadline > self.clock)
+            .min()
+    }
+}
+
+/// 确定性的绿色线程调度器: 所有Java线程在同一个宿主线程上按轮转顺序执行,
+/// 在时间片用完和阻塞点切换. 睡眠和超时使用虚拟时钟, 没有线程可以执行时直接前进
+pub struct Scheduler {
+    state: Mutex<SchedulerState>,
+    /// 虚拟时钟的起点对应的墙上时间, 单位为毫秒
+    start_millis: i64,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(SchedulerState {
+                next_id: MAIN_THREAD_ID + 1,
+                clock: 0,
+                wait_seq: 0,
+                queue: VecDeque::new(),
+                threads: BTreeMap::new(),
+                main_result: None,
+            }),
+            start_millis: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_millis() as i64),
+        }
+    }
+}
+
+impl Scheduler {
+    pub fn next_id(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        state.next_id - 1
+    }
+    /// 虚拟时钟的纳秒数
+    pub fn now(&self) -> u64 {
+        self.state.lock().unwrap().clock
+    }
+    pub fn current_time_millis(&self) -> i64 {
+        self.start_millis + (self.now() / 1_000_000) as i64
+    }
+    /// 加入调度队列, `java_thread`为`Thread.start`的接收者
+    pub fn spawn(&self, thread: Thread, java_thread: Option<ObjectRef>, daemon: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.threads.insert(
+            thread.id(),
+            ThreadInfo {
+                java_thread,
+                daemon,
+                ..Default::default()
+            },
+        );
+        state.queue.push_back(thread);
+    }
+    /// 线程已经启动且尚未结束
+    pub fn is_alive(&self, id: u64) -> bool {
+        self.state.lock().unwrap().threads.contains_key(&id)
+    }
+    pub fn java_thread(&self, id: u64) -> Option<ObjectRef> {
+        self.state
+            .lock()
+            .unwrap()
+            .threads
+            .get(&id)
+            .and_then(|info| info.java_thread)
+    }
+    pub fn set_java_thread(&self, id: u64, java_thread: ObjectRef) {
+        let mut state = self.state.lock().unwrap();
+        state.threads.entry(id).or_default().java_thread = Some(java_thread);
+    }
+    /// 线程挂起前记录阻塞的原因
+    pub fn block(&self, id: u64, blocker: Blocker) {
+        let mut state = self.state.lock().unwrap();
+        state.threads.entry(id).or_default().state = ThreadState::Blocked(blocker);
+    }
+    /// 挂起的线程恢复时的阻塞原因, 只能取出一次
+    pub fn take_resumed(&self, id: u64) -> Option<Blocker> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .threads
+            .get_mut(&id)
+            .and_then(|info| info.resumed.take())
+    }
+    /// 不能挂起的线程在自身的Rust栈上执行其他线程, 直到`blocker`的条件满足
+    pub fn block_in_place(
+        &self,
+        thread: &mut Thread,
+        blocker: Blocker,
+    ) -> Result<(), RuntimeError> {
+        let vm = thread.vm().clone();
+        let id = thread.id();
+        self.park_in_place(thread, ThreadState::Blocked(blocker));
+        let result = loop {
+            if self.state.lock().unwrap().is_ready(id, &vm) {
+                break Ok(());
+            }
+            if !self.run_round(&vm) && !self.advance_clock() {
+                break Err(RuntimeError::Deadlock);
+            }
+        };
+        self.unpark_in_place(id);
+        result
+    }
+    /// 不能挂起的线程让出时, 让其他线程各执行一个时间片
+    pub fn yield_in_place(&self, thread: &mut Thread) {
+        let vm = thread.vm().clone();
+        self.park_in_place(thread, ThreadState::Running);
+        self.run_round(&vm);
+        self.unpark_in_place(thread.id());
+    }
+    fn park_in_place(&self, thread: &Thread, thread_state: ThreadState) {
+        let mut state = self.state.lock().unwrap();
+        let info = state.threads.entry(thread.id()).or_default();
+        info.state = thread_state;
+        info.parked_roots = thread.roots();
+    }
+    fn unpark_in_place(&self, id: u64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(info) = state.threads.get_mut(&id) {
+            info.state = ThreadState::Running;
+            info.parked_roots.clear();
+        }
+    }
+    /// 完全释放锁之后进入`object`的等待集合
+    pub fn begin_wait(&self, id: u64, object: ObjectRef, count: u64, deadline: Option<u64>) {
+        let mut state = self.state.lock().unwrap();
+        state.wait_seq += 1;
+        let seq = state.wait_seq;
+        state.threads.entry(id).or_default().wait = Some(WaitState {
+            object,
+            count,
+            deadline,
+            notified: false,
+            seq,
+        });
+    }
+    /// 已经开始`Object.wait`, 且尚未重新获得锁
+    pub fn is_waiting(&self, id: u64) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .threads
+            .get(&id)
+            .is_some_and(|info| info.wait.is_some())
+    }
+    /// 已经被notify或超时
+    pub fn is_wait_over(&self, id: u64) -> bool {
+        let state = self.state.lock().unwrap();
+        let wait = state.threads.get(&id).and_then(|info| info.wait.as_ref());
+        wait.is_none_or(|wait| state.is_over(wait))
+    }
+    /// 重新获得锁之后离开等待, 返回释放前进入锁的次数
+    pub fn end_wait(&self, id: u64) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state
+            .threads
+            .get_mut(&id)
+            .and_then(|info| info.wait.take())
+            .map_or(1, |wait| wait.count)
+    }
+    /// 按进入等待集合的顺序唤醒`object`上的一个或全部线程
+    pub fn notify(&self, object: ObjectRef, all: bool) {
+        let mut state = self.state.lock().unwrap();
+        let clock = state.clock;
+        let mut waiters: Vec<&mut WaitState> = state
+            .threads
+            .values_mut()
+            .filter_map(|info| info.wait.as_mut())
+            .filter(|wait| {
+                wait.object == object
+                    && !wait.notified
+                    && wait.deadline.is_none_or(|deadline| clock < deadline)
+            })
+            .collect();
+        waiters.sort_by_key(|wait| wait.seq);
+        let count = if all { waiters.len() } else { 1 };
+        for wait in waiters.into_iter().take(count) {
+            wait.notified = true;
+        }
+    }
+    /// 执行调度队列中的线程, 直到只剩下守护线程. 返回主线程的结果
+    pub fn run(&self, vm: &Arc<Vm>, main: Thread) -> Result<(), RuntimeError> {
+        self.spawn(main, None, false);
+        loop {
+            let has_user_threads = {
+                let state = self.state.lock().unwrap();
+                state.queue.iter().any(|thread| {
+                    state
+                        .threads
+                        .get(&thread.id())
+                        .is_none_or(|info| !info.daemon)
+                })
+            };
+            if !has_user_threads {
+                break;
+            }
+            if !self.run_round(vm) && !self.advance_clock() {
+                return Err(RuntimeError::Deadlock);
+            }
+        }
+        self.state
+            .lock()
+            .unwrap()
+            .main_result
+            .take()
+            .unwrap_or(Ok(()))
+    }
+    /// 按轮转顺序让每个可以继续的挂起线程执行一个时间片, 返回是否执行了线程
+    fn run_round(&self, vm: &Arc<Vm>) -> bool {
+        let ids: Vec<u64> = {
+            let state = self.state.lock().unwrap();
+            state.queue.iter().map(Thread::id).collect()
+        };
+        let mut ran = false;
+        for id in ids {
+            let mut thread = {
+                let mut state = self.state.lock().unwrap();
+                // 可能已经在嵌套的调度中执行
+                let Some(index) = state.queue.iter().position(|thread| thread.id() == id) else {
+                    continue;
+                };
+                if !state.is_ready(id, vm) {
+                    continue;
+                }
+                if let Some(info) = state.threads.get_mut(&id)
+                    && let ThreadState::Blocked(blocker) =
+                        std::mem::replace(&mut info.state, ThreadState::Running)
+                {
+                    info.resumed = Some(blocker);
+                }
+                state.queue.remove(index).unwrap()
+            };
+            ran = true;
+            let executed = thread.executed();
+            let end = thread.run_slice(TIME_SLICE);
+            let elapsed = (thread.executed() - executed) * INSTRUCTION_NANOS;
+            self.state.lock().unwrap().clock += elapsed;
+            match end {
+                Some(result) => self.terminate(vm, thread, result),
+                None => self.state.lock().unwrap().queue.push_back(thread),
+            }
+        }
+        ran
+    }
+    /// 没有线程可以执行时把虚拟时钟拨到最早的超时时刻, 没有超时返回false
+    fn advance_clock(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.next_deadline() {
+            Some(deadline) => {
+                state.clock = deadline;
+                true
+            }
+            None => false,
+        }
+    }
+    /// 输出未捕获的异常, 唤醒在线程对象上join的线程
+    fn terminate(&self, vm: &Vm, thread: Thread, result: Result<(), RuntimeError>) {
+        let info = self.state.lock().unwrap().threads.remove(&thread.id());
+        let java_thread = info.and_then(|info| info.java_thread);
+        if let Err(err) = &result {
+            let name = match java_thread {
+                Some(object) => thread_name(vm, object),
+                None => "main".to_string(),
+            };
+            let message = vm.uncaught_exception_message(&name, err);
+            let _ = vm.console().write(2, format!("{}\n", message).as_bytes());
+        }
+        if let Some(object) = java_thread {
+            self.notify(object, true);
+        }
+        if thread.id() == MAIN_THREAD_ID {
+            self.state.lock().unwrap().main_result = Some(result);
+        }
+    }
+    /// 挂起线程的栈帧, 线程对象和等待中的对象
+    pub fn push_roots(&self, roots: &mut Vec<ObjectRef>) {
+        let state = self.state.lock().unwrap();
+        for thread in &state.queue {
+            roots.extend(thread.roots());
+        }
+        for info in state.threads.values() {
+            roots.extend(info.java_thread);
+            roots.extend(info.parked_roots.iter().copied());
+            roots.extend(info.wait.as_ref().map(|wait| wait.object));
+            if let ThreadState::Blocked(Blocker::Monitor(object) | Blocker::Wait(object)) =
+                &info.state
+            {
+                roots.push(*object);
+            }
+        }
+    }
+}
+
+/// `Thread.name`, 不能读取时为空
+fn thread_name(vm: &Vm, object: ObjectRef) -> String {
+    let name = vm
+        .class_loader()
+        .load_class("java/lang/Thread")
+        .ok()
+        .and_then(|klass| klass.lookup_field("name", "Ljava/lang/String;"))
+        .and_then(|(_, field)| vm.heap().get_field(Some(object), &field).ok());
+    match name {
+        Some(Slot::Ref(Some(name))) => match vm.heap().get(name) {
+            Object::String(name) => name.to_string(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        runtime::{
+            ClassLoader, ClassPath, Method, Vm,
+            heap::Object,
+            native::NativeEnv,
+            slot::{ObjectRef, Slot},
+            thread::Thread,
+        },
+        test_context::TestContext,
+    };
+
+    fn vm() -> Arc<Vm> {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        Arc::new(Vm::new(ClassLoader::new(class_path)))
+    }
+
+    /// 在不由调度器执行的线程上调用静态方法, 阻塞时在该线程的栈上执行其他线程
+    fn call_static(
+        vm: &Arc<Vm>,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+        args: Vec<Slot>,
+    ) -> Option<Slot> {
+        let klass = vm.class_loader().load_class(class).unwrap();
+        let method = klass.find_method(name, descriptor).unwrap();
+        let mut thread = Thread::new(0, Arc::new(Method::default()), vm.clone());
+        thread.initialize_class(&klass).unwrap();
+        thread.call(method, args).unwrap()
+    }
+
+    fn interleave() -> String {
+        let vm = vm();
+        let result = call_static(&vm, "Threads", "interleave", "()Ljava/lang/String;", vec![]);
+        let Some(Slot::Ref(Some(string))) = result else {
+            panic!("null result");
+        };
+        match vm.heap().get(string) {
+            Object::String(string) => string.to_string(),
+            _ => panic!("not a string"),
+        }
+    }
+
+    #[test]
+    fn test_deterministic_interleaving() {
+        let first = interleave();
+        assert_eq!(first.len(), 400);
+        assert_eq!(first.matches('a').count(), 200);
+        // 两个线程在时间片用完时切换, 不是依次执行完
+        assert!(first.matches("ab").count() + first.matches("ba").count() > 1);
+        for _ in 0..3 {
+            assert_eq!(interleave(), first);
+        }
+    }
+
+    #[test]
+    fn test_sleep_advances_clock() {
+        let vm = vm();
+        let sleep = vm
+            .natives()
+            .lookup("java/lang/Thread", "sleep", "(J)V")
+            .unwrap();
+        let mut thread = Thread::new(0, Arc::new(Method::default()), vm.clone());
+        sleep(&mut NativeEnv::new(&mut thread, vec![Slot::from(5i64)])).unwrap();
+        // 没有其他线程时直接拨到截止时刻
+        assert_eq!(vm.scheduler().now(), 5_000_000);
+        sleep(&mut NativeEnv::new(&mut thread, vec![Slot::from(0i64)])).unwrap();
+        assert_eq!(vm.scheduler().now(), 5_000_000);
+        let millis = vm.scheduler().current_time_millis();
+        assert_eq!(millis, vm.scheduler().start_millis + 5);
+    }
+
+    #[test]
+    fn test_roots_of_waiting_threads() {
+        let vm = vm();
+        let object = vm.heap().alloc(Object::String("lock".into()));
+        let id = vm.scheduler().next_id();
+        vm.scheduler().begin_wait(id, object, 1, None);
+        let mut roots: Vec<ObjectRef> = vec![];
+        vm.scheduler().push_roots(&mut roots);
+        assert_eq!(roots, vec![object]);
+        assert!(vm.scheduler().is_waiting(id));
+        assert!(!vm.scheduler().is_wait_over(id));
+        vm.scheduler().notify(object, false);
+        assert!(vm.scheduler().is_wait_over(id));
+        assert_eq!(vm.scheduler().end_wait(id), 1);
+        assert!(!vm.scheduler().is_waiting(id));
+    }
+}
@@ -1,10 +1,38 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Slot {
     Bits32(u32),
     Bits64(u64),
     Ref(Option<ObjectRef>),
 }
 
+impl Slot {
+    /// JVMS 2.11.1的计算类型分类, long和double为2
+    pub fn category(&self) -> usize {
+        match self {
+            Slot::Bits64(_) => 2,
+            _ => 1,
+        }
+    }
+    /// 按字段描述符取默认的零值
+    pub fn zero_value(descriptor: &str) -> Self {
+        match descriptor.as_bytes().first() {
+            Some(b'J' | b'D') => Slot::Bits64(0),
+            Some(b'L' | b'[') => Slot::Ref(None),
+            _ => Slot::Bits32(0),
+        }
+    }
+    /// 按字段描述符截断int值, byte和short符号扩展, char零扩展, boolean只保留最低位
+    pub fn narrow(self, descriptor: &str) -> Self {
+        match (descriptor.as_bytes().first(), self) {
+            (Some(b'B'), Slot::Bits32(bits)) => Slot::from(bits as i8 as i32),
+            (Some(b'Z'), Slot::Bits32(bits)) => Slot::Bits32(bits & 1),
+            (Some(b'C'), Slot::Bits32(bits)) => Slot::Bits32(bits as u16 as u32),
+            (Some(b'S'), Slot::Bits32(bits)) => Slot::from(bits as i16 as i32),
+            (_, value) => value,
+        }
+    }
+}
+
 macro_rules! convert_panic {
     ($ty: ty) => {
         panic!("failed to convert {}", stringify!($ty))
@@ -50,6 +78,13 @@ impl From<i32> for Slot {
     }
 }
 
+/// boolean在栈上是int的0或1
+impl From<bool> for Slot {
+    fn from(value: bool) -> Self {
+        Slot::Bits32(value as u32)
+    }
+}
+
 impl From<f32> for Slot {
     fn from(value: f32) -> Self {
         Slot::Bits32(value.to_bits())
@@ -67,9 +102,33 @@ impl From<f64> for Slot {
         Slot::Bits64(value.to_bits())
     }
 }
-#[derive(Debug, Clone)]
+impl From<Slot> for Option<ObjectRef> {
+    fn from(value: Slot) -> Self {
+        match value {
+            Slot::Ref(object_ref) => object_ref,
+            _ => convert_panic!(ObjectRef),
+        }
+    }
+}
+
+impl From<Option<ObjectRef>> for Slot {
+    fn from(value: Option<ObjectRef>) -> Self {
+        Slot::Ref(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ObjectRef(u32);
 
+impl ObjectRef {
+    pub fn new(index: u32) -> Self {
+        Self(index)
+    }
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::runtime::slot::Slot;
@@ -97,4 +156,20 @@ mod tests {
         let slot = Slot::Bits32(0);
         let _f: f64 = slot.into();
     }
+    #[test]
+    fn test_float_bits_round_trip() {
+        // 带payload的NaN和负零都要保留原始位模式
+        for bits in [0x7fc0_0001_u32, 0xffa0_0000, 0x8000_0000, 0x0000_0001] {
+            let slot = Slot::from(f32::from_bits(bits));
+            assert_eq!(f32::from(slot).to_bits(), bits);
+        }
+        for bits in [
+            0x7ff8_0000_0000_0001_u64,
+            0xfff4_0000_0000_0000,
+            0x8000_0000_0000_0000,
+        ] {
+            let slot = Slot::from(f64::from_bits(bits));
+            assert_eq!(f64::from(slot).to_bits(), bits);
+        }
+    }
 }
@@ -0,0 +1,109 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::runtime::{
+    Method,
+    heap::{Heap, Object},
+    slot::{ObjectRef, Slot},
+};
+
+/// Java栈轨迹中的一帧, 与`java.lang.StackTraceElement`对应
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackTraceElement {
+    /// 以`.`分隔的类名
+    pub class_name: String,
+    pub method_name: String,
+    pub file_name: Option<Arc<str>>,
+    /// 未知时为-1, 本地方法为-2
+    pub line_number: i32,
+}
+
+impl StackTraceElement {
+    /// 执行到`pc`处的`method`
+    pub fn new(method: &Method, pc: u16) -> Self {
+        let line_number = if method.is_native() {
+            -2
+        } else {
+            method.line_number(pc).map_or(-1, i32::from)
+        };
+        Self {
+            class_name: method.constant_pool.class_name().replace('/', "."),
+            method_name: method.name.clone(),
+            file_name: method.source_file.clone(),
+            line_number,
+        }
+    }
+    pub fn is_native_method(&self) -> bool {
+        self.line_number == -2
+    }
+}
+
+/// 与`StackTraceElement.toString`的格式相同
+impl Display for StackTraceElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}(", self.class_name, self.method_name)?;
+        match &self.file_name {
+            _ if self.is_native_method() => write!(f, "Native Method")?,
+            Some(file_name) if self.line_number >= 0 => {
+                write!(f, "{}:{}", file_name, self.line_number)?
+            }
+            Some(file_name) => write!(f, "{}", file_name)?,
+            None => write!(f, "Unknown Source")?,
+        }
+        write!(f, ")")
+    }
+}
+
+/// `Throwable.backtrace`字段中保存的栈轨迹, 没有填充时为None
+pub fn backtrace(heap: &Heap, throwable: ObjectRef) -> Option<Arc<[StackTraceElement]>> {
+    let (_, field) = heap
+        .klass(throwable)?
+        .lookup_field("backtrace", "Ljava/lang/Object;")?;
+    let Ok(Slot::Ref(Some(backtrace))) = heap.get_field(Some(throwable), &field) else {
+        return None;
+    };
+    match heap.get(backtrace) {
+        Object::Backtrace(elements) => Some(elements),
+        _ => None,
+    }
+}
+
+/// 把栈轨迹保存到`Throwable.backtrace`字段, 没有该字段时忽略. 调用者需要先经过分配的安全点
+pub fn set_backtrace(heap: &Heap, throwable: ObjectRef, elements: Vec<StackTraceElement>) {
+    let Some((_, field)) = heap
+        .klass(throwable)
+        .and_then(|klass| klass.lookup_field("backtrace", "Ljava/lang/Object;"))
+    else {
+        return;
+    };
+    let backtrace = heap.alloc(Object::Backtrace(elements.into()));
+    // 字段属于throwable的类, 不会失败
+    let _ = heap.put_field(Some(throwable), &field, Slot::Ref(Some(backtrace)));
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::StackTraceElement;
+
+    fn element(file_name: Option<&str>, line_number: i32) -> StackTraceElement {
+        StackTraceElement {
+            class_name: "Simple1Impl".into(),
+            method_name: "main".into(),
+            file_name: file_name.map(Into::into),
+            line_number,
+        }
+    }
+
+    #[rstest]
+    #[case(
+        element(Some("Simple1Impl.java"), 7),
+        "Simple1Impl.main(Simple1Impl.java:7)"
+    )]
+    #[case(element(Some("Simple1Impl.java"), -1), "Simple1Impl.main(Simple1Impl.java)")]
+    #[case(element(None, 7), "Simple1Impl.main(Unknown Source)")]
+    #[case(element(Some("Simple1Impl.java"), -2), "Simple1Impl.main(Native Method)")]
+    fn test_display(#[case] element: StackTraceElement, #[case] expected: &str) {
+        assert_eq!(element.to_string(), expected);
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    sync::{Arc, OnceLock},
+};
+
+use crate::modified_utf8;
+
+/// `value`的编码, 与JDK的`String.coder`取值相同
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Coder {
+    /// 每个字符一个字节
+    Latin1 = 0,
+    /// 每个字符两个字节, 小端序
+    Utf16 = 1,
+}
+
+/// 堆中`java.lang.String`的内容, 与JDK 9的compact strings相同:
+/// 所有字符都不超过U+00FF时按Latin1存储, 否则按UTF-16存储. 内容不可变, 复制只增加引用计数
+#[derive(Clone)]
+pub struct JavaString(Arc<Inner>);
+
+struct Inner {
+    coder: Coder,
+    value: Box<[u8]>,
+    // 与String.hash字段一样在第一次调用hashCode时计算
+    hash: OnceLock<i32>,
+}
+
+impl JavaString {
+    fn new(coder: Coder, value: Box<[u8]>) -> Self {
+        Self(Arc::new(Inner {
+            coder,
+            value,
+            hash: OnceLock::new(),
+        }))
+    }
+    /// 从UTF-16代码单元创建, 可以包含单独的代理项
+    pub fn from_utf16(units: &[u16]) -> Self {
+        if units.iter().all(|unit| *unit <= 0xff) {
+            let value = units.iter().map(|unit| *unit as u8).collect();
+            return Self::new(Coder::Latin1, value);
+        }
+        let value = units.iter().flat_map(|unit| unit.to_le_bytes()).collect();
+        Self::new(Coder::Utf16, value)
+    }
+    /// 从常量池中的modified UTF-8创建, 格式错误时返回None
+    pub fn from_modified_utf8(bytes: &[u8]) -> Option<Self> {
+        Some(Self::from_utf16(&modified_utf8::decode(bytes)?))
+    }
+    pub fn to_modified_utf8(&self) -> Vec<u8> {
+        modified_utf8::encode(self.units())
+    }
+    pub fn coder(&self) -> Coder {
+        self.0.coder
+    }
+    /// 按`coder`编码的字节
+    pub fn value(&self) -> &[u8] {
+        &self.0.value
+    }
+    /// `String.length`, 即UTF-16代码单元的个数
+    pub fn len(&self) -> usize {
+        match self.0.coder {
+            Coder::Latin1 => self.0.value.len(),
+            Coder::Utf16 => self.0.value.len() / 2,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.value.is_empty()
+    }
+    /// `String.charAt`, 越界时返回None
+    pub fn char_at(&self, index: usize) -> Option<u16> {
+        match self.0.coder {
+            Coder::Latin1 => self.0.value.get(index).map(|byte| *byte as u16),
+            Coder::Utf16 => {
+                let bytes = self.0.value.get(index * 2..index * 2 + 2)?;
+                Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+            }
+        }
+    }
+    /// 依次访问UTF-16代码单元
+    pub fn units(&self) -> impl Iterator<Item = u16> + '_ {
+        (0..self.len()).map(|index| self.char_at(index).unwrap())
+    }
+    pub fn to_utf16(&self) -> Vec<u16> {
+        self.units().collect()
+    }
+    /// `String.hashCode`: `s[0]*31^(n-1) + ... + s[n-1]`, 计算后缓存
+    pub fn hash_code(&self) -> i32 {
+        *self.0.hash.get_or_init(|| {
+            self.units().fold(0i32, |hash, unit| {
+                hash.wrapping_mul(31).wrapping_add(unit as i32)
+            })
+        })
+    }
+}
+
+impl From<&str> for JavaString {
+    fn from(value: &str) -> Self {
+        if value.is_ascii() {
+            return Self::new(Coder::Latin1, value.as_bytes().into());
+        }
+        Self::from_utf16(&value.encode_utf16().collect::<Vec<_>>())
+    }
+}
+
+impl From<String> for JavaString {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+/// 单独的代理项显示为U+FFFD
+impl Display for JavaString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        char::decode_utf16(self.units())
+            .map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .try_for_each(|ch| write!(f, "{}", ch))
+    }
+}
+
+impl Debug for JavaString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+/// 相同内容总是选择相同的编码, 比较字节即可
+impl PartialEq for JavaString {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.coder == other.0.coder && self.0.value == other.0.value
+    }
+}
+
+impl Eq for JavaString {}
+
+impl Hash for JavaString {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.coder.hash(state);
+        self.0.value.hash(state);
+    }
+}
+
+impl PartialEq<str> for JavaString {
+    fn eq(&self, other: &str) -> bool {
+        self.units().eq(other.encode_utf16())
+    }
+}
+
+impl PartialEq<&str> for JavaString {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::runtime::string::{Coder, JavaString};
+
+    #[rstest]
+    #[case("", Coder::Latin1, 0)]
+    #[case("hello", Coder::Latin1, 5)]
+    #[case("café", Coder::Latin1, 4)]
+    #[case("中文", Coder::Utf16, 2)]
+    #[case("a\u{1F600}", Coder::Utf16, 3)]
+    fn test_coder(#[case] value: &str, #[case] coder: Coder, #[case] len: usize) {
+        let string = JavaString::from(value);
+        assert_eq!(string.coder(), coder);
+        assert_eq!(string.len(), len);
+        assert_eq!(
+            string.value().len(),
+            if coder == Coder::Latin1 { len } else { len * 2 }
+        );
+        assert_eq!(string.to_string(), value);
+        assert!(string == value);
+    }
+
+    #[rstest]
+    #[case("", 0)]
+    #[case("a", 97)]
+    #[case("hello", 99162322)]
+    #[case("\u{1F600}", 1772899)]
+    fn test_hash_code(#[case] value: &str, #[case] expected: i32) {
+        let string = JavaString::from(value);
+        assert_eq!(string.hash_code(), expected);
+        assert_eq!(string.clone().hash_code(), expected);
+    }
+
+    #[test]
+    fn test_char_at() {
+        let string = JavaString::from("a中\u{1F600}");
+        assert_eq!(string.char_at(0), Some('a' as u16));
+        assert_eq!(string.char_at(1), Some('中' as u16));
+        assert_eq!(string.char_at(2), Some(0xd83d));
+        assert_eq!(string.char_at(3), Some(0xde00));
+        assert_eq!(string.char_at(4), None);
+    }
+
+    #[test]
+    fn test_equality() {
+        // 只含Latin1字符的UTF-16输入也按Latin1存储
+        let latin1 = JavaString::from_utf16(&[0x68, 0xe9]);
+        assert_eq!(latin1.coder(), Coder::Latin1);
+        assert_eq!(latin1, JavaString::from("hé"));
+        assert_ne!(latin1, JavaString::from("he"));
+    }
+
+    #[test]
+    fn test_modified_utf8() {
+        let string = JavaString::from_modified_utf8(&[0x61, 0xc0, 0x80, 0xed, 0xa0, 0x80]).unwrap();
+        assert_eq!(string.to_utf16(), [0x61, 0, 0xd800]);
+        assert_eq!(string.to_string(), "a\0\u{fffd}");
+        assert_eq!(
+            string.to_modified_utf8(),
+            [0x61, 0xc0, 0x80, 0xed, 0xa0, 0x80]
+        );
+        assert!(JavaString::from_modified_utf8(&[0xff]).is_none());
+    }
+}
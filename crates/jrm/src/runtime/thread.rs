@@ -3,24 +3,49 @@ use std::{ops::Index, sync::Arc, usize};
 use jrm_macro::define_instructions;
 
 use crate::{
-    constant_pool::ConstantPool,
+    constant_pool::Constant,
+    instance_klass::{ClassAccessFlags, MethodAccessFlags},
     runtime::{
-        Method,
+        Klass, Method, RuntimeConstantPool, RuntimeError, Vm, bootstrap,
+        dispatch::{select_special, select_virtual},
         frame::{Frame, LocalVarsLike, OperandStackLike},
-        slot::Slot,
+        heap::{
+            MAX_ARRAY_LENGTH, Object, array_bytes, backtrace_bytes, instance_bytes, string_bytes,
+        },
+        klass::ClassInitializer,
+        native::NativeEnv,
+        operand::Operand,
+        runtime_constant_pool::{FieldRef, MethodRef},
+        scheduler::Blocker,
+        slot::{ObjectRef, Slot},
+        stack_trace::{StackTraceElement, set_backtrace},
+        string::JavaString,
     },
 };
 
+/// 线程栈的最大栈帧数, 超过时抛出StackOverflowError
+const MAX_STACK_DEPTH: usize = 2048;
+
 pub enum ThreadState {
     Running,
-    Blocked,
-    Terminated,
+    /// 等待`Blocker`的条件满足
+    Blocked(Blocker),
 }
 pub struct Thread {
     id: u64,
     stack: Vec<Frame>,
-    state: ThreadState,
-    constant_pool: Arc<ConstantPool>,
+    vm: Arc<Vm>,
+    // 当前解释循环的入口栈深度, 返回到该深度时返回值交给调用者
+    entry_depth: usize,
+    return_value: Option<Slot>,
+    // 向后跳转的次数
+    backward_branches: u64,
+    // 执行的指令数
+    executed: u64,
+    // 在调度器的时间片中直接执行指令, 阻塞时可以挂起
+    suspendable: bool,
+    // 时间片结束前让出
+    yielded: bool,
 }
 
 // FIXME trait的可见性问题
@@ -42,19 +67,46 @@ impl LocalVarsLike for Thread {
     }
 }
 
+impl ClassInitializer for Thread {
+    fn intern(&mut self, string: JavaString) -> Result<ObjectRef, RuntimeError> {
+        self.vm.intern(string, || self.roots())
+    }
+    fn run_clinit(&mut self, _: &Arc<Klass>, clinit: Arc<Method>) -> Result<(), RuntimeError> {
+        self.run_method(clinit).map(|_| ())
+    }
+    fn wait(&mut self, klass: &Arc<Klass>) -> Result<(), RuntimeError> {
+        self.park(Blocker::Initialization(klass.clone()))
+    }
+}
+
 impl Thread {
-    pub fn new(id: u64, method: Method, constant_pool: Arc<ConstantPool>) -> Self {
+    pub fn new(id: u64, method: Arc<Method>, vm: Arc<Vm>) -> Self {
         let initial_frame = Frame::new(method, 0);
-        {
-            let constant_pool = constant_pool.clone();
-            Self {
-                id,
-                stack: vec![initial_frame],
-                state: ThreadState::Running,
-                constant_pool: constant_pool,
-            }
+        Self {
+            id,
+            stack: vec![initial_frame],
+            vm,
+            entry_depth: 0,
+            return_value: None,
+            backward_branches: 0,
+            executed: 0,
+            suspendable: false,
+            yielded: false,
         }
     }
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+    pub fn vm(&self) -> &Arc<Vm> {
+        &self.vm
+    }
+    pub fn executed(&self) -> u64 {
+        self.executed
+    }
+    /// 当前方法所属类的运行时常量池
+    pub fn constant_pool(&self) -> Arc<RuntimeConstantPool> {
+        self.current_frame().method().constant_pool.clone()
+    }
     pub fn current_frame_mut(&mut self) -> &mut Frame {
         debug_assert!(!self.stack.is_empty(), "none frame");
         let len = self.stack.len();
@@ -65,6 +117,687 @@ impl Thread {
         let len = self.stack.len();
         unsafe { self.stack.get_unchecked(len - 1) }
     }
+    /// 按JVMS 5.5初始化类, `<clinit>`在当前线程上执行.
+    /// 类、父类或父接口正在由其他线程初始化时阻塞到初始化结束,
+    /// 可以挂起的线程挂起, 恢复后重新执行当前指令
+    pub fn initialize_class(&mut self, klass: &Arc<Klass>) -> Result<(), RuntimeError> {
+        klass.initialize(self.id, self)
+    }
+    /// 压入新栈帧, 按方法描述符从调用者的操作数栈弹出参数放入局部变量表.
+    /// 同步方法在弹出参数之前获得锁, 挂起后可以重新执行调用指令
+    pub fn push_frame(&mut self, method: Arc<Method>) -> Result<(), RuntimeError> {
+        let this = self.stacked_this(&method)?;
+        let monitor = self.enter_method_monitor(&method, this)?;
+        let args = self.pop_args(&method)?;
+        self.push_frame_with_args(method, args, monitor)
+    }
+    /// 操作数栈上同步实例方法的接收者
+    fn stacked_this(&self, method: &Method) -> Result<Option<ObjectRef>, RuntimeError> {
+        if method.is_static || !method.is_synchronized() {
+            return Ok(None);
+        }
+        let depth = method.parsed_descriptor()?.parameters.len();
+        Ok(self.current_frame().peek(depth).clone().into())
+    }
+    /// 按方法描述符从操作数栈弹出参数, 按声明顺序返回
+    fn pop_args(&mut self, method: &Method) -> Result<Vec<Slot>, RuntimeError> {
+        let descriptor = method.parsed_descriptor()?;
+        let arg_count = descriptor.parameters.len() + usize::from(!method.is_static);
+        let mut args: Vec<Slot> = (0..arg_count).map(|_| self.pop()).collect();
+        args.reverse();
+        Ok(args)
+    }
+    /// `monitor`为同步方法已经获得的锁, 由栈帧弹出时释放
+    fn push_frame_with_args(
+        &mut self,
+        method: Arc<Method>,
+        args: Vec<Slot>,
+        monitor: Option<ObjectRef>,
+    ) -> Result<(), RuntimeError> {
+        if self.stack.len() >= MAX_STACK_DEPTH {
+            if let Some(monitor) = monitor {
+                self.vm.heap().monitor_exit(monitor, self.id)?;
+            }
+            return Err(RuntimeError::StackOverflowError);
+        }
+        let return_pc = self.stack.last().map_or(0, |frame| frame.pc);
+        let mut frame = Frame::new(method, return_pc);
+        frame.set_args(args);
+        frame.monitor = monitor;
+        self.stack.push(frame);
+        Ok(())
+    }
+    /// 同步方法要获得的锁: 实例方法为this, 静态方法为所属类的Class对象
+    fn method_monitor(
+        &self,
+        method: &Method,
+        this: Option<ObjectRef>,
+    ) -> Result<Option<ObjectRef>, RuntimeError> {
+        if !method.is_synchronized() {
+            return Ok(None);
+        }
+        if method.is_static {
+            let klass = self
+                .vm
+                .class_loader()
+                .load_class(method.constant_pool.class_name())?;
+            return Ok(Some(klass.mirror(self.vm.heap())));
+        }
+        Ok(this)
+    }
+    /// 进入同步方法的锁, 其他线程持有时阻塞
+    fn enter_method_monitor(
+        &mut self,
+        method: &Method,
+        this: Option<ObjectRef>,
+    ) -> Result<Option<ObjectRef>, RuntimeError> {
+        let monitor = self.method_monitor(method, this)?;
+        if let Some(monitor) = monitor {
+            self.enter_monitor(monitor)?;
+        }
+        Ok(monitor)
+    }
+    /// 获得对象的锁, 其他线程持有时阻塞
+    pub fn enter_monitor(&mut self, object: ObjectRef) -> Result<(), RuntimeError> {
+        while !self.vm.heap().monitor_enter(object, self.id) {
+            self.park(Blocker::Monitor(object))?;
+        }
+        Ok(())
+    }
+    /// 阻塞到`blocker`的条件满足. 在调度器的时间片中直接执行指令时挂起线程,
+    /// 恢复后重新执行当前指令; 否则(如`<clinit>`和本地方法中的调用)在当前的Rust栈上执行其他线程
+    pub fn park(&mut self, blocker: Blocker) -> Result<(), RuntimeError> {
+        let vm = self.vm.clone();
+        if self.suspendable {
+            vm.scheduler().block(self.id, blocker);
+            return Err(RuntimeError::Parked);
+        }
+        vm.scheduler().block_in_place(self, blocker)
+    }
+    /// `Thread.yield`, 让其他线程先执行
+    pub fn yield_now(&mut self) {
+        if self.suspendable {
+            self.yielded = true;
+        } else {
+            let vm = self.vm.clone();
+            vm.scheduler().yield_in_place(self);
+        }
+    }
+    /// 在Rust调用中嵌套执行, 期间线程不能挂起
+    fn nested<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let suspendable = std::mem::replace(&mut self.suspendable, false);
+        let result = f(self);
+        self.suspendable = suspendable;
+        result
+    }
+    /// 压入新栈帧并执行到该栈帧返回
+    fn run_method(&mut self, method: Arc<Method>) -> Result<Option<Slot>, RuntimeError> {
+        self.nested(|thread| {
+            let depth = thread.stack.len();
+            thread.push_frame(method)?;
+            thread.run_until(depth)
+        })
+    }
+    /// 以`args`为参数调用方法, 实例方法的第一个参数为this
+    pub fn call(
+        &mut self,
+        method: Arc<Method>,
+        args: Vec<Slot>,
+    ) -> Result<Option<Slot>, RuntimeError> {
+        let this = match (method.is_static, args.first()) {
+            (false, Some(Slot::Ref(this))) => *this,
+            _ => None,
+        };
+        self.nested(|thread| {
+            let depth = thread.stack.len();
+            let monitor = thread.enter_method_monitor(&method, this)?;
+            thread.push_frame_with_args(method, args, monitor)?;
+            thread.run_until(depth)
+        })
+    }
+    /// 执行到线程栈为空, 返回最外层方法的返回值
+    pub fn run(&mut self) -> Result<Option<Slot>, RuntimeError> {
+        self.run_until(0)
+    }
+    fn run_until(&mut self, depth: usize) -> Result<Option<Slot>, RuntimeError> {
+        let entry_depth = std::mem::replace(&mut self.entry_depth, depth);
+        let result = loop {
+            if self.stack.len() <= depth {
+                break Ok(self.return_value.take());
+            }
+            if let Err(err) = self.step(depth) {
+                self.unwind(depth);
+                break Err(err);
+            }
+        };
+        self.entry_depth = entry_depth;
+        result
+    }
+    /// 由调度器调用, 最多执行`budget`条指令. 时间片用完, 挂起或让出时返回None,
+    /// 线程结束时返回结果
+    pub fn run_slice(&mut self, budget: u32) -> Option<Result<(), RuntimeError>> {
+        self.suspendable = true;
+        let mut remaining = budget;
+        let result = loop {
+            if self.stack.is_empty() {
+                self.return_value = None;
+                break Some(Ok(()));
+            }
+            if std::mem::take(&mut self.yielded) || remaining == 0 {
+                break None;
+            }
+            remaining -= 1;
+            match self.step(0) {
+                Ok(()) => {}
+                Err(RuntimeError::Parked) => break None,
+                Err(err) => {
+                    self.unwind(0);
+                    break Some(Err(err));
+                }
+            }
+        };
+        self.suspendable = false;
+        result
+    }
+    /// 执行一条指令, 在`depth`之上的栈帧中处理异常. 挂起时当前指令在恢复后重新执行
+    fn step(&mut self, depth: usize) -> Result<(), RuntimeError> {
+        self.executed += 1;
+        match self.checked_fetch().and_then(|opcode| self.execute(opcode)) {
+            Ok(()) => Ok(()),
+            Err(RuntimeError::Parked) => Err(RuntimeError::Parked),
+            Err(err) => self.handle_exception(err, depth),
+        }
+    }
+    /// 执行到方法代码的末尾之外时抛出VerifyError
+    fn checked_fetch(&self) -> Result<u8, RuntimeError> {
+        let frame = self.current_frame();
+        let method = frame.method();
+        method.code.get(frame.pc as usize).copied().ok_or_else(|| {
+            RuntimeError::VerifyError(format!(
+                "Falling off the end of the code in {}.{}{}",
+                method.constant_pool.class_name(),
+                method.name,
+                method.descriptor
+            ))
+        })
+    }
+    fn fetch(&self) -> u8 {
+        let frame = self.current_frame();
+        frame.method().code[frame.pc as usize]
+    }
+    fn illegal_opcode(&self, opcode: u8) -> RuntimeError {
+        let frame = self.current_frame();
+        let method = frame.method();
+        RuntimeError::IllegalOpcode {
+            opcode,
+            pc: frame.pc,
+            method: format!(
+                "{}.{}{}",
+                method.constant_pool.class_name(),
+                method.name,
+                method.descriptor
+            ),
+        }
+    }
+    /// 读取当前pc之后`offset`处的操作数
+    fn read_operand<T: Operand>(&self, offset: u16) -> T {
+        let frame = self.current_frame();
+        T::read(&frame.method().code, (frame.pc + offset) as usize)
+    }
+    /// 读取switch跳转表中当前pc之后`offset`处的i32, 表项的偏移可能超出u16
+    fn read_switch_entry(&self, offset: usize) -> i32 {
+        let frame = self.current_frame();
+        i32::read(&frame.method().code, frame.pc as usize + offset)
+    }
+    /// 弹出当前栈帧, 返回到调用者. 同步方法的锁已经不被当前线程持有时,
+    /// 在调用者中抛出IllegalMonitorStateException
+    fn return_from_method(&mut self, value: Option<Slot>) -> Result<(), RuntimeError> {
+        self.pop_frame()?;
+        if self.stack.len() <= self.entry_depth {
+            self.return_value = value;
+            return Ok(());
+        }
+        self.complete_invoke(value);
+        Ok(())
+    }
+    /// 弹出栈帧, 同步方法释放进入时获得的锁
+    fn pop_frame(&mut self) -> Result<(), RuntimeError> {
+        let frame = self.stack.pop().expect("none frame");
+        match frame.monitor {
+            Some(monitor) => self.vm.heap().monitor_exit(monitor, self.id),
+            None => Ok(()),
+        }
+    }
+    /// 异常没有被处理时弹出`depth`之上的所有栈帧
+    fn unwind(&mut self, depth: usize) {
+        while self.stack.len() > depth {
+            let _ = self.pop_frame();
+        }
+    }
+    /// 跳过当前的调用指令, 返回值压入操作数栈
+    fn complete_invoke(&mut self, value: Option<Slot>) {
+        let length = match self.fetch() {
+            0xb9 | 0xba => 5,
+            _ => 3,
+        };
+        self.inc_pc(length);
+        if let Some(value) = value {
+            self.push(value);
+        }
+    }
+    fn load_local(&mut self, index: usize) {
+        let value: Slot = self.get(index);
+        self.push(value);
+    }
+    fn store_local(&mut self, index: usize) {
+        let value: Slot = self.pop();
+        self.set(index, value);
+    }
+    fn iinc_local(&mut self, index: usize, value: i32) {
+        let local: i32 = self.get(index);
+        self.set(index, local.wrapping_add(value));
+    }
+    /// 弹出合计`words`个字的值(category 2的值算两个字), 按入栈顺序返回
+    fn pop_words(&mut self, words: usize) -> Vec<Slot> {
+        let mut values = vec![];
+        let mut count = 0;
+        while count < words {
+            let value: Slot = self.pop();
+            count += value.category();
+            values.push(value);
+        }
+        debug_assert_eq!(count, words, "category mismatch");
+        values.reverse();
+        values
+    }
+    /// 复制栈顶`top`个字的值, 插入到其下`below`个字之下, 覆盖dup的所有形式
+    fn dup_words(&mut self, top: usize, below: usize) {
+        let top_values = self.pop_words(top);
+        let below_values = self.pop_words(below);
+        for value in top_values
+            .iter()
+            .chain(&below_values)
+            .chain(top_values.iter())
+        {
+            self.push(value.clone());
+        }
+        self.inc_pc(1);
+    }
+    fn array_load(&mut self) -> Result<(), RuntimeError> {
+        let index: i32 = self.pop();
+        let array: Option<ObjectRef> = self.pop();
+        let value = self.vm.heap().array_load(array, index)?;
+        self.push(value);
+        self.inc_pc(1);
+        Ok(())
+    }
+    fn array_store(&mut self) -> Result<(), RuntimeError> {
+        let value: Slot = self.pop();
+        let index: i32 = self.pop();
+        let array: Option<ObjectRef> = self.pop();
+        self.vm.heap().array_store(array, index, value)?;
+        self.inc_pc(1);
+        Ok(())
+    }
+    /// 把错误转换为Java异常对象, 在`depth`之上的栈帧中由内向外查找异常处理器.
+    /// 找到时清空该栈帧的操作数栈, 压入异常并跳转到处理器, 否则返回异常
+    fn handle_exception(&mut self, err: RuntimeError, depth: usize) -> Result<(), RuntimeError> {
+        let mut err = self.convert_throwable(err);
+        while self.stack.len() > depth {
+            let RuntimeError::Throwable { object, .. } = err else {
+                return Err(err);
+            };
+            let class = self.object_class(object)?;
+            if let Some(handler_pc) = self.find_handler(&class)? {
+                let frame = self.current_frame_mut();
+                frame.clear_operand_stack();
+                frame.push(Some(object));
+                frame.pc = handler_pc;
+                return Ok(());
+            }
+            // 同步方法的锁已经不被当前线程持有时, 改为抛出IllegalMonitorStateException
+            if let Err(exit_err) = self.pop_frame() {
+                err = self.convert_throwable(exit_err);
+            }
+        }
+        Err(err)
+    }
+    /// 当前栈帧的异常表中第一个范围包含pc且能捕获`class`的处理器
+    fn find_handler(&self, class: &Klass) -> Result<Option<u16>, RuntimeError> {
+        let frame = self.current_frame();
+        let method = frame.method();
+        for handler in &method.exception_table {
+            if !(handler.start_pc..handler.end_pc).contains(&frame.pc) {
+                continue;
+            }
+            if handler.catch_type == 0 {
+                return Ok(Some(handler.handler_pc));
+            }
+            let catch_type = method
+                .constant_pool
+                .resolve_class(handler.catch_type, self.vm.class_loader())?;
+            if class.is_subclass_of(&catch_type) {
+                return Ok(Some(handler.handler_pc));
+            }
+        }
+        Ok(None)
+    }
+    /// 虚拟机抛出的错误转换为对应异常类的实例, 不能转换时原样返回
+    fn convert_throwable(&mut self, err: RuntimeError) -> RuntimeError {
+        if matches!(err, RuntimeError::Throwable { .. }) {
+            return err;
+        }
+        match self.create_throwable(&err) {
+            Some(object) => self.thrown(object),
+            None => err,
+        }
+    }
+    fn create_throwable(&mut self, err: &RuntimeError) -> Option<ObjectRef> {
+        self.new_throwable(err.class_name()?, err.message().as_deref())
+            .ok()
+    }
+    /// 不执行构造方法, 直接设置detailMessage并填充栈轨迹
+    pub fn new_throwable(
+        &mut self,
+        class_name: &str,
+        message: Option<&str>,
+    ) -> Result<ObjectRef, RuntimeError> {
+        let klass = self.vm.class_loader().load_class(class_name)?;
+        self.initialize_class(&klass)?;
+        let (_, field) = klass
+            .lookup_field("detailMessage", "Ljava/lang/String;")
+            .ok_or_else(|| {
+                RuntimeError::NoSuchFieldError(format!("{}.detailMessage", class_name))
+            })?;
+        let message = message.map(JavaString::from);
+        let size = instance_bytes(&klass) + message.as_ref().map_or(0, string_bytes);
+        self.reserve_throwable(&klass, size, None)?;
+        let heap = self.vm.heap();
+        let object = heap.alloc_instance(klass);
+        let message = message.map(|message| heap.alloc(Object::String(message)));
+        heap.put_field(Some(object), &field, Slot::Ref(message))?;
+        self.fill_in_stack_trace(object)?;
+        Ok(object)
+    }
+    /// 记录当前的栈轨迹, 跳过`fillInStackTrace`和异常类自身的构造方法
+    pub fn fill_in_stack_trace(&self, throwable: ObjectRef) -> Result<(), RuntimeError> {
+        let class = self.object_class(throwable)?;
+        let is_throwable_class = |name: &str| {
+            let mut current = Some(&class);
+            while let Some(class) = current {
+                if class.name() == name {
+                    return true;
+                }
+                current = class.super_class();
+            }
+            false
+        };
+        let elements = self
+            .stack
+            .iter()
+            .rev()
+            .skip_while(|frame| frame.method().name == "fillInStackTrace")
+            .skip_while(|frame| {
+                let method = frame.method();
+                method.name == "<init>" && is_throwable_class(method.constant_pool.class_name())
+            })
+            .map(|frame| StackTraceElement::new(frame.method(), frame.pc))
+            .collect::<Vec<_>>();
+        self.reserve_throwable(&class, backtrace_bytes(elements.len()), Some(throwable))?;
+        set_backtrace(self.vm.heap(), throwable, elements);
+        Ok(())
+    }
+    /// 创建异常对象或栈轨迹之前的安全点, `throwable`是已经创建的异常对象.
+    /// 堆已满时OutOfMemoryError自身也要能创建, 只回收不检查上限
+    fn reserve_throwable(
+        &self,
+        class: &Klass,
+        size: usize,
+        throwable: Option<ObjectRef>,
+    ) -> Result<(), RuntimeError> {
+        let result = self.vm.reserve(size, || {
+            let mut roots = self.roots();
+            roots.extend(throwable);
+            roots
+        });
+        match result {
+            Err(RuntimeError::OutOfMemoryError(_))
+                if class.name() == "java/lang/OutOfMemoryError" =>
+            {
+                Ok(())
+            }
+            result => result,
+        }
+    }
+    /// athrow抛出的异常对象
+    pub fn thrown(&self, object: ObjectRef) -> RuntimeError {
+        let heap = self.vm.heap();
+        let Some(klass) = heap.klass(object) else {
+            return RuntimeError::IllegalState;
+        };
+        let message = klass
+            .lookup_field("detailMessage", "Ljava/lang/String;")
+            .and_then(|(_, field)| match heap.get_field(Some(object), &field) {
+                Ok(Slot::Ref(Some(message))) => Some(message),
+                _ => None,
+            })
+            .and_then(|message| match heap.get(message) {
+                Object::String(message) => Some(message),
+                _ => None,
+            });
+        let class_name = klass.name().replace('/', ".");
+        let mut is_error = false;
+        let mut current = Some(&klass);
+        while let Some(class) = current {
+            is_error |= class.name() == "java/lang/Error";
+            current = class.super_class();
+        }
+        RuntimeError::Throwable {
+            object,
+            description: match message {
+                Some(message) => format!("{}: {}", class_name, message),
+                None => class_name,
+            },
+            is_error,
+        }
+    }
+    /// 当前方法所属的类
+    fn current_class(&self) -> Result<Arc<Klass>, RuntimeError> {
+        self.vm
+            .class_loader()
+            .load_class(self.current_frame().method().constant_pool.class_name())
+    }
+    /// 对象的运行时类
+    pub fn object_class(&self, object_ref: ObjectRef) -> Result<Arc<Klass>, RuntimeError> {
+        let heap = self.vm.heap();
+        if let Some(klass) = heap.klass(object_ref) {
+            return Ok(klass);
+        }
+        let name = heap.with_object(object_ref, |object| match object {
+            Object::Class(_) => "java/lang/Class",
+            Object::String(_) => "java/lang/String",
+            Object::MethodType(_) => "java/lang/invoke/MethodType",
+            Object::MethodHandle(_) => "java/lang/invoke/MethodHandle",
+            // 数组的方法都继承自Object
+            Object::Array(_) | Object::Instance(_) | Object::Backtrace(_) => "java/lang/Object",
+        });
+        self.vm.class_loader().load_class(name)
+    }
+    /// instanceof, checkcast和aastore的类型检查
+    fn is_instance_of(&self, object_ref: ObjectRef, class: &Klass) -> Result<bool, RuntimeError> {
+        Ok(self.object_class(object_ref)?.is_subclass_of(class))
+    }
+    /// 栈帧中和尚未交给调用者的返回值中的引用
+    pub fn roots(&self) -> Vec<ObjectRef> {
+        let mut roots = vec![];
+        for frame in &self.stack {
+            frame.push_roots(&mut roots);
+        }
+        if let Some(Slot::Ref(Some(object_ref))) = &self.return_value {
+            roots.push(*object_ref);
+        }
+        roots
+    }
+    /// 分配`size`字节之前的安全点, 可能触发GC
+    pub fn reserve(&self, size: usize) -> Result<(), RuntimeError> {
+        self.vm.reserve(size, || self.roots())
+    }
+    /// 分配`class`的(多维)数组之前的安全点, 长度非法时由分配时报告
+    fn reserve_arrays(&self, class: &Klass, counts: &[i32]) -> Result<(), RuntimeError> {
+        let mut size = 0usize;
+        let mut arrays = 1usize;
+        let mut class = Some(class);
+        for count in counts {
+            let (Some(klass), Ok(count)) = (class, usize::try_from(*count)) else {
+                return Ok(());
+            };
+            let Some(component) = klass.component_type() else {
+                return Ok(());
+            };
+            if count > MAX_ARRAY_LENGTH {
+                return Ok(());
+            }
+            size = size.saturating_add(arrays.saturating_mul(array_bytes(component, count)));
+            arrays = arrays.saturating_mul(count);
+            class = klass.component_class().map(AsRef::as_ref);
+        }
+        self.reserve(size)
+    }
+    /// 分配`class`的数组, 长度为负时抛出NegativeArraySizeException
+    fn new_array(&self, class: Arc<Klass>, count: i32) -> Result<ObjectRef, RuntimeError> {
+        if count < 0 {
+            return Err(RuntimeError::NegativeArraySizeException(count.to_string()));
+        }
+        self.vm.heap().alloc_array(class, count as usize)
+    }
+    /// 按`counts`逐维分配多维数组, 其后的维度作为元素
+    fn new_multi_array(
+        &self,
+        class: &Arc<Klass>,
+        counts: &[i32],
+    ) -> Result<ObjectRef, RuntimeError> {
+        let array = self.new_array(class.clone(), counts[0])?;
+        if let (Some(component), [_, rest @ ..]) = (class.component_class(), counts)
+            && !rest.is_empty()
+        {
+            for index in 0..counts[0] {
+                let element = self.new_multi_array(component, rest)?;
+                self.vm
+                    .heap()
+                    .array_store(Some(array), index, Slot::Ref(Some(element)))?;
+            }
+        }
+        Ok(array)
+    }
+    /// final字段只能由声明它的类在`initializer`(`<init>`或`<clinit>`)中赋值
+    fn check_final_store(
+        &self,
+        field_ref: &FieldRef,
+        initializer: &str,
+    ) -> Result<(), RuntimeError> {
+        let field = &field_ref.field;
+        if !field.is_final() {
+            return Ok(());
+        }
+        let method = self.current_frame().method();
+        let class_name = method.constant_pool.class_name();
+        let kind = if field.is_static() {
+            "static"
+        } else {
+            "non-static"
+        };
+        let field_name = format!(
+            "{}.{}",
+            field_ref.klass.name().replace('/', "."),
+            field.name
+        );
+        if field_ref.klass.name() != class_name {
+            return Err(RuntimeError::IllegalAccessError(format!(
+                "Update to {} final field {} attempted from a different class ({}) than the field's declaring class",
+                kind,
+                field_name,
+                class_name.replace('/', ".")
+            )));
+        }
+        if method.name != initializer {
+            return Err(RuntimeError::IllegalAccessError(format!(
+                "Update to {} final field {} attempted from a different method ({}) than the initializer method {}",
+                kind, field_name, method.name, initializer
+            )));
+        }
+        Ok(())
+    }
+    /// 操作数栈上参数之下的接收者, 为null时抛出NullPointerException
+    fn receiver(&self, method: &Method) -> Result<ObjectRef, RuntimeError> {
+        let depth = method.parsed_descriptor()?.parameters.len();
+        let receiver: Option<ObjectRef> = self.current_frame().peek(depth).clone().into();
+        receiver.ok_or_else(|| {
+            RuntimeError::NullPointerException(format!(
+                "Cannot invoke \"{}.{}()\" because receiver is null",
+                method.constant_pool.class_name().replace('/', "."),
+                method.name
+            ))
+        })
+    }
+    /// 经由当前调用点的内联缓存选择方法, 虚拟机关闭了内联缓存时直接查表
+    fn select_cached(
+        &self,
+        receiver_class: &Arc<Klass>,
+        method_ref: &MethodRef,
+    ) -> Result<Arc<Method>, RuntimeError> {
+        let frame = self.current_frame();
+        match frame.method().inline_caches.get(frame.pc) {
+            Some(cache) if self.vm.inline_caches() => cache.lookup(receiver_class, || {
+                select_virtual(receiver_class, method_ref)
+            }),
+            _ => select_virtual(receiver_class, method_ref),
+        }
+    }
+    /// 进入选择出的方法, 调用者的pc停在调用指令上直到方法返回
+    fn invoke(&mut self, method: Arc<Method>) -> Result<(), RuntimeError> {
+        let name = || {
+            format!(
+                "{}.{}{}",
+                method.constant_pool.class_name(),
+                method.name,
+                method.descriptor
+            )
+        };
+        // 本地方法不压入栈帧, 在调用者的栈帧上直接执行
+        if method.is_native() {
+            let native = self
+                .vm
+                .natives()
+                .lookup(
+                    method.constant_pool.class_name(),
+                    &method.name,
+                    &method.descriptor,
+                )
+                .ok_or_else(|| RuntimeError::UnsatisfiedLinkError(name()))?;
+            let this = self.stacked_this(&method)?;
+            let monitor = self.enter_method_monitor(&method, this)?;
+            let args = self.pop_args(&method)?;
+            let mut env = NativeEnv::new(self, args);
+            let result = native(&mut env);
+            let args = env.into_args();
+            if let Some(monitor) = monitor {
+                self.vm.heap().monitor_exit(monitor, self.id)?;
+            }
+            if let Err(RuntimeError::Parked) = result {
+                // 恢复参数, 以便重新执行调用指令
+                for arg in args {
+                    self.push(arg);
+                }
+                return Err(RuntimeError::Parked);
+            }
+            self.complete_invoke(result?);
+            return Ok(());
+        }
+        if method.is_abstract() {
+            return Err(RuntimeError::AbstractMethodError(name()));
+        }
+        self.push_frame(method)
+    }
     fn inc_pc(&mut self, val: u16) {
         self.current_frame_mut().pc += val;
     }
@@ -75,74 +808,3449 @@ impl Thread {
         let pc = setter(&self.current_frame().pc);
         self.current_frame_mut().pc = pc;
     }
+    /// 跳转到相对于当前指令地址的`offset`处
+    fn branch(&mut self, offset: i32) {
+        if offset <= 0 {
+            self.on_backward_branch();
+        }
+        self.set_pc(|pc| (*pc as i32 + offset) as u16);
+    }
+    /// 条件成立时跳转, 否则执行下一条指令
+    fn branch_if(&mut self, condition: bool, offset: i16, length: u16) {
+        if condition {
+            self.branch(offset as i32);
+        } else {
+            self.inc_pc(length);
+        }
+    }
+    /// 循环的回边, 以后在这里检查安全点和收集profiling信息
+    fn on_backward_branch(&mut self) {
+        self.backward_branches += 1;
+    }
+    /// tableswitch和lookupswitch的操作数从4字节对齐的地址开始
+    fn switch_padding(&self) -> u16 {
+        let pc = self.current_frame().pc;
+        3 - pc % 4
+    }
 }
 
-define_instructions! {
-    0x00 => nop {
-        fn nop() { self.inc_pc(1) }
-    };
-    //0x01
-    0x02 => iconst_m1 {
-        fn iconst_m1() {
-            self.push::<i32>(-1);
+/// ldc, ldc_w和ldc2_w共用
+macro_rules! load_constant {
+    ($self: ident, $index: expr) => {{
+        let constant_pool = $self.constant_pool();
+        match constant_pool.get($index)? {
+            Constant::Integer(integer) => $self.push(Slot::Bits32(integer.bytes)),
+            Constant::Float(float) => $self.push(Slot::Bits32(float.bytes)),
+            Constant::Long(long) => $self.push(Slot::Bits64(
+                ((long.high_bytes as u64) << 32) | long.low_bytes as u64,
+            )),
+            Constant::Double(double) => $self.push(Slot::Bits64(
+                ((double.high_bytes as u64) << 32) | double.low_bytes as u64,
+            )),
+            Constant::Dynamic(_) => {
+                let value = bootstrap::resolve_dynamic($self, &constant_pool, $index)?;
+                $self.push(value);
+            }
+            _ => {
+                let object_ref =
+                    constant_pool.resolve_object($index, &$self.vm, || $self.roots())?;
+                $self.push(Some(object_ref));
+            }
+        }
+    }};
+}
+
+/// 非静态方法不能用invokestatic调用, 反之亦然
+fn expect_static(method: &Method, is_static: bool) -> Result<(), RuntimeError> {
+    if method.is_static == is_static {
+        return Ok(());
+    }
+    Err(RuntimeError::IncompatibleClassChangeError(format!(
+        "Expected {} method '{}.{}{}'",
+        if is_static { "static" } else { "non-static" },
+        method.constant_pool.class_name(),
+        method.name,
+        method.descriptor
+    )))
+}
+
+/// getstatic和putstatic只能访问静态字段, getfield和putfield只能访问实例字段
+fn expect_static_field(field_ref: &FieldRef, is_static: bool) -> Result<(), RuntimeError> {
+    if field_ref.field.is_static() == is_static {
+        return Ok(());
+    }
+    Err(RuntimeError::IncompatibleClassChangeError(format!(
+        "Expected {} field {}.{}",
+        if is_static { "static" } else { "non-static" },
+        field_ref.klass.name().replace('/', "."),
+        field_ref.field.name
+    )))
+}
+
+/// xload_<n>
+macro_rules! load_n {
+    ($name: ident, $index: expr) => {
+        fn $name(&mut self) {
+            self.load_local($index);
             self.inc_pc(1);
         }
     };
-    // ...
-    0x18 => ldc {
-        fn ldc() {
+}
 
+/// xstore_<n>
+macro_rules! store_n {
+    ($name: ident, $index: expr) => {
+        fn $name(&mut self) {
+            self.store_local($index);
+            self.inc_pc(1);
         }
-    }
+    };
 }
 
-#[cfg(test)]
-mod tests {
-    use std::sync::Arc;
+/// 弹出两个操作数, 压入运算结果
+macro_rules! binary_op {
+    ($name: ident, $ty: ty, |$a: ident, $b: ident| $expr: expr) => {
+        fn $name(&mut self) {
+            let $b: $ty = self.pop();
+            let $a: $ty = self.pop();
+            self.push::<$ty>($expr);
+            self.inc_pc(1);
+        }
+    };
+}
 
-    use rstest::{fixture, rstest};
+macro_rules! unary_op {
+    ($name: ident, $ty: ty, |$a: ident| $expr: expr) => {
+        fn $name(&mut self) {
+            let $a: $ty = self.pop();
+            self.push::<$ty>($expr);
+            self.inc_pc(1);
+        }
+    };
+}
 
-    use crate::{
-        constant_pool::{Constant, ConstantPool},
-        runtime::{Method, thread::Thread},
+/// 移位距离总是int, wrapping_shl/wrapping_shr会按位宽取低5位或低6位
+macro_rules! shift_op {
+    ($name: ident, $ty: ty, |$a: ident, $b: ident| $expr: expr) => {
+        fn $name(&mut self) {
+            let $b: i32 = self.pop();
+            let $a: $ty = self.pop();
+            self.push::<$ty>($expr);
+            self.inc_pc(1);
+        }
     };
+}
 
-    #[fixture]
-    fn thread() -> Thread {
-        let constant_pool = ConstantPool::from(vec![
-            Constant::Invalid,
-            Constant::from("some string".to_string()),
-        ]);
-        let constant_pool = Arc::new(constant_pool);
+/// 浮点数转整数时`as`的语义与JLS 5.1.3相同: 向零取整, 溢出时饱和, NaN为0
+macro_rules! convert_op {
+    ($name: ident, $from: ty, $to: ty, |$a: ident| $expr: expr) => {
+        fn $name(&mut self) {
+            let $a: $from = self.pop();
+            self.push::<$to>($expr);
+            self.inc_pc(1);
+        }
+    };
+}
 
-        let method = Method {
-            max_stack: 100,
-            ..Default::default()
-        };
-        let thread = Thread::new(0, method, constant_pool);
-        thread
-    }
+/// 大于为1, 等于为0, 小于为-1, 有NaN时为`$nan`
+macro_rules! compare_op {
+    ($name: ident, $ty: ty, $nan: expr) => {
+        fn $name(&mut self) {
+            let b: $ty = self.pop();
+            let a: $ty = self.pop();
+            let result: i32 = match a.partial_cmp(&b) {
+                Some(std::cmp::Ordering::Greater) => 1,
+                Some(std::cmp::Ordering::Equal) => 0,
+                Some(std::cmp::Ordering::Less) => -1,
+                None => $nan,
+            };
+            self.push(result);
+            self.inc_pc(1);
+        }
+    };
+}
 
-    #[rstest]
-    fn test_thread_frame(thread: Thread) {
-        assert_eq!(thread.id, 11);
-    }
-    #[rstest]
-    #[should_panic(expected = "none frame")]
-    fn test_thread_frame_panic(mut thread: Thread) {
-        let _ = thread.stack.pop();
-        let _ = thread.current_frame();
-    }
-    #[rstest]
-    fn test_nop(mut thread: Thread) {
-        thread.execute_nop();
-        assert_eq!(thread.current_frame().pc, 1);
-    }
+fn divide_by_zero() -> RuntimeError {
+    RuntimeError::ArithmeticException("/ by zero".to_string())
+}
 
-    #[rstest]
-    fn test_iconst_m1(mut thread: Thread) {
-        thread.execute_iconst_m1();
-        assert_eq!(thread.current_frame().pc, 1);
-        assert_eq!(thread.current_frame().top::<i32>(), -1)
+define_instructions! {
+    0x00 => nop {
+        fn nop() { self.inc_pc(1) }
+    };
+    0x01 => aconst_null {
+        fn aconst_null() {
+            self.push(Slot::Ref(None));
+            self.inc_pc(1);
+        }
+    };
+    0x02 => iconst_m1 {
+        fn iconst_m1() {
+            self.push::<i32>(-1);
+            self.inc_pc(1);
+        }
+    };
+    0x03 => iconst_0 {
+        fn iconst_0() {
+            self.push::<i32>(0);
+            self.inc_pc(1);
+        }
+    };
+    0x04 => iconst_1 {
+        fn iconst_1() {
+            self.push::<i32>(1);
+            self.inc_pc(1);
+        }
+    };
+    0x05 => iconst_2 {
+        fn iconst_2() {
+            self.push::<i32>(2);
+            self.inc_pc(1);
+        }
+    };
+    0x06 => iconst_3 {
+        fn iconst_3() {
+            self.push::<i32>(3);
+            self.inc_pc(1);
+        }
+    };
+    0x07 => iconst_4 {
+        fn iconst_4() {
+            self.push::<i32>(4);
+            self.inc_pc(1);
+        }
+    };
+    0x08 => iconst_5 {
+        fn iconst_5() {
+            self.push::<i32>(5);
+            self.inc_pc(1);
+        }
+    };
+    0x09 => lconst_0 {
+        fn lconst_0() {
+            self.push::<i64>(0);
+            self.inc_pc(1);
+        }
+    };
+    0x0a => lconst_1 {
+        fn lconst_1() {
+            self.push::<i64>(1);
+            self.inc_pc(1);
+        }
+    };
+    0x0b => fconst_0 {
+        fn fconst_0() {
+            self.push::<f32>(0.0);
+            self.inc_pc(1);
+        }
+    };
+    0x0c => fconst_1 {
+        fn fconst_1() {
+            self.push::<f32>(1.0);
+            self.inc_pc(1);
+        }
+    };
+    0x0d => fconst_2 {
+        fn fconst_2() {
+            self.push::<f32>(2.0);
+            self.inc_pc(1);
+        }
+    };
+    0x0e => dconst_0 {
+        fn dconst_0() {
+            self.push::<f64>(0.0);
+            self.inc_pc(1);
+        }
+    };
+    0x0f => dconst_1 {
+        fn dconst_1() {
+            self.push::<f64>(1.0);
+            self.inc_pc(1);
+        }
+    };
+    0x10 => bipush {
+        fn bipush(value: i8) {
+            self.push(value as i32);
+            self.inc_pc(2);
+        }
+    };
+    0x11 => sipush {
+        fn sipush(value: i16) {
+            self.push(value as i32);
+            self.inc_pc(3);
+        }
+    };
+    0x12 => ldc {
+        fn ldc(index: u8) -> Result<(), RuntimeError> {
+            load_constant!(self, index as u16);
+            self.inc_pc(2);
+            Ok(())
+        }
+    };
+    0x13 => ldc_w {
+        fn ldc_w(index: u16) -> Result<(), RuntimeError> {
+            load_constant!(self, index);
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0x14 => ldc2_w {
+        fn ldc2_w(index: u16) -> Result<(), RuntimeError> {
+            load_constant!(self, index);
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0x15 => iload {
+        fn iload(index: u8) {
+            self.load_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x16 => lload {
+        fn lload(index: u8) {
+            self.load_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x17 => fload {
+        fn fload(index: u8) {
+            self.load_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x18 => dload {
+        fn dload(index: u8) {
+            self.load_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x19 => aload {
+        fn aload(index: u8) {
+            self.load_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x1a => iload_0 {
+        load_n! { execute_iload_0, 0 }
+    };
+    0x1b => iload_1 {
+        load_n! { execute_iload_1, 1 }
+    };
+    0x1c => iload_2 {
+        load_n! { execute_iload_2, 2 }
+    };
+    0x1d => iload_3 {
+        load_n! { execute_iload_3, 3 }
+    };
+    0x1e => lload_0 {
+        load_n! { execute_lload_0, 0 }
+    };
+    0x1f => lload_1 {
+        load_n! { execute_lload_1, 1 }
+    };
+    0x20 => lload_2 {
+        load_n! { execute_lload_2, 2 }
+    };
+    0x21 => lload_3 {
+        load_n! { execute_lload_3, 3 }
+    };
+    0x22 => fload_0 {
+        load_n! { execute_fload_0, 0 }
+    };
+    0x23 => fload_1 {
+        load_n! { execute_fload_1, 1 }
+    };
+    0x24 => fload_2 {
+        load_n! { execute_fload_2, 2 }
+    };
+    0x25 => fload_3 {
+        load_n! { execute_fload_3, 3 }
+    };
+    0x26 => dload_0 {
+        load_n! { execute_dload_0, 0 }
+    };
+    0x27 => dload_1 {
+        load_n! { execute_dload_1, 1 }
+    };
+    0x28 => dload_2 {
+        load_n! { execute_dload_2, 2 }
+    };
+    0x29 => dload_3 {
+        load_n! { execute_dload_3, 3 }
+    };
+    0x2a => aload_0 {
+        load_n! { execute_aload_0, 0 }
+    };
+    0x2b => aload_1 {
+        load_n! { execute_aload_1, 1 }
+    };
+    0x2c => aload_2 {
+        load_n! { execute_aload_2, 2 }
+    };
+    0x2d => aload_3 {
+        load_n! { execute_aload_3, 3 }
+    };
+    0x2e => iaload {
+        fn iaload() -> Result<(), RuntimeError> {
+            self.array_load()
+        }
+    };
+    0x2f => laload {
+        fn laload() -> Result<(), RuntimeError> {
+            self.array_load()
+        }
+    };
+    0x30 => faload {
+        fn faload() -> Result<(), RuntimeError> {
+            self.array_load()
+        }
+    };
+    0x31 => daload {
+        fn daload() -> Result<(), RuntimeError> {
+            self.array_load()
+        }
+    };
+    0x32 => aaload {
+        fn aaload() -> Result<(), RuntimeError> {
+            self.array_load()
+        }
+    };
+    0x33 => baload {
+        fn baload() -> Result<(), RuntimeError> {
+            self.array_load()
+        }
+    };
+    0x34 => caload {
+        fn caload() -> Result<(), RuntimeError> {
+            self.array_load()
+        }
+    };
+    0x35 => saload {
+        fn saload() -> Result<(), RuntimeError> {
+            self.array_load()
+        }
+    };
+    0x36 => istore {
+        fn istore(index: u8) {
+            self.store_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x37 => lstore {
+        fn lstore(index: u8) {
+            self.store_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x38 => fstore {
+        fn fstore(index: u8) {
+            self.store_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x39 => dstore {
+        fn dstore(index: u8) {
+            self.store_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x3a => astore {
+        fn astore(index: u8) {
+            self.store_local(index as usize);
+            self.inc_pc(2);
+        }
+    };
+    0x3b => istore_0 {
+        store_n! { execute_istore_0, 0 }
+    };
+    0x3c => istore_1 {
+        store_n! { execute_istore_1, 1 }
+    };
+    0x3d => istore_2 {
+        store_n! { execute_istore_2, 2 }
+    };
+    0x3e => istore_3 {
+        store_n! { execute_istore_3, 3 }
+    };
+    0x3f => lstore_0 {
+        store_n! { execute_lstore_0, 0 }
+    };
+    0x40 => lstore_1 {
+        store_n! { execute_lstore_1, 1 }
+    };
+    0x41 => lstore_2 {
+        store_n! { execute_lstore_2, 2 }
+    };
+    0x42 => lstore_3 {
+        store_n! { execute_lstore_3, 3 }
+    };
+    0x43 => fstore_0 {
+        store_n! { execute_fstore_0, 0 }
+    };
+    0x44 => fstore_1 {
+        store_n! { execute_fstore_1, 1 }
+    };
+    0x45 => fstore_2 {
+        store_n! { execute_fstore_2, 2 }
+    };
+    0x46 => fstore_3 {
+        store_n! { execute_fstore_3, 3 }
+    };
+    0x47 => dstore_0 {
+        store_n! { execute_dstore_0, 0 }
+    };
+    0x48 => dstore_1 {
+        store_n! { execute_dstore_1, 1 }
+    };
+    0x49 => dstore_2 {
+        store_n! { execute_dstore_2, 2 }
+    };
+    0x4a => dstore_3 {
+        store_n! { execute_dstore_3, 3 }
+    };
+    0x4b => astore_0 {
+        store_n! { execute_astore_0, 0 }
+    };
+    0x4c => astore_1 {
+        store_n! { execute_astore_1, 1 }
+    };
+    0x4d => astore_2 {
+        store_n! { execute_astore_2, 2 }
+    };
+    0x4e => astore_3 {
+        store_n! { execute_astore_3, 3 }
+    };
+    0x4f => iastore {
+        fn iastore() -> Result<(), RuntimeError> {
+            self.array_store()
+        }
+    };
+    0x50 => lastore {
+        fn lastore() -> Result<(), RuntimeError> {
+            self.array_store()
+        }
+    };
+    0x51 => fastore {
+        fn fastore() -> Result<(), RuntimeError> {
+            self.array_store()
+        }
+    };
+    0x52 => dastore {
+        fn dastore() -> Result<(), RuntimeError> {
+            self.array_store()
+        }
+    };
+    0x53 => aastore {
+        fn aastore() -> Result<(), RuntimeError> {
+            let value: Option<ObjectRef> = self.current_frame().peek(0).clone().into();
+            let index: i32 = self.current_frame().peek(1).clone().into();
+            let array: Option<ObjectRef> = self.current_frame().peek(2).clone().into();
+            // 先检查null和下标, 再检查元素类型
+            let length = self.vm.heap().array_length(array)?;
+            if let (Some(array), Some(value)) = (array, value)
+                && (0..length).contains(&index)
+            {
+                let array_class = self.object_class(array)?;
+                if let Some(component) = array_class.component_class()
+                    && !self.is_instance_of(value, component)?
+                {
+                    return Err(RuntimeError::ArrayStoreException(
+                        self.object_class(value)?.name().replace('/', "."),
+                    ));
+                }
+            }
+            self.array_store()
+        }
+    };
+    0x54 => bastore {
+        fn bastore() -> Result<(), RuntimeError> {
+            self.array_store()
+        }
+    };
+    0x55 => castore {
+        fn castore() -> Result<(), RuntimeError> {
+            self.array_store()
+        }
+    };
+    0x56 => sastore {
+        fn sastore() -> Result<(), RuntimeError> {
+            self.array_store()
+        }
+    };
+    0x57 => pop {
+        fn pop() {
+            self.pop_words(1);
+            self.inc_pc(1);
+        }
+    };
+    0x58 => pop2 {
+        fn pop2() {
+            self.pop_words(2);
+            self.inc_pc(1);
+        }
+    };
+    0x59 => dup {
+        fn dup() {
+            self.dup_words(1, 0);
+        }
+    };
+    0x5a => dup_x1 {
+        fn dup_x1() {
+            self.dup_words(1, 1);
+        }
+    };
+    0x5b => dup_x2 {
+        fn dup_x2() {
+            self.dup_words(1, 2);
+        }
+    };
+    0x5c => dup2 {
+        fn dup2() {
+            self.dup_words(2, 0);
+        }
+    };
+    0x5d => dup2_x1 {
+        fn dup2_x1() {
+            self.dup_words(2, 1);
+        }
+    };
+    0x5e => dup2_x2 {
+        fn dup2_x2() {
+            self.dup_words(2, 2);
+        }
+    };
+    0x5f => swap {
+        fn swap() {
+            let values = self.pop_words(2);
+            debug_assert_eq!(values.len(), 2, "category mismatch");
+            for value in values.into_iter().rev() {
+                self.push(value);
+            }
+            self.inc_pc(1);
+        }
+    };
+    0x60 => iadd {
+        binary_op! { execute_iadd, i32, |a, b| a.wrapping_add(b) }
+    };
+    0x61 => ladd {
+        binary_op! { execute_ladd, i64, |a, b| a.wrapping_add(b) }
+    };
+    0x62 => fadd {
+        binary_op! { execute_fadd, f32, |a, b| a + b }
+    };
+    0x63 => dadd {
+        binary_op! { execute_dadd, f64, |a, b| a + b }
+    };
+    0x64 => isub {
+        binary_op! { execute_isub, i32, |a, b| a.wrapping_sub(b) }
+    };
+    0x65 => lsub {
+        binary_op! { execute_lsub, i64, |a, b| a.wrapping_sub(b) }
+    };
+    0x66 => fsub {
+        binary_op! { execute_fsub, f32, |a, b| a - b }
+    };
+    0x67 => dsub {
+        binary_op! { execute_dsub, f64, |a, b| a - b }
+    };
+    0x68 => imul {
+        binary_op! { execute_imul, i32, |a, b| a.wrapping_mul(b) }
+    };
+    0x69 => lmul {
+        binary_op! { execute_lmul, i64, |a, b| a.wrapping_mul(b) }
+    };
+    0x6a => fmul {
+        binary_op! { execute_fmul, f32, |a, b| a * b }
+    };
+    0x6b => dmul {
+        binary_op! { execute_dmul, f64, |a, b| a * b }
+    };
+    0x6c => idiv {
+        fn idiv() -> Result<(), RuntimeError> {
+            let b: i32 = self.pop();
+            let a: i32 = self.pop();
+            if b == 0 {
+                return Err(divide_by_zero());
+            }
+            // MIN / -1 溢出为MIN
+            self.push(a.wrapping_div(b));
+            self.inc_pc(1);
+            Ok(())
+        }
+    };
+    0x6d => ldiv {
+        fn ldiv() -> Result<(), RuntimeError> {
+            let b: i64 = self.pop();
+            let a: i64 = self.pop();
+            if b == 0 {
+                return Err(divide_by_zero());
+            }
+            // MIN / -1 溢出为MIN
+            self.push(a.wrapping_div(b));
+            self.inc_pc(1);
+            Ok(())
+        }
+    };
+    0x6e => fdiv {
+        binary_op! { execute_fdiv, f32, |a, b| a / b }
+    };
+    0x6f => ddiv {
+        binary_op! { execute_ddiv, f64, |a, b| a / b }
+    };
+    0x70 => irem {
+        fn irem() -> Result<(), RuntimeError> {
+            let b: i32 = self.pop();
+            let a: i32 = self.pop();
+            if b == 0 {
+                return Err(divide_by_zero());
+            }
+            self.push(a.wrapping_rem(b));
+            self.inc_pc(1);
+            Ok(())
+        }
+    };
+    0x71 => lrem {
+        fn lrem() -> Result<(), RuntimeError> {
+            let b: i64 = self.pop();
+            let a: i64 = self.pop();
+            if b == 0 {
+                return Err(divide_by_zero());
+            }
+            self.push(a.wrapping_rem(b));
+            self.inc_pc(1);
+            Ok(())
+        }
+    };
+    0x72 => frem {
+        // 与C的fmod相同, 结果的符号与被除数相同
+        binary_op! { execute_frem, f32, |a, b| a % b }
+    };
+    0x73 => drem {
+        // 与C的fmod相同, 结果的符号与被除数相同
+        binary_op! { execute_drem, f64, |a, b| a % b }
+    };
+    0x74 => ineg {
+        unary_op! { execute_ineg, i32, |a| a.wrapping_neg() }
+    };
+    0x75 => lneg {
+        unary_op! { execute_lneg, i64, |a| a.wrapping_neg() }
+    };
+    0x76 => fneg {
+        unary_op! { execute_fneg, f32, |a| -a }
+    };
+    0x77 => dneg {
+        unary_op! { execute_dneg, f64, |a| -a }
+    };
+    0x78 => ishl {
+        shift_op! { execute_ishl, i32, |a, b| a.wrapping_shl(b as u32) }
+    };
+    0x79 => lshl {
+        shift_op! { execute_lshl, i64, |a, b| a.wrapping_shl(b as u32) }
+    };
+    0x7a => ishr {
+        shift_op! { execute_ishr, i32, |a, b| a.wrapping_shr(b as u32) }
+    };
+    0x7b => lshr {
+        shift_op! { execute_lshr, i64, |a, b| a.wrapping_shr(b as u32) }
+    };
+    0x7c => iushr {
+        shift_op! { execute_iushr, i32, |a, b| (a as u32).wrapping_shr(b as u32) as i32 }
+    };
+    0x7d => lushr {
+        shift_op! { execute_lushr, i64, |a, b| (a as u64).wrapping_shr(b as u32) as i64 }
+    };
+    0x7e => iand {
+        binary_op! { execute_iand, i32, |a, b| a & b }
+    };
+    0x7f => land {
+        binary_op! { execute_land, i64, |a, b| a & b }
+    };
+    0x80 => ior {
+        binary_op! { execute_ior, i32, |a, b| a | b }
+    };
+    0x81 => lor {
+        binary_op! { execute_lor, i64, |a, b| a | b }
+    };
+    0x82 => ixor {
+        binary_op! { execute_ixor, i32, |a, b| a ^ b }
+    };
+    0x83 => lxor {
+        binary_op! { execute_lxor, i64, |a, b| a ^ b }
+    };
+    0x84 => iinc {
+        fn iinc(index: u8, value: i8) {
+            self.iinc_local(index as usize, value as i32);
+            self.inc_pc(3);
+        }
+    };
+    0x85 => i2l {
+        convert_op! { execute_i2l, i32, i64, |a| a as i64 }
+    };
+    0x86 => i2f {
+        convert_op! { execute_i2f, i32, f32, |a| a as f32 }
+    };
+    0x87 => i2d {
+        convert_op! { execute_i2d, i32, f64, |a| a as f64 }
+    };
+    0x88 => l2i {
+        convert_op! { execute_l2i, i64, i32, |a| a as i32 }
+    };
+    0x89 => l2f {
+        convert_op! { execute_l2f, i64, f32, |a| a as f32 }
+    };
+    0x8a => l2d {
+        convert_op! { execute_l2d, i64, f64, |a| a as f64 }
+    };
+    0x8b => f2i {
+        convert_op! { execute_f2i, f32, i32, |a| a as i32 }
+    };
+    0x8c => f2l {
+        convert_op! { execute_f2l, f32, i64, |a| a as i64 }
+    };
+    0x8d => f2d {
+        convert_op! { execute_f2d, f32, f64, |a| a as f64 }
+    };
+    0x8e => d2i {
+        convert_op! { execute_d2i, f64, i32, |a| a as i32 }
+    };
+    0x8f => d2l {
+        convert_op! { execute_d2l, f64, i64, |a| a as i64 }
+    };
+    0x90 => d2f {
+        convert_op! { execute_d2f, f64, f32, |a| a as f32 }
+    };
+    0x91 => i2b {
+        convert_op! { execute_i2b, i32, i32, |a| a as i8 as i32 }
+    };
+    0x92 => i2c {
+        convert_op! { execute_i2c, i32, i32, |a| a as u16 as i32 }
+    };
+    0x93 => i2s {
+        convert_op! { execute_i2s, i32, i32, |a| a as i16 as i32 }
+    };
+    0x94 => lcmp {
+        compare_op! { execute_lcmp, i64, 0 }
+    };
+    0x95 => fcmpl {
+        compare_op! { execute_fcmpl, f32, -1 }
+    };
+    0x96 => fcmpg {
+        compare_op! { execute_fcmpg, f32, 1 }
+    };
+    0x97 => dcmpl {
+        compare_op! { execute_dcmpl, f64, -1 }
+    };
+    0x98 => dcmpg {
+        compare_op! { execute_dcmpg, f64, 1 }
+    };
+    0x99 => ifeq {
+        fn ifeq(offset: i16) {
+            let value: i32 = self.pop();
+            self.branch_if(value == 0, offset, 3);
+        }
+    };
+    0x9a => ifne {
+        fn ifne(offset: i16) {
+            let value: i32 = self.pop();
+            self.branch_if(value != 0, offset, 3);
+        }
+    };
+    0x9b => iflt {
+        fn iflt(offset: i16) {
+            let value: i32 = self.pop();
+            self.branch_if(value < 0, offset, 3);
+        }
+    };
+    0x9c => ifge {
+        fn ifge(offset: i16) {
+            let value: i32 = self.pop();
+            self.branch_if(value >= 0, offset, 3);
+        }
+    };
+    0x9d => ifgt {
+        fn ifgt(offset: i16) {
+            let value: i32 = self.pop();
+            self.branch_if(value > 0, offset, 3);
+        }
+    };
+    0x9e => ifle {
+        fn ifle(offset: i16) {
+            let value: i32 = self.pop();
+            self.branch_if(value <= 0, offset, 3);
+        }
+    };
+    0x9f => if_icmpeq {
+        fn if_icmpeq(offset: i16) {
+            let b: i32 = self.pop();
+            let a: i32 = self.pop();
+            self.branch_if(a == b, offset, 3);
+        }
+    };
+    0xa0 => if_icmpne {
+        fn if_icmpne(offset: i16) {
+            let b: i32 = self.pop();
+            let a: i32 = self.pop();
+            self.branch_if(a != b, offset, 3);
+        }
+    };
+    0xa1 => if_icmplt {
+        fn if_icmplt(offset: i16) {
+            let b: i32 = self.pop();
+            let a: i32 = self.pop();
+            self.branch_if(a < b, offset, 3);
+        }
+    };
+    0xa2 => if_icmpge {
+        fn if_icmpge(offset: i16) {
+            let b: i32 = self.pop();
+            let a: i32 = self.pop();
+            self.branch_if(a >= b, offset, 3);
+        }
+    };
+    0xa3 => if_icmpgt {
+        fn if_icmpgt(offset: i16) {
+            let b: i32 = self.pop();
+            let a: i32 = self.pop();
+            self.branch_if(a > b, offset, 3);
+        }
+    };
+    0xa4 => if_icmple {
+        fn if_icmple(offset: i16) {
+            let b: i32 = self.pop();
+            let a: i32 = self.pop();
+            self.branch_if(a <= b, offset, 3);
+        }
+    };
+    0xa5 => if_acmpeq {
+        fn if_acmpeq(offset: i16) {
+            let b: Option<ObjectRef> = self.pop();
+            let a: Option<ObjectRef> = self.pop();
+            self.branch_if(a == b, offset, 3);
+        }
+    };
+    0xa6 => if_acmpne {
+        fn if_acmpne(offset: i16) {
+            let b: Option<ObjectRef> = self.pop();
+            let a: Option<ObjectRef> = self.pop();
+            self.branch_if(a != b, offset, 3);
+        }
+    };
+    0xa7 => goto {
+        fn goto(offset: i16) {
+            self.branch(offset as i32);
+        }
+    };
+    // jsr和ret只出现在版本50之前的类文件中, 不支持子程序, 按非法指令抛出VerifyError
+    0xa8 => jsr {
+        fn jsr() -> Result<(), RuntimeError> {
+            Err(self.illegal_opcode(0xa8))
+        }
+    };
+    0xa9 => ret {
+        fn ret() -> Result<(), RuntimeError> {
+            Err(self.illegal_opcode(0xa9))
+        }
+    };
+    0xaa => tableswitch {
+        fn tableswitch() {
+            let index: i32 = self.pop();
+            let base = 1 + self.switch_padding();
+            let default: i32 = self.read_operand(base);
+            let low: i32 = self.read_operand(base + 4);
+            let high: i32 = self.read_operand(base + 8);
+            let offset = if index < low || index > high {
+                default
+            } else {
+                let entry = (index as i64 - low as i64) as usize;
+                self.read_switch_entry(base as usize + 12 + entry * 4)
+            };
+            self.branch(offset);
+        }
+    };
+    0xab => lookupswitch {
+        fn lookupswitch() {
+            let key: i32 = self.pop();
+            let base = 1 + self.switch_padding();
+            let default: i32 = self.read_operand(base);
+            let npairs: i32 = self.read_operand(base + 4);
+            // match-offset对按match升序排列
+            let (mut low, mut high) = (0, npairs.max(0) as usize);
+            let mut offset = default;
+            while low < high {
+                let mid = low + (high - low) / 2;
+                let pair = base as usize + 8 + mid * 8;
+                let value = self.read_switch_entry(pair);
+                match value.cmp(&key) {
+                    std::cmp::Ordering::Less => low = mid + 1,
+                    std::cmp::Ordering::Greater => high = mid,
+                    std::cmp::Ordering::Equal => {
+                        offset = self.read_switch_entry(pair + 4);
+                        break;
+                    }
+                }
+            }
+            self.branch(offset);
+        }
+    };
+    0xac => ireturn {
+        fn ireturn() -> Result<(), RuntimeError> {
+            let value: Slot = self.pop();
+            self.return_from_method(Some(value))
+        }
+    };
+    0xad => lreturn {
+        fn lreturn() -> Result<(), RuntimeError> {
+            let value: Slot = self.pop();
+            self.return_from_method(Some(value))
+        }
+    };
+    0xae => freturn {
+        fn freturn() -> Result<(), RuntimeError> {
+            let value: Slot = self.pop();
+            self.return_from_method(Some(value))
+        }
+    };
+    0xaf => dreturn {
+        fn dreturn() -> Result<(), RuntimeError> {
+            let value: Slot = self.pop();
+            self.return_from_method(Some(value))
+        }
+    };
+    0xb0 => areturn {
+        fn areturn() -> Result<(), RuntimeError> {
+            let value: Slot = self.pop();
+            self.return_from_method(Some(value))
+        }
+    };
+    0xb1 => r#return {
+        fn r#return() -> Result<(), RuntimeError> {
+            self.return_from_method(None)
+        }
+    };
+    0xb2 => getstatic {
+        fn getstatic(index: u16) -> Result<(), RuntimeError> {
+            let field_ref = self
+                .constant_pool()
+                .resolve_field(index, self.vm.class_loader())?;
+            expect_static_field(&field_ref, true)?;
+            self.initialize_class(&field_ref.klass)?;
+            self.push(field_ref.klass.get_static(field_ref.field.offset));
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0xb3 => putstatic {
+        fn putstatic(index: u16) -> Result<(), RuntimeError> {
+            let field_ref = self
+                .constant_pool()
+                .resolve_field(index, self.vm.class_loader())?;
+            expect_static_field(&field_ref, true)?;
+            self.check_final_store(&field_ref, "<clinit>")?;
+            self.initialize_class(&field_ref.klass)?;
+            let value: Slot = self.pop();
+            let field = &field_ref.field;
+            field_ref
+                .klass
+                .set_static(field.offset, value.narrow(&field.descriptor));
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0xb4 => getfield {
+        fn getfield(index: u16) -> Result<(), RuntimeError> {
+            let field_ref = self
+                .constant_pool()
+                .resolve_field(index, self.vm.class_loader())?;
+            expect_static_field(&field_ref, false)?;
+            let object: Option<ObjectRef> = self.pop();
+            let value = self.vm.heap().get_field(object, &field_ref.field)?;
+            self.push(value);
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0xb5 => putfield {
+        fn putfield(index: u16) -> Result<(), RuntimeError> {
+            let field_ref = self
+                .constant_pool()
+                .resolve_field(index, self.vm.class_loader())?;
+            expect_static_field(&field_ref, false)?;
+            self.check_final_store(&field_ref, "<init>")?;
+            let value: Slot = self.pop();
+            let object: Option<ObjectRef> = self.pop();
+            self.vm.heap().put_field(object, &field_ref.field, value)?;
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0xb6 => invokevirtual {
+        fn invokevirtual(index: u16) -> Result<(), RuntimeError> {
+            let method_ref = self
+                .constant_pool()
+                .resolve_method(index, self.vm.class_loader())?;
+            expect_static(&method_ref.method, false)?;
+            let receiver = self.receiver(&method_ref.method)?;
+            let method = self.select_cached(&self.object_class(receiver)?, &method_ref)?;
+            self.invoke(method)
+        }
+    };
+    0xb7 => invokespecial {
+        fn invokespecial(index: u16) -> Result<(), RuntimeError> {
+            let method_ref = self
+                .constant_pool()
+                .resolve_method(index, self.vm.class_loader())?;
+            expect_static(&method_ref.method, false)?;
+            self.receiver(&method_ref.method)?;
+            let method = select_special(&self.current_class()?, &method_ref)?;
+            self.invoke(method)
+        }
+    };
+    0xb8 => invokestatic {
+        fn invokestatic(index: u16) -> Result<(), RuntimeError> {
+            let method_ref = self
+                .constant_pool()
+                .resolve_method(index, self.vm.class_loader())?;
+            expect_static(&method_ref.method, true)?;
+            self.initialize_class(&method_ref.klass)?;
+            self.invoke(method_ref.method)
+        }
+    };
+    0xb9 => invokeinterface {
+        fn invokeinterface(index: u16, _count: u8, _zero: u8) -> Result<(), RuntimeError> {
+            let method_ref = self
+                .constant_pool()
+                .resolve_method(index, self.vm.class_loader())?;
+            expect_static(&method_ref.method, false)?;
+            let receiver = self.receiver(&method_ref.method)?;
+            let receiver_class = self.object_class(receiver)?;
+            if !receiver_class.is_subclass_of(&method_ref.class) {
+                return Err(RuntimeError::IncompatibleClassChangeError(format!(
+                    "Class {} does not implement the requested interface {}",
+                    receiver_class.name(),
+                    method_ref.class.name()
+                )));
+            }
+            let method = self.select_cached(&receiver_class, &method_ref)?;
+            if !method
+                .access_flags
+                .intersects(MethodAccessFlags::PUBLIC | MethodAccessFlags::PRIVATE)
+            {
+                return Err(RuntimeError::IllegalAccessError(format!(
+                    "{}.{}{} is not public",
+                    method.constant_pool.class_name(),
+                    method.name,
+                    method.descriptor
+                )));
+            }
+            self.invoke(method)
+        }
+    };
+    // 调用点需要java.lang.invoke的方法句柄, 内建的类库中没有, 按非法指令抛出VerifyError
+    0xba => invokedynamic {
+        fn invokedynamic() -> Result<(), RuntimeError> {
+            Err(self.illegal_opcode(0xba))
+        }
+    };
+    0xbb => new {
+        fn new(index: u16) -> Result<(), RuntimeError> {
+            let klass = self
+                .constant_pool()
+                .resolve_class(index, self.vm.class_loader())?;
+            if klass.is_interface() || klass.access_flags().contains(ClassAccessFlags::ABSTRACT) {
+                return Err(RuntimeError::InstantiationError(klass.name().replace('/', ".")));
+            }
+            self.initialize_class(&klass)?;
+            self.reserve(instance_bytes(&klass))?;
+            let object_ref = self.vm.heap().alloc_instance(klass);
+            self.push(Some(object_ref));
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0xbc => newarray {
+        fn newarray(atype: u8) -> Result<(), RuntimeError> {
+            let name = match atype {
+                4 => "[Z",
+                5 => "[C",
+                6 => "[F",
+                7 => "[D",
+                8 => "[B",
+                9 => "[S",
+                10 => "[I",
+                11 => "[J",
+                _ => return Err(self.illegal_opcode(0xbc)),
+            };
+            let class = self.vm.class_loader().load_class(name)?;
+            let count: i32 = self.pop();
+            self.reserve_arrays(&class, &[count])?;
+            let array = self.new_array(class, count)?;
+            self.push(Some(array));
+            self.inc_pc(2);
+            Ok(())
+        }
+    };
+    0xbd => anewarray {
+        fn anewarray(index: u16) -> Result<(), RuntimeError> {
+            let component = self
+                .constant_pool()
+                .resolve_class(index, self.vm.class_loader())?;
+            let name = match component.name() {
+                name if name.starts_with('[') => format!("[{}", name),
+                name => format!("[L{};", name),
+            };
+            let class = self.vm.class_loader().load_class(&name)?;
+            let count: i32 = self.pop();
+            self.reserve_arrays(&class, &[count])?;
+            let array = self.new_array(class, count)?;
+            self.push(Some(array));
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0xbe => arraylength {
+        fn arraylength() -> Result<(), RuntimeError> {
+            let array: Option<ObjectRef> = self.pop();
+            let length = self.vm.heap().array_length(array)?;
+            self.push(length);
+            self.inc_pc(1);
+            Ok(())
+        }
+    };
+    0xbf => athrow {
+        fn athrow() -> Result<(), RuntimeError> {
+            let object: Option<ObjectRef> = self.pop();
+            let object = object.ok_or_else(|| {
+                RuntimeError::NullPointerException(
+                    "Cannot throw exception because value is null".to_string(),
+                )
+            })?;
+            Err(self.thrown(object))
+        }
+    };
+    0xc0 => checkcast {
+        fn checkcast(index: u16) -> Result<(), RuntimeError> {
+            let class = self
+                .constant_pool()
+                .resolve_class(index, self.vm.class_loader())?;
+            let object: Option<ObjectRef> = self.current_frame().peek(0).clone().into();
+            // null可以转换为任何类型
+            if let Some(object_ref) = object
+                && !self.is_instance_of(object_ref, &class)?
+            {
+                return Err(RuntimeError::ClassCastException(format!(
+                    "class {} cannot be cast to class {}",
+                    self.object_class(object_ref)?.name().replace('/', "."),
+                    class.name().replace('/', ".")
+                )));
+            }
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0xc1 => instanceof {
+        fn instanceof(index: u16) -> Result<(), RuntimeError> {
+            let class = self
+                .constant_pool()
+                .resolve_class(index, self.vm.class_loader())?;
+            let object: Option<ObjectRef> = self.pop();
+            let result = match object {
+                Some(object_ref) => self.is_instance_of(object_ref, &class)?,
+                None => false,
+            };
+            self.push(result as i32);
+            self.inc_pc(3);
+            Ok(())
+        }
+    };
+    0xc2 => monitorenter {
+        fn monitorenter() -> Result<(), RuntimeError> {
+            // 获得锁之后才弹出, 挂起后重新执行
+            let object: Option<ObjectRef> = self.current_frame().peek(0).clone().into();
+            let object = object.ok_or_else(|| {
+                RuntimeError::NullPointerException(
+                    "Cannot enter synchronized block because value is null".to_string(),
+                )
+            })?;
+            self.enter_monitor(object)?;
+            self.pop::<Slot>();
+            self.inc_pc(1);
+            Ok(())
+        }
+    };
+    0xc3 => monitorexit {
+        fn monitorexit() -> Result<(), RuntimeError> {
+            let object: Option<ObjectRef> = self.pop();
+            let object = object.ok_or_else(|| {
+                RuntimeError::NullPointerException(
+                    "Cannot exit synchronized block because value is null".to_string(),
+                )
+            })?;
+            self.vm.heap().monitor_exit(object, self.id)?;
+            self.inc_pc(1);
+            Ok(())
+        }
+    };
+    0xc4 => wide {
+        fn wide(opcode: u8, index: u16) -> Result<(), RuntimeError> {
+            let index = index as usize;
+            match opcode {
+                0x15..=0x19 => self.load_local(index),
+                0x36..=0x3a => self.store_local(index),
+                0x84 => {
+                    let value: i16 = self.read_operand(4);
+                    self.iinc_local(index, value as i32);
+                    self.inc_pc(6);
+                    return Ok(());
+                }
+                // ret不支持. pc仍指向wide, 异常处理器按整条指令查找
+                _ => return Err(self.illegal_opcode(opcode)),
+            }
+            self.inc_pc(4);
+            Ok(())
+        }
+    };
+    0xc5 => multianewarray {
+        fn multianewarray(index: u16, dimensions: u8) -> Result<(), RuntimeError> {
+            let class = self
+                .constant_pool()
+                .resolve_class(index, self.vm.class_loader())?;
+            let mut counts: Vec<i32> = (0..dimensions).map(|_| self.pop()).collect();
+            counts.reverse();
+            if let Some(count) = counts.iter().find(|count| **count < 0) {
+                return Err(RuntimeError::NegativeArraySizeException(count.to_string()));
+            }
+            self.reserve_arrays(&class, &counts)?;
+            let array = self.new_multi_array(&class, &counts)?;
+            self.push(Some(array));
+            self.inc_pc(4);
+            Ok(())
+        }
+    };
+    0xc6 => ifnull {
+        fn ifnull(offset: i16) {
+            let value: Option<ObjectRef> = self.pop();
+            self.branch_if(value.is_none(), offset, 3);
+        }
+    };
+    0xc7 => ifnonnull {
+        fn ifnonnull(offset: i16) {
+            let value: Option<ObjectRef> = self.pop();
+            self.branch_if(value.is_some(), offset, 3);
+        }
+    };
+    0xc8 => goto_w {
+        fn goto_w(offset: i32) {
+            self.branch(offset);
+        }
+    };
+    0xc9 => jsr_w {
+        fn jsr_w() -> Result<(), RuntimeError> {
+            Err(self.illegal_opcode(0xc9))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rstest::{fixture, rstest};
+
+    use crate::{
+        constant_pool::{Constant, ConstantDynamic, ConstantInteger, ConstantPool},
+        runtime::{
+            ClassLoader, ClassPath, ClassPathEntry, InitState, Klass, Method, RuntimeConstantPool,
+            RuntimeError, Vm,
+            descriptor::FieldType,
+            frame::{LocalVarsLike, OperandStackLike},
+            gc::{CollectorKind, GcOptions},
+            heap::{Array, Object},
+            klass::ClassInitializer,
+            native::NativeEnv,
+            scheduler::MAIN_THREAD_ID,
+            slot::{ObjectRef, Slot},
+            stack_trace::{StackTraceElement, backtrace},
+            string::JavaString,
+            thread::{MAX_STACK_DEPTH, Thread},
+        },
+        test_context::TestContext,
+    };
+
+    #[fixture]
+    fn thread() -> Thread {
+        thread_with_code(vec![])
+    }
+
+    fn thread_with_code(code: Vec<u8>) -> Thread {
+        let constant_pool = ConstantPool::from(vec![
+            Constant::Invalid,
+            Constant::from("some string".to_string()),
+            Constant::Integer(ConstantInteger { tag: 3, bytes: 7 }),
+            Constant::Dynamic(ConstantDynamic {
+                tag: 17,
+                bootstrap_method_attr_index: 0,
+                name_and_type_index: 0,
+            }),
+        ]);
+        let constant_pool = RuntimeConstantPool::new("Test".to_string(), Arc::new(constant_pool));
+
+        let method = Method {
+            name: "test".to_string(),
+            descriptor: "()V".to_string(),
+            max_locals: 300,
+            max_stack: 100,
+            code,
+            constant_pool: Arc::new(constant_pool),
+            ..Default::default()
+        };
+        let vm = Vm::new(ClassLoader::new(Default::default()));
+        Thread::new(0, Arc::new(method), Arc::new(vm))
+    }
+
+    /// 在Ldc类的常量池上执行`code`
+    fn ldc_thread(code: Vec<u8>) -> Thread {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let vm = Vm::new(ClassLoader::new(class_path));
+        let klass = vm.class_loader().load_class("Ldc").unwrap();
+        let method = Method {
+            max_stack: 100,
+            code,
+            constant_pool: klass.constant_pool().clone(),
+            ..Default::default()
+        };
+        Thread::new(0, Arc::new(method), Arc::new(vm))
+    }
+
+    #[rstest]
+    fn test_thread_frame(thread: Thread) {
+        assert_eq!(thread.id, 11);
+    }
+    #[rstest]
+    #[should_panic(expected = "none frame")]
+    fn test_thread_frame_panic(mut thread: Thread) {
+        let _ = thread.stack.pop();
+        let _ = thread.current_frame();
+    }
+    #[rstest]
+    fn test_nop(mut thread: Thread) {
+        thread.execute_nop();
+        assert_eq!(thread.current_frame().pc, 1);
+    }
+
+    #[rstest]
+    fn test_iconst_m1(mut thread: Thread) {
+        thread.execute_iconst_m1();
+        assert_eq!(thread.current_frame().pc, 1);
+        assert_eq!(thread.current_frame().top::<i32>(), -1)
+    }
+
+    #[rstest]
+    fn test_aconst_null(mut thread: Thread) {
+        thread.execute_aconst_null();
+        assert_eq!(thread.current_frame().pc, 1);
+        assert_eq!(thread.current_frame().top::<Option<ObjectRef>>(), None)
+    }
+
+    #[rstest]
+    #[case(Thread::execute_iconst_0, 0)]
+    #[case(Thread::execute_iconst_1, 1)]
+    #[case(Thread::execute_iconst_2, 2)]
+    #[case(Thread::execute_iconst_3, 3)]
+    #[case(Thread::execute_iconst_4, 4)]
+    #[case(Thread::execute_iconst_5, 5)]
+    fn test_iconst(mut thread: Thread, #[case] execute: fn(&mut Thread), #[case] expected: i32) {
+        execute(&mut thread);
+        assert_eq!(thread.current_frame().pc, 1);
+        assert_eq!(thread.current_frame().top::<i32>(), expected)
+    }
+
+    #[rstest]
+    #[case(Thread::execute_lconst_0, 0)]
+    #[case(Thread::execute_lconst_1, 1)]
+    fn test_lconst(mut thread: Thread, #[case] execute: fn(&mut Thread), #[case] expected: i64) {
+        execute(&mut thread);
+        assert_eq!(thread.current_frame().pc, 1);
+        assert_eq!(thread.current_frame().top::<i64>(), expected)
+    }
+
+    #[rstest]
+    #[case(Thread::execute_fconst_0, 0.0)]
+    #[case(Thread::execute_fconst_1, 1.0)]
+    #[case(Thread::execute_fconst_2, 2.0)]
+    fn test_fconst(mut thread: Thread, #[case] execute: fn(&mut Thread), #[case] expected: f32) {
+        execute(&mut thread);
+        assert_eq!(thread.current_frame().pc, 1);
+        assert_eq!(thread.current_frame().top::<f32>(), expected)
+    }
+
+    #[rstest]
+    #[case(Thread::execute_dconst_0, 0.0)]
+    #[case(Thread::execute_dconst_1, 1.0)]
+    fn test_dconst(mut thread: Thread, #[case] execute: fn(&mut Thread), #[case] expected: f64) {
+        execute(&mut thread);
+        assert_eq!(thread.current_frame().pc, 1);
+        assert_eq!(thread.current_frame().top::<f64>(), expected)
+    }
+
+    #[rstest]
+    #[case(vec![0x10, 0x7f], 2, 127)]
+    #[case(vec![0x10, 0xff], 2, -1)]
+    #[case(vec![0x11, 0x80, 0x00], 3, -32768)]
+    #[case(vec![0x11, 0x01, 0x00], 3, 256)]
+    fn test_push_immediate(#[case] code: Vec<u8>, #[case] pc: u16, #[case] expected: i32) {
+        let mut thread = thread_with_code(code);
+        let opcode = thread.fetch();
+        thread.execute(opcode).unwrap();
+        assert_eq!(thread.current_frame().pc, pc);
+        assert_eq!(thread.current_frame().top::<i32>(), expected)
+    }
+
+    // 常量下标见`javap -v Ldc.class`
+    #[rstest]
+    #[case(vec![0x12, 11], 2, Slot::Bits32(100000))]
+    #[case(vec![0x12, 12], 2, Slot::Bits32(1.5f32.to_bits()))]
+    #[case(vec![0x13, 0, 11], 3, Slot::Bits32(100000))]
+    #[case(vec![0x14, 0, 13], 3, Slot::Bits64(1234567890123))]
+    #[case(vec![0x14, 0, 15], 3, Slot::Bits64(2.5f64.to_bits()))]
+    fn test_ldc_primitive(#[case] code: Vec<u8>, #[case] pc: u16, #[case] expected: Slot) {
+        let mut thread = ldc_thread(code);
+        let opcode = thread.fetch();
+        thread.execute(opcode).unwrap();
+        assert_eq!(thread.current_frame().pc, pc);
+        assert_eq!(thread.current_frame().top::<Slot>(), expected);
+    }
+
+    fn ldc_object(thread: &mut Thread, index: u8) -> Object {
+        thread.current_frame_mut().pc = 0;
+        thread.execute_ldc(index).unwrap();
+        let object_ref = thread.pop::<Option<ObjectRef>>().unwrap();
+        thread.vm.heap().get(object_ref)
+    }
+
+    #[test]
+    fn test_ldc_string() {
+        let mut thread = ldc_thread(vec![]);
+        assert!(matches!(ldc_object(&mut thread, 7), Object::String(string) if string == "hello"));
+        // 同一字符串常量得到同一个引用
+        thread.current_frame_mut().pc = 0;
+        thread.execute_ldc(7).unwrap();
+        thread.execute_ldc_w(7).unwrap();
+        let first = thread.pop::<Option<ObjectRef>>();
+        assert_eq!(first, thread.pop::<Option<ObjectRef>>());
+        assert_eq!(thread.current_frame().pc, 5);
+    }
+
+    #[test]
+    fn test_ldc_class() {
+        let mut thread = ldc_thread(vec![]);
+        assert!(
+            matches!(ldc_object(&mut thread, 9), Object::Class(klass) if klass.name() == "Ldc")
+        );
+        assert!(
+            matches!(ldc_object(&mut thread, 2), Object::Class(klass) if klass.name() == "java/lang/Object")
+        );
+    }
+
+    #[test]
+    fn test_ldc_method_type_and_handle() {
+        let mut thread = ldc_thread(vec![]);
+        assert!(
+            matches!(ldc_object(&mut thread, 46), Object::MethodType(descriptor) if &*descriptor == "()V")
+        );
+        let Object::MethodHandle(method_handle) = ldc_object(&mut thread, 47) else {
+            panic!("not a MethodHandle");
+        };
+        assert_eq!(method_handle.reference_kind, 6);
+        let first = thread.vm.heap().object_count();
+        ldc_object(&mut thread, 47);
+        assert_eq!(thread.vm.heap().object_count(), first);
+    }
+
+    #[test]
+    fn test_ldc_dynamic() {
+        let mut thread = class_thread("Condy", vec![]);
+        let expected = thread
+            .vm
+            .heap()
+            .intern(JavaString::from("greeting java.lang.String in Condy"));
+        let Some(Slot::Ref(Some(greeting))) =
+            call_static(&mut thread, "Condy", "describe", vec![]).unwrap()
+        else {
+            panic!("dynamic constant is not a string");
+        };
+        assert_eq!(thread.vm.heap().intern_object(greeting).unwrap(), expected);
+        // 解析结果缓存在常量池中, 作为静态参数时也使用同一个结果
+        for name in ["describe", "nested"] {
+            let result = call_static(&mut thread, "Condy", name, vec![]).unwrap();
+            assert_eq!(result, Some(Slot::Ref(Some(greeting))));
+        }
+        let sum = call_static(&mut thread, "Condy", "sum", vec![]).unwrap();
+        assert_eq!(sum, Some(Slot::from(3i64)));
+        let unbox = call_static(&mut thread, "Condy", "unbox", vec![]).unwrap();
+        assert_eq!(unbox, Some(Slot::from(42)));
+        let klass = thread
+            .vm
+            .class_loader()
+            .load_class("CondyBootstrap")
+            .unwrap();
+        let calls = klass.find_field("calls", "I").unwrap();
+        assert_eq!(klass.get_static(calls.offset), Slot::from(1));
+    }
+
+    #[rstest]
+    #[case::bootstrap_throws("fail", "java.lang.IllegalStateException: boom")]
+    #[case::wrong_type(
+        "wrongType",
+        "java.lang.ClassCastException: class java.lang.String cannot be cast to class java.lang.Integer"
+    )]
+    fn test_ldc_dynamic_error(#[case] name: &str, #[case] cause: &str) {
+        let mut thread = class_thread("Condy", vec![]);
+        let err = call_static(&mut thread, "Condy", name, vec![]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "java.lang.BootstrapMethodError: bootstrap method initialization exception: {}",
+                cause
+            )
+        );
+        // 解析失败的结果也被缓存, 再次执行ldc不会重新调用引导方法
+        let again = call_static(&mut thread, "Condy", name, vec![]).unwrap_err();
+        assert_eq!(again.to_string(), err.to_string());
+        let klass = thread
+            .vm
+            .class_loader()
+            .load_class("CondyBootstrap")
+            .unwrap();
+        let calls = klass.find_field("calls", "I").unwrap();
+        assert_eq!(klass.get_static(calls.offset), Slot::from(1));
+    }
+
+    #[rstest]
+    fn test_initialize_class(mut thread: Thread) {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let klass = ClassLoader::new(class_path)
+            .load_class("InitConstants")
+            .unwrap();
+        thread.initialize_class(&klass).unwrap();
+        assert_eq!(klass.init_state(), InitState::Initialized);
+        assert_eq!(thread.stack.len(), 1);
+        // ConstantValue中的字符串常量在初始化时放入字符串池
+        let field = klass.find_field("STRING", "Ljava/lang/String;").unwrap();
+        let Slot::Ref(Some(string)) = klass.get_static(field.offset) else {
+            panic!("string constant is not initialized");
+        };
+        let heap = thread.vm.heap();
+        assert_eq!(heap.intern(JavaString::from("constant")), string);
+    }
+
+    #[test]
+    fn test_ldc() {
+        // ldc #2; return
+        let mut thread = thread_with_code(vec![0x12, 0x02, 0xb1]);
+        thread.execute(0x12).unwrap();
+        assert_eq!(thread.current_frame().pc, 2);
+        assert_eq!(thread.current_frame().top::<i32>(), 7);
+    }
+    #[test]
+    fn test_run() {
+        // iconst_m1; nop; ldc #2; return
+        let mut thread = thread_with_code(vec![0x02, 0x00, 0x12, 0x02, 0xb1]);
+        assert!(thread.run().unwrap().is_none());
+        assert!(thread.stack.is_empty());
+    }
+    #[test]
+    fn test_return_value() {
+        let mut thread = thread_with_code(vec![0xb1]);
+        thread.return_from_method(Some(Slot::Bits32(1))).unwrap();
+        assert!(thread.stack.is_empty());
+        assert!(matches!(thread.return_value, Some(Slot::Bits32(1))));
+    }
+    #[rstest]
+    #[case(0xa8)]
+    #[case(0xa9)]
+    #[case(0xba)]
+    #[case(0xc9)]
+    #[case(0xca)]
+    #[case(0xfe)]
+    #[case(0xff)]
+    fn test_illegal_opcode(#[case] opcode: u8) {
+        let mut thread = thread_with_code(vec![0x00, opcode]);
+        let err = thread.run().unwrap_err();
+        assert!(matches!(
+            err,
+            RuntimeError::IllegalOpcode { opcode: actual, pc: 1, ref method }
+                if actual == opcode && method == "Test.test()V"
+        ));
+        assert!(thread.stack.is_empty());
+    }
+
+    #[rstest]
+    #[case(Thread::execute_iload_0, 0, Slot::Bits32(7))]
+    #[case(Thread::execute_iload_1, 1, Slot::Bits32(7))]
+    #[case(Thread::execute_iload_2, 2, Slot::Bits32(7))]
+    #[case(Thread::execute_iload_3, 3, Slot::Bits32(7))]
+    #[case(Thread::execute_lload_0, 0, Slot::Bits64(7))]
+    #[case(Thread::execute_lload_1, 1, Slot::Bits64(7))]
+    #[case(Thread::execute_lload_2, 2, Slot::Bits64(7))]
+    #[case(Thread::execute_lload_3, 3, Slot::Bits64(7))]
+    #[case(Thread::execute_fload_0, 0, Slot::Bits32(0x3fc0_0000))]
+    #[case(Thread::execute_fload_1, 1, Slot::Bits32(0x3fc0_0000))]
+    #[case(Thread::execute_fload_2, 2, Slot::Bits32(0x3fc0_0000))]
+    #[case(Thread::execute_fload_3, 3, Slot::Bits32(0x3fc0_0000))]
+    #[case(Thread::execute_dload_0, 0, Slot::Bits64(0x4004_0000_0000_0000))]
+    #[case(Thread::execute_dload_1, 1, Slot::Bits64(0x4004_0000_0000_0000))]
+    #[case(Thread::execute_dload_2, 2, Slot::Bits64(0x4004_0000_0000_0000))]
+    #[case(Thread::execute_dload_3, 3, Slot::Bits64(0x4004_0000_0000_0000))]
+    #[case(Thread::execute_aload_0, 0, Slot::Ref(Some(ObjectRef::new(3))))]
+    #[case(Thread::execute_aload_1, 1, Slot::Ref(Some(ObjectRef::new(3))))]
+    #[case(Thread::execute_aload_2, 2, Slot::Ref(Some(ObjectRef::new(3))))]
+    #[case(Thread::execute_aload_3, 3, Slot::Ref(Some(ObjectRef::new(3))))]
+    fn test_load_n(
+        mut thread: Thread,
+        #[case] execute: fn(&mut Thread),
+        #[case] index: usize,
+        #[case] value: Slot,
+    ) {
+        thread.set(index, value.clone());
+        execute(&mut thread);
+        assert_eq!(thread.current_frame().pc, 1);
+        assert_eq!(thread.current_frame().top::<Slot>(), value)
+    }
+
+    #[rstest]
+    #[case(Thread::execute_istore_0, 0, Slot::Bits32(7))]
+    #[case(Thread::execute_istore_1, 1, Slot::Bits32(7))]
+    #[case(Thread::execute_istore_2, 2, Slot::Bits32(7))]
+    #[case(Thread::execute_istore_3, 3, Slot::Bits32(7))]
+    #[case(Thread::execute_lstore_0, 0, Slot::Bits64(7))]
+    #[case(Thread::execute_lstore_1, 1, Slot::Bits64(7))]
+    #[case(Thread::execute_lstore_2, 2, Slot::Bits64(7))]
+    #[case(Thread::execute_lstore_3, 3, Slot::Bits64(7))]
+    #[case(Thread::execute_fstore_0, 0, Slot::Bits32(0x3fc0_0000))]
+    #[case(Thread::execute_fstore_1, 1, Slot::Bits32(0x3fc0_0000))]
+    #[case(Thread::execute_fstore_2, 2, Slot::Bits32(0x3fc0_0000))]
+    #[case(Thread::execute_fstore_3, 3, Slot::Bits32(0x3fc0_0000))]
+    #[case(Thread::execute_dstore_0, 0, Slot::Bits64(0x4004_0000_0000_0000))]
+    #[case(Thread::execute_dstore_1, 1, Slot::Bits64(0x4004_0000_0000_0000))]
+    #[case(Thread::execute_dstore_2, 2, Slot::Bits64(0x4004_0000_0000_0000))]
+    #[case(Thread::execute_dstore_3, 3, Slot::Bits64(0x4004_0000_0000_0000))]
+    #[case(Thread::execute_astore_0, 0, Slot::Ref(Some(ObjectRef::new(3))))]
+    #[case(Thread::execute_astore_1, 1, Slot::Ref(Some(ObjectRef::new(3))))]
+    #[case(Thread::execute_astore_2, 2, Slot::Ref(Some(ObjectRef::new(3))))]
+    #[case(Thread::execute_astore_3, 3, Slot::Ref(Some(ObjectRef::new(3))))]
+    fn test_store_n(
+        mut thread: Thread,
+        #[case] execute: fn(&mut Thread),
+        #[case] index: usize,
+        #[case] value: Slot,
+    ) {
+        thread.push(value.clone());
+        execute(&mut thread);
+        assert_eq!(thread.current_frame().pc, 1);
+        assert_eq!(thread.get::<Slot>(index), value)
+    }
+
+    #[rstest]
+    #[case(0x15, 0x36, Slot::Bits32(1))]
+    #[case(0x16, 0x37, Slot::Bits64(1 << 40))]
+    #[case(0x17, 0x38, Slot::from(1.5_f32))]
+    #[case(0x18, 0x39, Slot::from(-1.5_f64))]
+    #[case(0x19, 0x3a, Slot::Ref(None))]
+    fn test_load_store(#[case] load: u8, #[case] store: u8, #[case] value: Slot) {
+        // xstore 9; xload 9
+        let mut thread = thread_with_code(vec![store, 9, load, 9]);
+        thread.push(value.clone());
+        thread.execute(store).unwrap();
+        assert_eq!(thread.current_frame().pc, 2);
+        assert_eq!(thread.get::<Slot>(9), value);
+        thread.execute(load).unwrap();
+        assert_eq!(thread.current_frame().pc, 4);
+        assert_eq!(thread.current_frame().top::<Slot>(), value)
+    }
+
+    #[test]
+    fn test_long_takes_two_slots() {
+        // lstore_1; iload_0
+        let mut thread = thread_with_code(vec![]);
+        thread.set(0, 5_i32);
+        thread.push(-1_i64);
+        thread.execute_lstore_1();
+        thread.execute_iload_0();
+        assert_eq!(thread.get::<i64>(1), -1);
+        assert_eq!(thread.pop::<i32>(), 5);
+    }
+
+    #[rstest]
+    #[case(5, -1, 4)]
+    #[case(i32::MAX, 1, i32::MIN)]
+    fn test_iinc(mut thread: Thread, #[case] local: i32, #[case] value: i8, #[case] expected: i32) {
+        thread.set(1, local);
+        thread.execute_iinc(1, value);
+        assert_eq!(thread.current_frame().pc, 3);
+        assert_eq!(thread.get::<i32>(1), expected)
+    }
+
+    #[test]
+    fn test_wide() {
+        // wide istore 256; wide iload 256; wide iinc 256 -32768
+        let mut thread = thread_with_code(vec![
+            0xc4, 0x36, 0x01, 0x00, 0xc4, 0x15, 0x01, 0x00, 0xc4, 0x84, 0x01, 0x00, 0x80, 0x00,
+        ]);
+        thread.push(7_i32);
+        thread.execute(0xc4).unwrap();
+        assert_eq!(thread.current_frame().pc, 4);
+        assert_eq!(thread.get::<i32>(256), 7);
+        thread.execute(0xc4).unwrap();
+        assert_eq!(thread.current_frame().pc, 8);
+        assert_eq!(thread.current_frame().top::<i32>(), 7);
+        thread.execute(0xc4).unwrap();
+        assert_eq!(thread.current_frame().pc, 14);
+        assert_eq!(thread.get::<i32>(256), 7 - 32768);
+    }
+
+    #[test]
+    fn test_wide_illegal() {
+        // wide nop
+        let mut thread = thread_with_code(vec![0xc4, 0x00, 0x00, 0x00]);
+        let err = thread.execute(0xc4).unwrap_err();
+        assert!(matches!(
+            err,
+            RuntimeError::IllegalOpcode {
+                opcode: 0,
+                pc: 0,
+                ..
+            }
+        ));
+        assert_eq!(thread.current_frame().pc, 0);
+    }
+
+    #[rstest]
+    #[case(0x4f, 0x2e, FieldType::Int, Slot::from(-7), Slot::from(-7))]
+    #[case(0x50, 0x2f, FieldType::Long, Slot::from(-7_i64), Slot::from(-7_i64))]
+    #[case(0x51, 0x30, FieldType::Float, Slot::from(0.5_f32), Slot::from(0.5_f32))]
+    #[case(
+        0x52,
+        0x31,
+        FieldType::Double,
+        Slot::from(0.5_f64),
+        Slot::from(0.5_f64)
+    )]
+    #[case(0x53, 0x32, FieldType::Object("java/lang/Object".to_string()), Slot::Ref(None), Slot::Ref(None))]
+    #[case(0x54, 0x33, FieldType::Byte, Slot::from(0x180), Slot::from(-128))]
+    #[case(0x54, 0x33, FieldType::Boolean, Slot::from(3), Slot::from(1))]
+    #[case(0x55, 0x34, FieldType::Char, Slot::from(-1), Slot::from(0xffff))]
+    #[case(0x56, 0x35, FieldType::Short, Slot::from(0x18000), Slot::from(-32768))]
+    fn test_array_load_store(
+        mut thread: Thread,
+        #[case] store: u8,
+        #[case] load: u8,
+        #[case] component: FieldType,
+        #[case] value: Slot,
+        #[case] expected: Slot,
+    ) {
+        let array = Some(
+            thread
+                .vm
+                .heap()
+                .alloc(Object::Array(Array::new(component, 3).unwrap())),
+        );
+        thread.push(array);
+        thread.push(2_i32);
+        thread.push(value);
+        thread.execute(store).unwrap();
+        assert_eq!(thread.current_frame().pc, 1);
+        thread.push(array);
+        thread.push(2_i32);
+        thread.execute(load).unwrap();
+        assert_eq!(thread.current_frame().pc, 2);
+        assert_eq!(thread.current_frame().top::<Slot>(), expected);
+    }
+
+    #[rstest]
+    fn test_array_load_exception(mut thread: Thread) {
+        let array = Some(
+            thread
+                .vm
+                .heap()
+                .alloc(Object::Array(Array::new(FieldType::Int, 3).unwrap())),
+        );
+        thread.push(array);
+        thread.push(-1_i32);
+        let err = thread.execute_iaload().unwrap_err();
+        assert!(matches!(
+            err,
+            RuntimeError::ArrayIndexOutOfBoundsException(_)
+        ));
+        thread.push(Slot::Ref(None));
+        thread.push(0_i32);
+        let err = thread.execute_iaload().unwrap_err();
+        assert!(matches!(err, RuntimeError::NullPointerException(_)));
+    }
+
+    #[rstest]
+    fn test_push_frame(mut thread: Thread) {
+        let method = Method {
+            descriptor: "(IJLjava/lang/Object;)V".to_string(),
+            max_locals: 5,
+            ..Default::default()
+        };
+        thread.push(Slot::Ref(None));
+        thread.push(1_i32);
+        thread.push(2_i64);
+        thread.push(Slot::Ref(Some(ObjectRef::new(0))));
+        thread.push_frame(Arc::new(method)).unwrap();
+        assert_eq!(thread.stack.len(), 2);
+        // this, int, long(两个槽), Object
+        assert_eq!(thread.get::<Slot>(0), Slot::Ref(None));
+        assert_eq!(thread.get::<i32>(1), 1);
+        assert_eq!(thread.get::<i64>(2), 2);
+        assert_eq!(thread.get::<Slot>(4), Slot::Ref(Some(ObjectRef::new(0))));
+    }
+
+    fn int(value: i32) -> Slot {
+        Slot::from(value)
+    }
+    fn long(value: i64) -> Slot {
+        Slot::from(value)
+    }
+    fn reference(index: u32) -> Slot {
+        Slot::Ref(Some(ObjectRef::new(index)))
+    }
+
+    // 栈从左到右为栈底到栈顶
+    #[rstest]
+    #[case::pop(Thread::execute_pop, vec![long(1), int(2)], vec![long(1)])]
+    #[case::pop2_form1(Thread::execute_pop2, vec![long(1), int(2), int(3)], vec![long(1)])]
+    #[case::pop2_form2(Thread::execute_pop2, vec![int(1), long(2)], vec![int(1)])]
+    #[case::dup(Thread::execute_dup, vec![long(1), reference(2)], vec![long(1), reference(2), reference(2)])]
+    #[case::dup_x1(Thread::execute_dup_x1, vec![int(1), int(2)], vec![int(2), int(1), int(2)])]
+    #[case::dup_x2_form1(
+        Thread::execute_dup_x2,
+        vec![int(1), reference(2), int(3)],
+        vec![int(3), int(1), reference(2), int(3)]
+    )]
+    #[case::dup_x2_form2(
+        Thread::execute_dup_x2,
+        vec![long(1), int(2)],
+        vec![int(2), long(1), int(2)]
+    )]
+    #[case::dup2_form1(
+        Thread::execute_dup2,
+        vec![int(1), reference(2)],
+        vec![int(1), reference(2), int(1), reference(2)]
+    )]
+    #[case::dup2_form2(Thread::execute_dup2, vec![int(0), long(1)], vec![int(0), long(1), long(1)])]
+    #[case::dup2_x1_form1(
+        Thread::execute_dup2_x1,
+        vec![int(1), int(2), int(3)],
+        vec![int(2), int(3), int(1), int(2), int(3)]
+    )]
+    #[case::dup2_x1_form2(
+        Thread::execute_dup2_x1,
+        vec![reference(1), long(2)],
+        vec![long(2), reference(1), long(2)]
+    )]
+    #[case::dup2_x2_form1(
+        Thread::execute_dup2_x2,
+        vec![int(1), int(2), int(3), int(4)],
+        vec![int(3), int(4), int(1), int(2), int(3), int(4)]
+    )]
+    #[case::dup2_x2_form2(
+        Thread::execute_dup2_x2,
+        vec![int(1), int(2), long(3)],
+        vec![long(3), int(1), int(2), long(3)]
+    )]
+    #[case::dup2_x2_form3(
+        Thread::execute_dup2_x2,
+        vec![long(1), int(2), int(3)],
+        vec![int(2), int(3), long(1), int(2), int(3)]
+    )]
+    #[case::dup2_x2_form4(
+        Thread::execute_dup2_x2,
+        vec![long(1), long(2)],
+        vec![long(2), long(1), long(2)]
+    )]
+    #[case::swap(Thread::execute_swap, vec![long(0), int(1), reference(2)], vec![long(0), reference(2), int(1)])]
+    fn test_stack_manipulation(
+        mut thread: Thread,
+        #[case] execute: fn(&mut Thread),
+        #[case] before: Vec<Slot>,
+        #[case] after: Vec<Slot>,
+    ) {
+        for value in before {
+            thread.push(value);
+        }
+        execute(&mut thread);
+        assert_eq!(thread.current_frame().pc, 1);
+        let mut stack: Vec<Slot> = (0..after.len()).map(|_| thread.pop()).collect();
+        stack.reverse();
+        assert_eq!(stack, after);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "category mismatch")]
+    fn test_pop2_category_mismatch(mut thread: Thread) {
+        thread.push(1_i64);
+        thread.push(2_i32);
+        thread.execute_dup2_x1();
+    }
+
+    /// 依次压入`operands`后执行`opcode`, 返回栈顶
+    fn execute_op(opcode: u8, operands: Vec<Slot>) -> Result<Slot, RuntimeError> {
+        let mut thread = thread_with_code(vec![opcode]);
+        for operand in operands {
+            thread.push(operand);
+        }
+        thread.execute(opcode)?;
+        assert_eq!(thread.current_frame().pc, 1);
+        Ok(thread.pop())
+    }
+
+    #[rstest]
+    #[case::iadd_overflow(0x60, vec![int(i32::MAX), int(1)], int(i32::MIN))]
+    #[case::ladd_overflow(0x61, vec![long(i64::MAX), long(1)], long(i64::MIN))]
+    #[case::isub_overflow(0x64, vec![int(i32::MIN), int(1)], int(i32::MAX))]
+    #[case::lsub(0x65, vec![long(1), long(3)], long(-2))]
+    #[case::imul_overflow(0x68, vec![int(0x10000), int(0x10000)], int(0))]
+    #[case::lmul(0x69, vec![long(-3), long(7)], long(-21))]
+    #[case::idiv(0x6c, vec![int(7), int(-2)], int(-3))]
+    #[case::idiv_min(0x6c, vec![int(i32::MIN), int(-1)], int(i32::MIN))]
+    #[case::ldiv_min(0x6d, vec![long(i64::MIN), long(-1)], long(i64::MIN))]
+    #[case::irem(0x70, vec![int(-7), int(2)], int(-1))]
+    #[case::irem_min(0x70, vec![int(i32::MIN), int(-1)], int(0))]
+    #[case::lrem(0x71, vec![long(7), long(-2)], long(1))]
+    #[case::ineg_min(0x74, vec![int(i32::MIN)], int(i32::MIN))]
+    #[case::lneg(0x75, vec![long(5)], long(-5))]
+    #[case::ishl_masked(0x78, vec![int(1), int(33)], int(2))]
+    #[case::lshl_masked(0x79, vec![long(1), int(65)], long(2))]
+    #[case::ishr(0x7a, vec![int(-8), int(1)], int(-4))]
+    #[case::ishr_negative_distance(0x7a, vec![int(i32::MIN), int(-1)], int(-1))]
+    #[case::lshr(0x7b, vec![long(-8), int(1)], long(-4))]
+    #[case::iushr(0x7c, vec![int(-1), int(28)], int(15))]
+    #[case::lushr(0x7d, vec![long(-1), int(60)], long(15))]
+    #[case::iand(0x7e, vec![int(0b1100), int(0b1010)], int(0b1000))]
+    #[case::land(0x7f, vec![long(0b1100), long(0b1010)], long(0b1000))]
+    #[case::ior(0x80, vec![int(0b1100), int(0b1010)], int(0b1110))]
+    #[case::lor(0x81, vec![long(0b1100), long(0b1010)], long(0b1110))]
+    #[case::ixor(0x82, vec![int(0b1100), int(0b1010)], int(0b0110))]
+    #[case::lxor(0x83, vec![long(-1), long(0b1010)], long(!0b1010))]
+    fn test_integer_arithmetic(
+        #[case] opcode: u8,
+        #[case] operands: Vec<Slot>,
+        #[case] expected: Slot,
+    ) {
+        assert_eq!(execute_op(opcode, operands).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::idiv(0x6c, vec![int(1), int(0)])]
+    #[case::ldiv(0x6d, vec![long(1), long(0)])]
+    #[case::irem(0x70, vec![int(1), int(0)])]
+    #[case::lrem(0x71, vec![long(1), long(0)])]
+    fn test_divide_by_zero(#[case] opcode: u8, #[case] operands: Vec<Slot>) {
+        let err = execute_op(opcode, operands).unwrap_err();
+        assert!(matches!(err, RuntimeError::ArithmeticException(msg) if msg == "/ by zero"));
+    }
+
+    fn float(value: f32) -> Slot {
+        Slot::from(value)
+    }
+    fn double(value: f64) -> Slot {
+        Slot::from(value)
+    }
+
+    // 比较位模式, 以区分正负零
+    #[rstest]
+    #[case::fadd(0x62, vec![float(0.5), float(0.25)], float(0.75))]
+    #[case::dadd(0x63, vec![double(f64::MAX), double(f64::MAX)], double(f64::INFINITY))]
+    #[case::fsub_zero(0x66, vec![float(-0.0), float(0.0)], float(-0.0))]
+    #[case::dsub(0x67, vec![double(1.0), double(0.5)], double(0.5))]
+    #[case::fmul(0x6a, vec![float(-2.0), float(0.0)], float(-0.0))]
+    #[case::dmul(0x6b, vec![double(1.5), double(2.0)], double(3.0))]
+    #[case::fdiv_zero(0x6e, vec![float(1.0), float(-0.0)], float(f32::NEG_INFINITY))]
+    #[case::ddiv_zero(0x6f, vec![double(-1.0), double(0.0)], double(f64::NEG_INFINITY))]
+    #[case::frem(0x72, vec![float(5.5), float(2.0)], float(1.5))]
+    #[case::frem_negative(0x72, vec![float(-5.5), float(2.0)], float(-1.5))]
+    #[case::frem_infinite_divisor(0x72, vec![float(2.0), float(f32::INFINITY)], float(2.0))]
+    #[case::frem_negative_zero(0x72, vec![float(-0.0), float(1.0)], float(-0.0))]
+    #[case::drem(0x73, vec![double(5.5), double(-2.0)], double(1.5))]
+    #[case::drem_negative_zero(0x73, vec![double(-4.0), double(2.0)], double(-0.0))]
+    #[case::fneg_zero(0x76, vec![float(0.0)], float(-0.0))]
+    #[case::dneg(0x77, vec![double(-1.5)], double(1.5))]
+    fn test_float_arithmetic(
+        #[case] opcode: u8,
+        #[case] operands: Vec<Slot>,
+        #[case] expected: Slot,
+    ) {
+        assert_eq!(execute_op(opcode, operands).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::frem_zero(0x72, vec![float(1.0), float(0.0)])]
+    #[case::frem_infinite(0x72, vec![float(f32::INFINITY), float(2.0)])]
+    #[case::fadd_nan(0x62, vec![float(f32::NAN), float(1.0)])]
+    #[case::fsub_infinite(0x66, vec![float(f32::INFINITY), float(f32::INFINITY)])]
+    #[case::fdiv_zero(0x6e, vec![float(0.0), float(0.0)])]
+    fn test_float_nan(#[case] opcode: u8, #[case] operands: Vec<Slot>) {
+        assert!(f32::from(execute_op(opcode, operands).unwrap()).is_nan());
+    }
+
+    #[rstest]
+    #[case::drem_zero(0x73, vec![double(1.0), double(-0.0)])]
+    #[case::drem_infinite(0x73, vec![double(f64::NEG_INFINITY), double(2.0)])]
+    #[case::dmul_infinite_zero(0x6b, vec![double(f64::INFINITY), double(0.0)])]
+    #[case::ddiv_nan(0x6f, vec![double(1.0), double(f64::NAN)])]
+    fn test_double_nan(#[case] opcode: u8, #[case] operands: Vec<Slot>) {
+        assert!(f64::from(execute_op(opcode, operands).unwrap()).is_nan());
+    }
+
+    #[rstest]
+    #[case::i2l(0x85, int(-1), long(-1))]
+    #[case::i2f(0x86, int(16777217), float(16777216.0))]
+    #[case::i2d(0x87, int(i32::MIN), double(-2147483648.0))]
+    #[case::l2i(0x88, long(0x1_8000_0000), int(i32::MIN))]
+    #[case::l2f(0x89, long(-1), float(-1.0))]
+    #[case::l2d(0x8a, long(i64::MAX), double(9.223372036854776e18))]
+    #[case::f2i_nan(0x8b, float(f32::NAN), int(0))]
+    #[case::f2i_saturate(0x8b, float(1e20), int(i32::MAX))]
+    #[case::f2i_truncate(0x8b, float(-2.9), int(-2))]
+    #[case::f2l_saturate(0x8c, float(f32::NEG_INFINITY), long(i64::MIN))]
+    #[case::f2d(0x8d, float(-0.0), double(-0.0))]
+    #[case::d2i_saturate(0x8e, double(-1e300), int(i32::MIN))]
+    #[case::d2l_nan(0x8f, double(f64::NAN), long(0))]
+    #[case::d2l_saturate(0x8f, double(1e19), long(i64::MAX))]
+    #[case::d2f_overflow(0x90, double(1e300), float(f32::INFINITY))]
+    #[case::i2b(0x91, int(0x1ff), int(-1))]
+    #[case::i2c(0x92, int(-1), int(0xffff))]
+    #[case::i2s(0x93, int(0x18000), int(-32768))]
+    fn test_conversion(#[case] opcode: u8, #[case] operand: Slot, #[case] expected: Slot) {
+        assert_eq!(execute_op(opcode, vec![operand]).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::lcmp_greater(0x94, vec![long(1), long(-1)], 1)]
+    #[case::lcmp_equal(0x94, vec![long(i64::MIN), long(i64::MIN)], 0)]
+    #[case::lcmp_less(0x94, vec![long(i64::MIN), long(i64::MAX)], -1)]
+    #[case::fcmpl_nan(0x95, vec![float(f32::NAN), float(1.0)], -1)]
+    #[case::fcmpg_nan(0x96, vec![float(1.0), float(f32::NAN)], 1)]
+    #[case::fcmpl_zero(0x95, vec![float(-0.0), float(0.0)], 0)]
+    #[case::fcmpg_less(0x96, vec![float(f32::NEG_INFINITY), float(0.0)], -1)]
+    #[case::dcmpl_nan(0x97, vec![double(f64::NAN), double(f64::NAN)], -1)]
+    #[case::dcmpg_nan(0x98, vec![double(f64::NAN), double(0.0)], 1)]
+    #[case::dcmpg_greater(0x98, vec![double(2.0), double(1.0)], 1)]
+    fn test_compare(#[case] opcode: u8, #[case] operands: Vec<Slot>, #[case] expected: i32) {
+        assert_eq!(execute_op(opcode, operands).unwrap(), int(expected));
+    }
+
+    /// 属性测试用的xorshift伪随机数, 固定种子便于复现
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+        /// 随机位模式覆盖NaN, 无穷, 非规格化数, 再混入边界值
+        fn next_f64(&mut self) -> f64 {
+            const SPECIAL: [f64; 8] = [
+                0.0,
+                -0.0,
+                2147483647.5,
+                -2147483648.5,
+                9.223372036854776e18,
+                -9.223372036854776e18,
+                f64::INFINITY,
+                f64::NAN,
+            ];
+            let bits = self.next();
+            match bits % 8 {
+                0 => SPECIAL[(bits >> 8) as usize % SPECIAL.len()],
+                1 => (bits as i64 >> (bits % 40)) as f64 + 0.5,
+                _ => f64::from_bits(bits),
+            }
+        }
+        fn next_f32(&mut self) -> f32 {
+            let bits = self.next();
+            match bits % 4 {
+                0 => self.next_f64() as f32,
+                _ => f32::from_bits(bits as u32),
+            }
+        }
+    }
+
+    /// JLS 5.1.3: NaN为0, 超出范围时取最接近的值, 其余向零取整
+    fn jls_to_int(value: f64) -> i32 {
+        if value.is_nan() {
+            0
+        } else if value >= 2147483648.0 {
+            i32::MAX
+        } else if value <= -2147483649.0 {
+            i32::MIN
+        } else {
+            value.trunc() as i32
+        }
+    }
+    fn jls_to_long(value: f64) -> i64 {
+        if value.is_nan() {
+            0
+        } else if value >= 9223372036854775808.0 {
+            i64::MAX
+        } else if value <= -9223372036854775808.0 {
+            i64::MIN
+        } else {
+            value.trunc() as i64
+        }
+    }
+    /// JVMS fcmp<op>/dcmp<op>
+    fn jvms_compare(a: f64, b: f64, nan: i32) -> i32 {
+        if a.is_nan() || b.is_nan() {
+            nan
+        } else if a > b {
+            1
+        } else if a == b {
+            0
+        } else {
+            -1
+        }
+    }
+
+    #[test]
+    fn test_float_to_integer_property() {
+        let mut random = XorShift(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..5000 {
+            let value = random.next_f64();
+            assert_eq!(
+                execute_op(0x8e, vec![double(value)]).unwrap(),
+                int(jls_to_int(value)),
+                "d2i {value}"
+            );
+            assert_eq!(
+                execute_op(0x8f, vec![double(value)]).unwrap(),
+                long(jls_to_long(value)),
+                "d2l {value}"
+            );
+            let value = random.next_f32();
+            // f32到f64是精确的
+            assert_eq!(
+                execute_op(0x8b, vec![float(value)]).unwrap(),
+                int(jls_to_int(value as f64)),
+                "f2i {value}"
+            );
+            assert_eq!(
+                execute_op(0x8c, vec![float(value)]).unwrap(),
+                long(jls_to_long(value as f64)),
+                "f2l {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_integer_narrowing_property() {
+        let mut random = XorShift(0x2545_f491_4f6c_dd1d);
+        for _ in 0..5000 {
+            let value = random.next();
+            let low = (value & 0xffff_ffff) as u32 as i32;
+            assert_eq!(
+                execute_op(0x88, vec![long(value as i64)]).unwrap(),
+                int(low)
+            );
+            // 保留低位后做符号扩展或零扩展
+            assert_eq!(
+                execute_op(0x91, vec![int(low)]).unwrap(),
+                int(((low & 0xff) ^ 0x80) - 0x80)
+            );
+            assert_eq!(execute_op(0x92, vec![int(low)]).unwrap(), int(low & 0xffff));
+            assert_eq!(
+                execute_op(0x93, vec![int(low)]).unwrap(),
+                int(((low & 0xffff) ^ 0x8000) - 0x8000)
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_property() {
+        let mut random = XorShift(0xdead_beef_cafe_f00d);
+        for _ in 0..5000 {
+            let (a, b) = (random.next_f64(), random.next_f64());
+            assert_eq!(
+                execute_op(0x97, vec![double(a), double(b)]).unwrap(),
+                int(jvms_compare(a, b, -1))
+            );
+            assert_eq!(
+                execute_op(0x98, vec![double(a), double(b)]).unwrap(),
+                int(jvms_compare(a, b, 1))
+            );
+            let (a, b) = (random.next_f32(), random.next_f32());
+            assert_eq!(
+                execute_op(0x95, vec![float(a), float(b)]).unwrap(),
+                int(jvms_compare(a as f64, b as f64, -1))
+            );
+            assert_eq!(
+                execute_op(0x96, vec![float(a), float(b)]).unwrap(),
+                int(jvms_compare(a as f64, b as f64, 1))
+            );
+            let (a, b) = (
+                random.next() as i64,
+                random.next() as i64 >> (random.next() % 64),
+            );
+            let expected = (a > b) as i32 - (a < b) as i32;
+            assert_eq!(
+                execute_op(0x94, vec![long(a), long(b)]).unwrap(),
+                int(expected)
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::ifeq_taken(0x99, vec![int(0)], true)]
+    #[case::ifeq(0x99, vec![int(1)], false)]
+    #[case::ifne_taken(0x9a, vec![int(-1)], true)]
+    #[case::iflt_taken(0x9b, vec![int(i32::MIN)], true)]
+    #[case::iflt(0x9b, vec![int(0)], false)]
+    #[case::ifge_taken(0x9c, vec![int(0)], true)]
+    #[case::ifgt(0x9d, vec![int(0)], false)]
+    #[case::ifle_taken(0x9e, vec![int(0)], true)]
+    #[case::if_icmpeq_taken(0x9f, vec![int(3), int(3)], true)]
+    #[case::if_icmpne(0xa0, vec![int(3), int(3)], false)]
+    #[case::if_icmplt_taken(0xa1, vec![int(-1), int(0)], true)]
+    #[case::if_icmpge(0xa2, vec![int(-1), int(0)], false)]
+    #[case::if_icmpgt_taken(0xa3, vec![int(1), int(0)], true)]
+    #[case::if_icmple(0xa4, vec![int(1), int(0)], false)]
+    #[case::if_acmpeq_taken(0xa5, vec![reference(1), reference(1)], true)]
+    #[case::if_acmpne_taken(0xa6, vec![reference(1), Slot::Ref(None)], true)]
+    #[case::ifnull_taken(0xc6, vec![Slot::Ref(None)], true)]
+    #[case::ifnonnull(0xc7, vec![Slot::Ref(None)], false)]
+    fn test_conditional_branch(
+        #[case] opcode: u8,
+        #[case] operands: Vec<Slot>,
+        #[case] taken: bool,
+    ) {
+        // nop; if<cond> +7
+        let mut thread = thread_with_code(vec![0x00, opcode, 0x00, 0x07]);
+        thread.current_frame_mut().pc = 1;
+        for operand in operands {
+            thread.push(operand);
+        }
+        thread.execute(opcode).unwrap();
+        assert_eq!(thread.current_frame().pc, if taken { 8 } else { 4 });
+    }
+
+    #[rstest]
+    #[case::goto_forward(vec![0x00, 0xa7, 0x00, 0x10], 0x11, 0)]
+    #[case::goto_backward(vec![0x00, 0xa7, 0xff, 0xff], 0, 1)]
+    #[case::goto_w(vec![0x00, 0xc8, 0x00, 0x01, 0x00, 0x00], 0x10001, 0)]
+    #[case::goto_w_backward(vec![0x00, 0xc8, 0xff, 0xff, 0xff, 0xff], 0, 1)]
+    fn test_goto(#[case] code: Vec<u8>, #[case] pc: u32, #[case] backward_branches: u64) {
+        let mut thread = thread_with_code(code);
+        thread.current_frame_mut().pc = 1;
+        let opcode = thread.fetch();
+        thread.execute(opcode).unwrap();
+        assert_eq!(thread.current_frame().pc, pc as u16);
+        assert_eq!(thread.backward_branches, backward_branches);
+    }
+
+    fn switch_code(prefix: usize, opcode: u8, operands: &[i32]) -> Vec<u8> {
+        let mut code = vec![0x00; prefix];
+        code.push(opcode);
+        while !code.len().is_multiple_of(4) {
+            code.push(0x00);
+        }
+        for operand in operands {
+            code.extend(operand.to_be_bytes());
+        }
+        code
+    }
+
+    // 不同的起始地址对应0-3字节的填充
+    #[rstest]
+    fn test_tableswitch(
+        #[values(0, 1, 2, 3)] prefix: usize,
+        #[values((-2, 100), (-1, 10), (0, 20), (1, 30), (2, 100))] case: (i32, i32),
+    ) {
+        // default 100, low -1, high 1
+        let code = switch_code(prefix, 0xaa, &[100, -1, 1, 10, 20, 30]);
+        let mut thread = thread_with_code(code);
+        thread.current_frame_mut().pc = prefix as u16;
+        thread.push(case.0);
+        thread.execute(0xaa).unwrap();
+        assert_eq!(thread.current_frame().pc, prefix as u16 + case.1 as u16);
+    }
+
+    #[test]
+    fn test_tableswitch_large() {
+        // 超过16383个表项时, 表项的偏移超出u16; low和high相差接近i32的范围
+        let entries = 20000;
+        let mut operands = vec![100, i32::MIN, i32::MIN + entries - 1];
+        operands.extend((0..entries).map(|entry| entry % 1000));
+        let code = switch_code(0, 0xaa, &operands);
+        let mut thread = thread_with_code(code);
+        thread.push(i32::MIN + 17003);
+        thread.execute(0xaa).unwrap();
+        assert_eq!(thread.current_frame().pc, 3);
+    }
+
+    #[rstest]
+    fn test_lookupswitch(
+        #[values(0, 1, 2, 3)] prefix: usize,
+        #[values((i32::MIN, 40), (-5, 50), (0, 100), (7, 60), (1000, 70), (1001, 100))] case: (
+            i32,
+            i32,
+        ),
+    ) {
+        // default 100, npairs 4
+        let code = switch_code(
+            prefix,
+            0xab,
+            &[100, 4, i32::MIN, 40, -5, 50, 7, 60, 1000, 70],
+        );
+        let mut thread = thread_with_code(code);
+        thread.current_frame_mut().pc = prefix as u16;
+        thread.push(case.0);
+        thread.execute(0xab).unwrap();
+        assert_eq!(thread.current_frame().pc, prefix as u16 + case.1 as u16);
+    }
+
+    #[test]
+    fn test_loop() {
+        // int sum = 0; for (int i = 1; i <= 10; i++) sum += i;
+        let mut thread = thread_with_code(vec![
+            0x03, 0x3b, 0x04, 0x3c, 0x1a, 0x1b, 0x60, 0x3b, 0x84, 0x01, 0x01, 0x1b, 0x10, 0x0a,
+            0xa4, 0xff, 0xf6, 0x00,
+        ]);
+        while thread.current_frame().pc != 17 {
+            let opcode = thread.fetch();
+            thread.execute(opcode).unwrap();
+        }
+        assert_eq!(thread.get::<i32>(0), 55);
+        assert_eq!(thread.backward_branches, 9);
+    }
+
+    /// 在Invoke类上执行静态方法的线程
+    fn invoke_thread() -> Thread {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let vm = Vm::new(ClassLoader::new(class_path));
+        let klass = vm.class_loader().load_class("Invoke").unwrap();
+        let method = Method {
+            constant_pool: klass.constant_pool().clone(),
+            ..Default::default()
+        };
+        Thread::new(0, Arc::new(method), Arc::new(vm))
+    }
+
+    fn new_instance(thread: &Thread, class: &str) -> Slot {
+        let klass = thread.vm.class_loader().load_class(class).unwrap();
+        Slot::Ref(Some(thread.vm.heap().alloc_instance(klass)))
+    }
+
+    fn call_invoke(
+        thread: &mut Thread,
+        name: &str,
+        descriptor: &str,
+        args: Vec<Slot>,
+    ) -> Result<Option<Slot>, RuntimeError> {
+        let klass = thread.vm.class_loader().load_class("Invoke").unwrap();
+        let method = klass.find_method(name, descriptor).unwrap();
+        let result = thread.call(method, args);
+        assert_eq!(thread.stack.len(), 1);
+        result
+    }
+
+    #[rstest]
+    #[case::virtual_base("callValue", "InvokeBase", 1)]
+    #[case::virtual_derived("callValue", "InvokeDerived", 2)]
+    #[case::super_call("callPub", "InvokeDerived", 15)]
+    #[case::nestmate_private("callSecret", "InvokeBase", 200)]
+    #[case::private_not_overridden("callSecret", "InvokeDerived", 200)]
+    fn test_invoke_class(#[case] name: &str, #[case] class: &str, #[case] expected: i32) {
+        let mut thread = invoke_thread();
+        let receiver = new_instance(&thread, class);
+        let result = call_invoke(&mut thread, name, "(LInvokeBase;)I", vec![receiver]);
+        assert_eq!(result.unwrap(), Some(Slot::from(expected)));
+    }
+
+    #[rstest]
+    #[case::default_method("callHello", "InvokeImpl", 42)]
+    #[case::super_default("callHello", "InvokeConflict", 43)]
+    #[case::inherited_abstract("callAbs", "InvokeImpl", 7)]
+    #[case::implemented("callAbs", "InvokeConflict", 8)]
+    fn test_invoke_interface(#[case] name: &str, #[case] class: &str, #[case] expected: i32) {
+        let mut thread = invoke_thread();
+        let receiver = new_instance(&thread, class);
+        let result = call_invoke(&mut thread, name, "(LInvokeIface;)I", vec![receiver]);
+        assert_eq!(result.unwrap(), Some(Slot::from(expected)));
+    }
+
+    #[test]
+    fn test_invoke_static() {
+        let mut thread = invoke_thread();
+        let result = call_invoke(&mut thread, "callAdd", "()J", vec![]);
+        assert_eq!(result.unwrap(), Some(Slot::from((1_i64 << 40) + 6 + 2)));
+        let result = call_invoke(&mut thread, "fib", "(I)I", vec![Slot::from(15)]);
+        assert_eq!(result.unwrap(), Some(Slot::from(610)));
+    }
+
+    #[test]
+    fn test_return_types() {
+        let mut thread = invoke_thread();
+        let result = call_invoke(&mut thread, "half", "(F)F", vec![Slot::from(3.0_f32)]);
+        assert_eq!(result.unwrap(), Some(Slot::from(1.5_f32)));
+        let result = call_invoke(&mut thread, "neg", "(D)D", vec![Slot::from(2.5_f64)]);
+        assert_eq!(result.unwrap(), Some(Slot::from(-2.5_f64)));
+        let object = new_instance(&thread, "InvokeBase");
+        let descriptor = "(Ljava/lang/Object;)Ljava/lang/Object;";
+        let result = call_invoke(&mut thread, "same", descriptor, vec![object.clone()]);
+        assert_eq!(result.unwrap(), Some(object));
+    }
+
+    #[test]
+    fn test_invoke_null_receiver() {
+        let mut thread = invoke_thread();
+        let result = call_invoke(
+            &mut thread,
+            "callValue",
+            "(LInvokeBase;)I",
+            vec![Slot::Ref(None)],
+        );
+        let err = result.unwrap_err().to_string();
+        assert!(err.starts_with("java.lang.NullPointerException: "));
+        assert!(err.contains("InvokeBase.value"));
+    }
+
+    #[test]
+    fn test_invoke_interface_not_implemented() {
+        let mut thread = invoke_thread();
+        let receiver = new_instance(&thread, "InvokeBase");
+        let result = call_invoke(&mut thread, "callHello", "(LInvokeIface;)I", vec![receiver]);
+        let err = result.unwrap_err();
+        assert!(err.is_error());
+        assert!(
+            err.to_string()
+                .starts_with("java.lang.IncompatibleClassChangeError: ")
+        );
+    }
+
+    #[test]
+    fn test_polymorphic_loop() {
+        let mut thread = invoke_thread();
+        let receivers: Vec<Slot> = ["PolyA", "PolyB", "PolyD"]
+            .iter()
+            .map(|class| new_instance(&thread, class))
+            .collect();
+        let poly = thread.vm.class_loader().load_class("Poly").unwrap();
+        let call = |thread: &mut Thread, name: &str, descriptor: &str, args: &[Slot]| {
+            let method = poly.find_method(name, descriptor).unwrap();
+            let mut args = args.to_vec();
+            args.push(Slot::from(30));
+            thread.call(method, args).unwrap().unwrap()
+        };
+        // 每轮 (1 + 100) + (2 + 100) + (3 + 100)
+        let virtual_descriptor = "(LPolyBase;LPolyBase;LPolyBase;I)I";
+        let result = call(&mut thread, "virtualLoop", virtual_descriptor, &receivers);
+        assert_eq!(result, Slot::from(10 * 306));
+        // 每轮 3 + 4 + 3
+        let descriptor = "(LPolyShape;LPolyShape;LPolyShape;I)I";
+        let result = call(&mut thread, "interfaceLoop", descriptor, &receivers);
+        assert_eq!(result, Slot::from(10 * 10));
+        let result = call(
+            &mut thread,
+            "monomorphicLoop",
+            "(LPolyBase;I)I",
+            &receivers[2..],
+        );
+        assert_eq!(result, Slot::from(30 * 3));
+
+        // 调用点的内联缓存绑定到第一次遇到的接收者类
+        let method = poly
+            .find_method("monomorphicLoop", "(LPolyBase;I)I")
+            .unwrap();
+        let cache = method.inline_caches.get(11).unwrap();
+        assert_eq!(cache.receiver().unwrap().name(), "PolyD");
+        let method = poly.find_method("virtualLoop", virtual_descriptor).unwrap();
+        let cache = method.inline_caches.get(42).unwrap();
+        assert_eq!(cache.receiver().unwrap().name(), "PolyA");
+    }
+
+    /// 在Fields类的常量池上执行`code`, 常量下标见`javap -v Fields.class`
+    fn fields_thread(code: Vec<u8>) -> Thread {
+        class_thread("Fields", code)
+    }
+
+    fn class_thread(class: &str, code: Vec<u8>) -> Thread {
+        gc_thread(class, code, GcOptions::default())
+    }
+
+    fn gc_thread(class: &str, code: Vec<u8>, options: GcOptions) -> Thread {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let vm = Vm::with_options(ClassLoader::new(class_path), options);
+        let klass = vm.class_loader().load_class(class).unwrap();
+        let method = Method {
+            name: "test".to_string(),
+            max_stack: 10,
+            code,
+            constant_pool: klass.constant_pool().clone(),
+            ..Default::default()
+        };
+        Thread::new(0, Arc::new(method), Arc::new(vm))
+    }
+
+    fn call_fields(
+        thread: &mut Thread,
+        name: &str,
+        args: Vec<Slot>,
+    ) -> Result<Option<Slot>, RuntimeError> {
+        call_static(thread, "Fields", name, args)
+    }
+
+    /// 按名称调用`class`中的方法
+    fn call_static(
+        thread: &mut Thread,
+        class: &str,
+        name: &str,
+        args: Vec<Slot>,
+    ) -> Result<Option<Slot>, RuntimeError> {
+        let klass = thread.vm.class_loader().load_class(class).unwrap();
+        let method = klass
+            .methods()
+            .iter()
+            .find(|method| method.name == name)
+            .unwrap()
+            .clone();
+        let result = thread.call(method, args);
+        assert_eq!(thread.stack.len(), 1);
+        result
+    }
+
+    fn make_fields(thread: &mut Thread, i: i32, l: i64) -> Slot {
+        call_fields(thread, "make", vec![Slot::from(i), Slot::from(l)])
+            .unwrap()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case(5, 10, 35)]
+    // (byte) 200 == -56
+    #[case(200, 0, 744)]
+    #[case(-1, 1 << 40, (1 << 40) + 0xffff - 4)]
+    fn test_get_put_field(#[case] i: i32, #[case] l: i64, #[case] expected: i64) {
+        let mut thread = fields_thread(vec![]);
+        let fields = make_fields(&mut thread, i, l);
+        let sum = call_fields(&mut thread, "sum", vec![fields]).unwrap();
+        assert_eq!(sum, Some(Slot::from(expected)));
+    }
+
+    #[test]
+    fn test_get_put_static() {
+        let mut thread = fields_thread(vec![]);
+        let klass = thread.vm.class_loader().load_class("Fields").unwrap();
+        assert_eq!(klass.init_state(), InitState::Linked);
+        make_fields(&mut thread, 1, 10);
+        make_fields(&mut thread, 2, 20);
+        // new触发初始化, <clinit>中total = 100
+        assert_eq!(klass.init_state(), InitState::Initialized);
+        let total = klass.find_field("total", "J").unwrap();
+        assert_eq!(klass.get_static(total.offset), Slot::from(130i64));
+        // 静态字段存放在声明它的类中
+        let created = call_fields(&mut thread, "created", vec![]).unwrap();
+        assert_eq!(created, Some(Slot::from(2)));
+        let base = thread.vm.class_loader().load_class("FieldsBase").unwrap();
+        let field = base.find_field("created", "I").unwrap();
+        assert_eq!(base.get_static(field.offset), Slot::from(2));
+    }
+
+    #[test]
+    fn test_reference_fields() {
+        let mut thread = fields_thread(vec![]);
+        let first = make_fields(&mut thread, 1, 0);
+        let second = make_fields(&mut thread, 2, 0);
+        let linked = call_fields(&mut thread, "link", vec![first.clone(), second.clone()]);
+        assert_eq!(linked.unwrap(), Some(second.clone()));
+        let next = call_fields(&mut thread, "readNext", vec![first]).unwrap();
+        assert_eq!(next, Some(Slot::from(2)));
+        // second.next为null
+        let err = call_fields(&mut thread, "readNext", vec![second]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "java.lang.NullPointerException: Cannot read field \"i\" because value is null"
+        );
+    }
+
+    #[rstest]
+    #[case::interface("isMarker", "Fields", true)]
+    #[case::not_implemented("isMarker", "FieldsBase", false)]
+    #[case::same_class("isFields", "Fields", true)]
+    #[case::superclass("isFields", "FieldsBase", false)]
+    fn test_instanceof(#[case] name: &str, #[case] class: &str, #[case] expected: bool) {
+        let mut thread = fields_thread(vec![]);
+        let object = new_instance(&thread, class);
+        let result = call_fields(&mut thread, name, vec![object]).unwrap();
+        assert_eq!(result, Some(Slot::from(expected as i32)));
+    }
+
+    #[test]
+    fn test_instanceof_null() {
+        let mut thread = fields_thread(vec![]);
+        let result = call_fields(&mut thread, "isMarker", vec![Slot::Ref(None)]).unwrap();
+        assert_eq!(result, Some(Slot::from(0)));
+    }
+
+    #[test]
+    fn test_checkcast() {
+        let mut thread = fields_thread(vec![]);
+        let object = new_instance(&thread, "Fields");
+        let result = call_fields(&mut thread, "cast", vec![object.clone()]).unwrap();
+        assert_eq!(result, Some(object));
+        let result = call_fields(&mut thread, "cast", vec![Slot::Ref(None)]).unwrap();
+        assert_eq!(result, Some(Slot::Ref(None)));
+        let object = new_instance(&thread, "FieldsBase");
+        let err = call_fields(&mut thread, "cast", vec![object]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "java.lang.ClassCastException: class FieldsBase cannot be cast to class Fields"
+        );
+    }
+
+    #[rstest]
+    // new FieldsMarker
+    #[case::new_interface(vec![0xbb, 0, 50], "java.lang.InstantiationError: FieldsMarker")]
+    // null.id = 1, id是FieldsBase的final字段
+    #[case::final_field(
+        vec![0x01, 0x04, 0xb5, 0, 39],
+        "java.lang.IllegalAccessError: Update to non-static final field FieldsBase.id \
+         attempted from a different class (Fields) than the field's declaring class"
+    )]
+    // Fields.i是实例字段
+    #[case::getstatic_instance_field(
+        vec![0xb2, 0, 10],
+        "java.lang.IncompatibleClassChangeError: Expected static field Fields.i"
+    )]
+    // Fields.total是静态字段
+    #[case::getfield_static_field(
+        vec![0x01, 0xb4, 0, 36],
+        "java.lang.IncompatibleClassChangeError: Expected non-static field Fields.total"
+    )]
+    fn test_field_errors(#[case] code: Vec<u8>, #[case] expected: &str) {
+        let mut thread = fields_thread(code);
+        let err = thread.run().unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    fn call_arrays(
+        thread: &mut Thread,
+        name: &str,
+        args: Vec<Slot>,
+    ) -> Result<Option<Slot>, RuntimeError> {
+        call_static(thread, "Arrays", name, args)
+    }
+
+    fn array_of(thread: &mut Thread, name: &str, n: i32) -> Slot {
+        call_arrays(thread, name, vec![Slot::from(n)])
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_primitive_arrays() {
+        let mut thread = class_thread("Arrays", vec![]);
+        let values = array_of(&mut thread, "range", 5);
+        let sum = call_arrays(&mut thread, "sum", vec![values]).unwrap();
+        assert_eq!(sum, Some(Slot::from(10)));
+        // 44 + 65535 - 25536 + 1 + 1 + 3 + 5
+        let typed = call_arrays(&mut thread, "typed", vec![Slot::from(1)]).unwrap();
+        assert_eq!(typed, Some(Slot::from(40053)));
+        let empty = array_of(&mut thread, "range", 0);
+        let sum = call_arrays(&mut thread, "sum", vec![empty]).unwrap();
+        assert_eq!(sum, Some(Slot::from(0)));
+    }
+
+    #[rstest]
+    #[case::index_too_large(
+        3,
+        "java.lang.ArrayIndexOutOfBoundsException: Index 3 out of bounds for length 3"
+    )]
+    #[case::negative_index(
+        -1,
+        "java.lang.ArrayIndexOutOfBoundsException: Index -1 out of bounds for length 3"
+    )]
+    fn test_array_index_out_of_bounds(#[case] index: i32, #[case] expected: &str) {
+        let mut thread = class_thread("Arrays", vec![]);
+        let values = array_of(&mut thread, "range", 3);
+        let err = call_arrays(&mut thread, "get", vec![values, Slot::from(index)]).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case::newarray("range", vec![Slot::from(-1)], "java.lang.NegativeArraySizeException: -1")]
+    #[case::anewarray("elements", vec![Slot::from(-2)], "java.lang.NegativeArraySizeException: -2")]
+    #[case::multianewarray(
+        "grid",
+        vec![Slot::from(2), Slot::from(-3)],
+        "java.lang.NegativeArraySizeException: -3"
+    )]
+    #[case::arraylength(
+        "sum",
+        vec![Slot::Ref(None)],
+        "java.lang.NullPointerException: Cannot read the array length because value is null"
+    )]
+    fn test_array_errors(#[case] name: &str, #[case] args: Vec<Slot>, #[case] expected: &str) {
+        let mut thread = class_thread("Arrays", vec![]);
+        let err = call_arrays(&mut thread, name, args).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    fn array_class(thread: &Thread, array: &Slot) -> String {
+        let array: Option<ObjectRef> = array.clone().into();
+        thread
+            .vm
+            .heap()
+            .klass(array.unwrap())
+            .unwrap()
+            .name()
+            .to_string()
+    }
+
+    fn array_element(thread: &Thread, array: &Slot, index: i32) -> Slot {
+        let array: Option<ObjectRef> = array.clone().into();
+        thread.vm.heap().array_load(array, index).unwrap()
+    }
+
+    #[test]
+    fn test_multianewarray() {
+        let mut thread = class_thread("Arrays", vec![]);
+        let grid = call_arrays(&mut thread, "grid", vec![Slot::from(2), Slot::from(3)])
+            .unwrap()
+            .unwrap();
+        assert_eq!(array_class(&thread, &grid), "[[J");
+        let row = array_element(&thread, &grid, 1);
+        assert_eq!(array_class(&thread, &row), "[J");
+        assert_eq!(array_element(&thread, &row, 2), Slot::from(7i64));
+        assert_eq!(array_element(&thread, &row, 0), Slot::from(0i64));
+        let first_row = array_element(&thread, &grid, 0);
+        assert_ne!(first_row, row);
+
+        // 只分配指定的前两维
+        let partial = array_of(&mut thread, "partial", 2);
+        assert_eq!(array_class(&thread, &partial), "[[[I");
+        let element = array_element(&thread, &partial, 1);
+        assert_eq!(array_class(&thread, &element), "[[I");
+        assert_eq!(array_element(&thread, &element, 1), Slot::Ref(None));
+    }
+
+    #[test]
+    fn test_aastore() {
+        let mut thread = class_thread("Arrays", vec![]);
+        let elements = array_of(&mut thread, "elements", 2);
+        assert_eq!(array_class(&thread, &elements), "[LArraysElement;");
+        let element = new_instance(&thread, "ArraysElement");
+        call_arrays(
+            &mut thread,
+            "store",
+            vec![elements.clone(), element.clone()],
+        )
+        .unwrap();
+        assert_eq!(array_element(&thread, &elements, 0), element);
+        call_arrays(
+            &mut thread,
+            "store",
+            vec![elements.clone(), Slot::Ref(None)],
+        )
+        .unwrap();
+        assert_eq!(array_element(&thread, &elements, 0), Slot::Ref(None));
+
+        let object = new_instance(&thread, "java/lang/Object");
+        let err = call_arrays(&mut thread, "store", vec![elements, object.clone()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "java.lang.ArrayStoreException: java.lang.Object"
+        );
+        // 下标检查先于类型检查
+        let empty = array_of(&mut thread, "elements", 0);
+        let err = call_arrays(&mut thread, "store", vec![empty, object]).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("java.lang.ArrayIndexOutOfBoundsException: ")
+        );
+    }
+
+    #[rstest]
+    #[case::object_array("isObjectArray", "elements", true)]
+    #[case::int_array_not_object_array("isObjectArray", "range", false)]
+    #[case::nested_array_is_object_array("isObjectArray", "partial", true)]
+    #[case::int_array("isIntArray", "range", true)]
+    #[case::object_array_not_int_array("isIntArray", "elements", false)]
+    #[case::cloneable("isCloneable", "range", true)]
+    fn test_array_instanceof(#[case] name: &str, #[case] factory: &str, #[case] expected: bool) {
+        let mut thread = class_thread("Arrays", vec![]);
+        let array = array_of(&mut thread, factory, 1);
+        let result = call_arrays(&mut thread, name, vec![array]).unwrap();
+        assert_eq!(result, Some(Slot::from(expected as i32)));
+    }
+
+    #[test]
+    fn test_array_checkcast() {
+        let mut thread = class_thread("Arrays", vec![]);
+        let elements = array_of(&mut thread, "elements", 3);
+        let length = call_arrays(&mut thread, "length", vec![elements]).unwrap();
+        assert_eq!(length, Some(Slot::from(3)));
+        let values = array_of(&mut thread, "range", 3);
+        let err = call_arrays(&mut thread, "length", vec![values]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "java.lang.ClassCastException: class [I cannot be cast to class [Ljava.lang.Object;"
+        );
+    }
+
+    fn call_exceptions(
+        thread: &mut Thread,
+        name: &str,
+        args: Vec<Slot>,
+    ) -> Result<Option<Slot>, RuntimeError> {
+        call_static(thread, "Exceptions", name, args)
+    }
+
+    #[rstest]
+    #[case::athrow("catchCustom", vec![Slot::from(7)], 7)]
+    #[case::unwind_to_superclass_handler("catchSuper", vec![], 2)]
+    #[case::divide_by_zero("catchDivide", vec![Slot::from(1), Slot::from(0)], -1)]
+    #[case::no_exception("catchDivide", vec![Slot::from(6), Slot::from(3)], 2)]
+    #[case::null_array("catchNull", vec![Slot::Ref(None)], -2)]
+    #[case::outer_handler("nested", vec![Slot::from(0)], -4)]
+    #[case::operand_stack_cleared("loopCatch", vec![Slot::from(50)], 50)]
+    #[case::throw_null("catchThrowable", vec![], 1)]
+    fn test_catch(#[case] name: &str, #[case] args: Vec<Slot>, #[case] expected: i32) {
+        let mut thread = class_thread("Exceptions", vec![]);
+        let result = call_exceptions(&mut thread, name, args).unwrap();
+        assert_eq!(result, Some(Slot::from(expected)));
+    }
+
+    #[test]
+    fn test_catch_subclass() {
+        let mut thread = class_thread("Exceptions", vec![]);
+        let values = call_static(&mut thread, "Arrays", "range", vec![Slot::from(2)])
+            .unwrap()
+            .unwrap();
+        let result = call_exceptions(
+            &mut thread,
+            "catchIndex",
+            vec![values.clone(), Slot::from(1)],
+        );
+        assert_eq!(result.unwrap(), Some(Slot::from(1)));
+        // ArrayIndexOutOfBoundsException由IndexOutOfBoundsException的处理器捕获
+        let result = call_exceptions(&mut thread, "catchIndex", vec![values, Slot::from(2)]);
+        assert_eq!(result.unwrap(), Some(Slot::from(-3)));
+    }
+
+    #[test]
+    fn test_finally() {
+        let mut thread = class_thread("Exceptions", vec![]);
+        let klass = thread.vm.class_loader().load_class("Exceptions").unwrap();
+        let count = klass.find_field("finallyCount", "I").unwrap();
+        let result = call_exceptions(
+            &mut thread,
+            "withFinally",
+            vec![Slot::from(4), Slot::from(2)],
+        );
+        assert_eq!(result.unwrap(), Some(Slot::from(2)));
+        assert_eq!(klass.get_static(count.offset), Slot::from(1));
+        // finally执行后重新抛出
+        let err = call_exceptions(
+            &mut thread,
+            "withFinally",
+            vec![Slot::from(4), Slot::from(0)],
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "java.lang.ArithmeticException: / by zero");
+        assert_eq!(klass.get_static(count.offset), Slot::from(2));
+    }
+
+    #[test]
+    fn test_uncaught() {
+        let mut thread = class_thread("Exceptions", vec![]);
+        let err = call_exceptions(&mut thread, "notCaught", vec![]).unwrap_err();
+        let RuntimeError::Throwable {
+            object, is_error, ..
+        } = &err
+        else {
+            panic!("not a java exception: {}", err);
+        };
+        assert_eq!(err.to_string(), "ExceptionsCustom: custom");
+        assert!(!is_error);
+        assert!(!err.is_error());
+        let class = thread.vm.heap().klass(*object).unwrap();
+        assert_eq!(class.name(), "ExceptionsCustom");
+
+        let err = call_exceptions(&mut thread, "throwError", vec![]).unwrap_err();
+        assert_eq!(err.to_string(), "ExceptionsError: fatal");
+        assert!(err.is_error());
+    }
+
+    #[test]
+    fn test_implicit_exception_message() {
+        let mut thread = class_thread("Exceptions", vec![]);
+        let message = call_exceptions(&mut thread, "caughtMessage", vec![]).unwrap();
+        let Some(Slot::Ref(Some(message))) = message else {
+            panic!("message is null");
+        };
+        assert!(matches!(
+            thread.vm.heap().get(message),
+            Object::String(message) if message == "Cannot store to null array"
+        ));
+    }
+
+    /// 通过Throwable.getStackTrace得到的栈轨迹, 每一项按StackTraceElement.toString的格式
+    fn java_stack_trace(thread: &mut Thread, name: &str, args: Vec<Slot>) -> Vec<String> {
+        let trace = call_static(thread, "StackTraces", name, args)
+            .unwrap()
+            .unwrap();
+        let heap = thread.vm.heap();
+        let length = heap.array_length(trace.clone().into()).unwrap();
+        (0..length)
+            .map(|index| {
+                let Slot::Ref(Some(element)) = array_element(thread, &trace, index) else {
+                    panic!("element is null");
+                };
+                let klass = heap.klass(element).unwrap();
+                let field = |name: &str, descriptor: &str| {
+                    let (_, field) = klass.lookup_field(name, descriptor).unwrap();
+                    heap.get_field(Some(element), &field).unwrap()
+                };
+                let string = |name: &str| match field(name, "Ljava/lang/String;") {
+                    Slot::Ref(Some(string)) => match heap.get(string) {
+                        Object::String(string) => Some(string),
+                        _ => panic!("not a string"),
+                    },
+                    _ => None,
+                };
+                StackTraceElement {
+                    class_name: string("declaringClass").unwrap().to_string(),
+                    method_name: string("methodName").unwrap().to_string(),
+                    file_name: string("fileName").map(|name| name.to_string().into()),
+                    line_number: field("lineNumber", "I").into(),
+                }
+                .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stack_trace() {
+        let mut thread = class_thread("StackTraces", vec![]);
+        let trace = java_stack_trace(&mut thread, "explicit", vec![]);
+        // 最后一项为测试线程的入口方法
+        assert_eq!(
+            trace,
+            [
+                "StackTraces.level2(StackTraces.java:9)",
+                "StackTraces.level1(StackTraces.java:13)",
+                "StackTraces.explicit(StackTraces.java:18)",
+                "StackTraces.test(Unknown Source)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_implicit_exception_stack_trace() {
+        let mut thread = class_thread("StackTraces", vec![]);
+        let trace = java_stack_trace(&mut thread, "implicit", vec![Slot::Ref(None)]);
+        assert_eq!(trace[0], "StackTraces.implicit(StackTraces.java:27)");
+        assert_eq!(trace.len(), 2);
+    }
+
+    #[test]
+    fn test_rethrow_keeps_stack_trace() {
+        let mut thread = class_thread("StackTraces", vec![]);
+        let line = call_static(&mut thread, "StackTraces", "rethrown", vec![]).unwrap();
+        assert_eq!(line, Some(Slot::from(9)));
+    }
+
+    fn call_errors(thread: &mut Thread, name: &str) -> Result<Option<Slot>, RuntimeError> {
+        call_static(thread, "Errors", name, vec![])
+    }
+
+    #[rstest]
+    #[case::stack_overflow("catchStackOverflow", -1)]
+    #[case::no_class_def_found("catchNoClassDef", -1)]
+    #[case::superclass_handler("catchLinkage", -2)]
+    fn test_catch_error(#[case] name: &str, #[case] expected: i32) {
+        let mut thread = class_thread("Errors", vec![]);
+        let result = call_errors(&mut thread, name).unwrap();
+        assert_eq!(result, Some(Slot::from(expected)));
+    }
+
+    #[test]
+    fn test_stack_overflow() {
+        let mut thread = class_thread("Errors", vec![]);
+        let err = call_errors(&mut thread, "recurse").unwrap_err();
+        assert!(err.is_error());
+        assert_eq!(err.to_string(), "java.lang.StackOverflowError");
+        let klass = thread.vm.class_loader().load_class("Errors").unwrap();
+        let depth = klass.find_field("depth", "I").unwrap();
+        assert_eq!(
+            klass.get_static(depth.offset),
+            Slot::from(MAX_STACK_DEPTH as i32 - 1)
+        );
+    }
+
+    #[test]
+    fn test_out_of_memory() {
+        let mut thread = class_thread("Errors", vec![]);
+        let Some(Slot::Ref(Some(message))) = call_errors(&mut thread, "catchHugeArray").unwrap()
+        else {
+            panic!("message is null");
+        };
+        assert!(matches!(
+            thread.vm.heap().get(message),
+            Object::String(message) if message == "Requested array size exceeds VM limit"
+        ));
+    }
+
+    /// 缺少部分类库的classpath
+    struct WithoutClasses(&'static [&'static str]);
+
+    impl ClassPathEntry for WithoutClasses {
+        fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+            if self.0.contains(&name) {
+                return None;
+            }
+            TestContext.read_class(name)
+        }
+    }
+
+    #[test]
+    fn test_error_without_class_library() {
+        let mut class_path = ClassPath::default();
+        class_path.push(WithoutClasses(&["java/lang/StackOverflowError"]));
+        let vm = Arc::new(Vm::new(ClassLoader::new(class_path)));
+        // 异常类不存在时保留虚拟机内部的错误
+        let err = vm.run_main("Errors", &[]).unwrap_err();
+        assert!(matches!(err, RuntimeError::StackOverflowError));
+        assert_eq!(
+            vm.uncaught_exception_message("main", &err),
+            "Exception in thread \"main\" java.lang.StackOverflowError"
+        );
+    }
+
+    #[rstest]
+    #[case::locals("Garbage", "buildList", vec![Slot::from(50)], 1225)]
+    #[case::statics_and_arrays("Garbage", "keepInStatics", vec![Slot::from(30)], 1305)]
+    #[case::multi_array("Garbage", "grid", vec![Slot::from(5), Slot::from(4)], 10)]
+    #[case::exceptions("Exceptions", "loopCatch", vec![Slot::from(50)], 50)]
+    #[case::stack_traces("StackTraces", "rethrown", vec![], 9)]
+    #[case::string_natives("Strings", "churn", vec![Slot::from(40)], 383126332)]
+    fn test_stress_gc(
+        #[case] class: &str,
+        #[case] name: &str,
+        #[case] args: Vec<Slot>,
+        #[case] expected: i32,
+        #[values(CollectorKind::MarkSweep, CollectorKind::Generational)] collector: CollectorKind,
+    ) {
+        // 每次分配前都回收, 漏掉的根或写屏障会导致对象被释放
+        let options = GcOptions {
+            collector,
+            tenuring_threshold: 1,
+            stress: true,
+            ..Default::default()
+        };
+        let mut thread = gc_thread(class, vec![], options);
+        let result = call_static(&mut thread, class, name, args).unwrap();
+        assert_eq!(result, Some(Slot::from(expected)));
+        assert!(thread.vm.heap().stats().collections > 0);
+    }
+
+    #[rstest]
+    fn test_gc_reclaims_garbage(
+        #[values(CollectorKind::MarkSweep, CollectorKind::Generational)] collector: CollectorKind,
+    ) {
+        let options = GcOptions {
+            collector,
+            max_heap: 64 << 10,
+            ..Default::default()
+        };
+        let mut thread = gc_thread("Garbage", vec![], options);
+        let result = call_static(&mut thread, "Garbage", "churn", vec![Slot::from(200)]).unwrap();
+        assert_eq!(result, Some(Slot::from(19900)));
+        let heap = thread.vm.heap();
+        let stats = heap.stats();
+        assert!(stats.collections > 0);
+        assert!(stats.freed_bytes >= 100 * 4096);
+        assert!(heap.used_bytes() <= 64 << 10);
+        if collector == CollectorKind::Generational {
+            assert!(stats.young_collections > 0);
+        }
+    }
+
+    #[rstest]
+    fn test_gc_out_of_memory(
+        #[values(CollectorKind::MarkSweep, CollectorKind::Generational)] collector: CollectorKind,
+    ) {
+        let options = GcOptions {
+            collector,
+            max_heap: 64 << 10,
+            ..Default::default()
+        };
+        let mut thread = gc_thread("Garbage", vec![], options);
+        // 捕获OutOfMemoryError之后不可达的对象可以被回收
+        let result = call_static(&mut thread, "Garbage", "exhaust", vec![]).unwrap();
+        assert_eq!(result, Some(Slot::from(45)));
+    }
+
+    #[test]
+    fn test_new_throwable_reserves() {
+        let options = GcOptions {
+            stress: true,
+            ..Default::default()
+        };
+        let mut thread = gc_thread("Errors", vec![], options);
+        let object = thread
+            .new_throwable("java/lang/ArithmeticException", Some("/ by zero"))
+            .unwrap();
+        assert!(thread.vm.heap().stats().collections > 0);
+        assert_eq!(
+            thread.thrown(object).to_string(),
+            "java.lang.ArithmeticException: / by zero"
+        );
+    }
+
+    #[test]
+    fn test_fill_in_stack_trace_reserves() {
+        let options = GcOptions {
+            stress: true,
+            ..Default::default()
+        };
+        let mut thread = gc_thread("Errors", vec![], options);
+        let object = thread
+            .new_throwable("java/lang/IllegalStateException", None)
+            .unwrap();
+        let collections = thread.vm.heap().stats().collections;
+        // 异常对象不在Java栈上, 分配栈轨迹前的回收也不能释放它
+        thread.fill_in_stack_trace(object).unwrap();
+        let heap = thread.vm.heap();
+        assert!(heap.stats().collections > collections);
+        assert!(backtrace(heap, object).is_some());
+    }
+
+    #[test]
+    fn test_new_throwable_heap_limit() {
+        let options = GcOptions {
+            max_heap: 1,
+            ..Default::default()
+        };
+        let mut thread = gc_thread("Errors", vec![], options);
+        let err = thread
+            .new_throwable("java/lang/ArithmeticException", Some("/ by zero"))
+            .unwrap_err();
+        assert!(matches!(err, RuntimeError::OutOfMemoryError(_)));
+        // 堆已满时仍然可以创建OutOfMemoryError
+        let object = thread
+            .new_throwable("java/lang/OutOfMemoryError", Some("Java heap space"))
+            .unwrap();
+        assert_eq!(
+            thread.thrown(object).to_string(),
+            "java.lang.OutOfMemoryError: Java heap space"
+        );
+    }
+
+    #[test]
+    fn test_falling_off_end_of_code() {
+        let mut thread = class_thread("Errors", vec![0x00]);
+        let err = thread.run().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "java.lang.VerifyError: Falling off the end of the code in Errors.test"
+        );
+    }
+
+    fn monitors_thread() -> (Thread, ObjectRef) {
+        let thread = class_thread("Monitors", vec![]);
+        let klass = thread.vm.class_loader().load_class("Monitors").unwrap();
+        let object = thread.vm.heap().alloc_instance(klass);
+        (thread, object)
+    }
+
+    #[test]
+    fn test_synchronized_method() {
+        let (mut thread, object) = monitors_thread();
+        let this = Slot::Ref(Some(object));
+        let result = call_static(&mut thread, "Monitors", "increment", vec![this, int(5)]);
+        assert_eq!(result.unwrap(), Some(int(5)));
+        assert_ne!(thread.vm.heap().monitor_owner(object), Some(thread.id));
+        // 重入没有超过轻量级锁的次数上限
+        assert!(!thread.vm.heap().is_inflated(object));
+
+        let result = call_static(&mut thread, "Monitors", "staticCount", vec![]);
+        assert_eq!(result.unwrap(), Some(int(1)));
+        let heap = thread.vm.heap();
+        let klass = thread.vm.class_loader().load_class("Monitors").unwrap();
+        assert_ne!(heap.monitor_owner(klass.mirror(heap)), Some(thread.id));
+    }
+
+    #[test]
+    fn test_synchronized_block() {
+        let (mut thread, object) = monitors_thread();
+        let other = thread.vm.heap().alloc(Object::String("lock".into()));
+        let args = vec![Slot::Ref(Some(object)), Slot::Ref(Some(other))];
+        let result = call_static(&mut thread, "Monitors", "nested", args);
+        assert_eq!(result.unwrap(), Some(int(3)));
+        let heap = thread.vm.heap();
+        assert_ne!(heap.monitor_owner(object), Some(thread.id));
+        assert_ne!(heap.monitor_owner(other), Some(thread.id));
+
+        let err = call_static(&mut thread, "Monitors", "lockNull", vec![Slot::Ref(None)]);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "java.lang.NullPointerException: Cannot enter synchronized block because value is null"
+        );
+    }
+
+    #[test]
+    fn test_synchronized_exception() {
+        let (mut thread, object) = monitors_thread();
+        let this = Slot::Ref(Some(object));
+        // 同步方法因异常退出时释放锁
+        let err = call_static(&mut thread, "Monitors", "fail", vec![this.clone()]);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "java.lang.IllegalStateException: fail"
+        );
+        assert_ne!(thread.vm.heap().monitor_owner(object), Some(thread.id));
+        let result = call_static(&mut thread, "Monitors", "failAndCatch", vec![this.clone()]);
+        assert_eq!(result.unwrap(), Some(int(1)));
+        assert_ne!(thread.vm.heap().monitor_owner(object), Some(thread.id));
+        // 同步块由编译器生成的处理器释放锁
+        let err = call_static(&mut thread, "Monitors", "throwInBlock", vec![this]);
+        assert!(err.is_err());
+        assert_ne!(thread.vm.heap().monitor_owner(object), Some(thread.id));
+    }
+
+    #[test]
+    fn test_monitorexit_not_owner() {
+        // ldc "hello"; monitorexit
+        let mut thread = ldc_thread(vec![0x12, 0x07, 0xc3]);
+        let err = thread.run().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "java.lang.IllegalMonitorStateException: current thread is not owner"
+        );
+        // ldc "hello"; dup; monitorenter; monitorexit; return
+        let mut thread = ldc_thread(vec![0x12, 0x07, 0x59, 0xc2, 0xc3, 0xb1]);
+        thread.run().unwrap();
+    }
+
+    #[test]
+    fn test_monitor_contention() {
+        let (mut thread, object) = monitors_thread();
+        let vm = thread.vm.clone();
+        let klass = vm.class_loader().load_class("Monitors").unwrap();
+        let add = klass.find_method("add", "(LMonitors;I)I").unwrap();
+        let new_thread = |id| {
+            let mut thread = Thread::new(id, add.clone(), vm.clone());
+            thread
+                .current_frame_mut()
+                .set_args(vec![Slot::Ref(Some(object)), int(2000)]);
+            thread
+        };
+        for _ in 0..2 {
+            let id = vm.scheduler().next_id();
+            vm.scheduler().spawn(new_thread(id), None, false);
+        }
+        // 测试线程持有锁时让出, 其他线程阻塞在锁上使锁膨胀
+        assert!(vm.heap().monitor_enter(object, thread.id));
+        vm.scheduler().yield_in_place(&mut thread);
+        assert!(vm.heap().is_inflated(object));
+        vm.heap().monitor_exit(object, thread.id).unwrap();
+        vm.scheduler().run(&vm, new_thread(MAIN_THREAD_ID)).unwrap();
+        // 三个线程的自增没有丢失
+        let result = call_static(
+            &mut thread,
+            "Monitors",
+            "increment",
+            vec![Slot::Ref(Some(object)), int(0)],
+        );
+        assert_eq!(result.unwrap(), Some(int(6000)));
+        assert_eq!(vm.heap().monitor_owner(object), None);
+    }
+
+    /// 初始化`InitDefault`期间让出, 让另一个线程执行
+    struct YieldingClinit<'a>(&'a mut Thread);
+
+    impl ClassInitializer for YieldingClinit<'_> {
+        fn intern(&mut self, string: JavaString) -> Result<ObjectRef, RuntimeError> {
+            self.0.intern(string)
+        }
+        fn run_clinit(
+            &mut self,
+            klass: &Arc<Klass>,
+            clinit: Arc<Method>,
+        ) -> Result<(), RuntimeError> {
+            let vm = self.0.vm.clone();
+            vm.scheduler().yield_in_place(self.0);
+            self.0.run_clinit(klass, clinit)
+        }
+        fn wait(&mut self, klass: &Arc<Klass>) -> Result<(), RuntimeError> {
+            self.0.wait(klass)
+        }
+    }
+
+    #[test]
+    fn test_initialize_contention() {
+        let mut thread = class_thread("InitChild", vec![]);
+        let vm = thread.vm.clone();
+        let child = vm.class_loader().load_class("InitChild").unwrap();
+        let interface = vm.class_loader().load_class("InitDefault").unwrap();
+        // getstatic InitChild.value; pop; return
+        let method = Method {
+            name: "test".to_string(),
+            max_stack: 1,
+            code: vec![0xb2, 0x00, 0x07, 0x57, 0xb1],
+            constant_pool: child.constant_pool().clone(),
+            ..Default::default()
+        };
+        let id = vm.scheduler().next_id();
+        vm.scheduler()
+            .spawn(Thread::new(id, Arc::new(method), vm.clone()), None, false);
+        interface
+            .initialize(thread.id, &mut YieldingClinit(&mut thread))
+            .unwrap();
+        // 另一个线程初始化了父类, 在默认方法所在的父接口上挂起, InitChild恢复为未初始化
+        let parent = vm.class_loader().load_class("InitParent").unwrap();
+        assert_eq!(parent.init_state(), InitState::Initialized);
+        assert_eq!(child.init_state(), InitState::Linked);
+        let main = Method {
+            code: vec![0xb1],
+            ..Default::default()
+        };
+        let main = Thread::new(MAIN_THREAD_ID, Arc::new(main), vm.clone());
+        vm.scheduler().run(&vm, main).unwrap();
+        assert_eq!(child.init_state(), InitState::Initialized);
+        let value = child.find_field("value", "I").unwrap();
+        assert_eq!(child.get_static(value.offset), Slot::from(2));
+    }
+
+    #[test]
+    fn test_wait_not_owner() {
+        let (mut thread, object) = monitors_thread();
+        let vm = thread.vm.clone();
+        let wait = vm
+            .natives()
+            .lookup("java/lang/Object", "wait", "(J)V")
+            .unwrap();
+        let args = vec![Slot::Ref(Some(object)), Slot::from(0i64)];
+        let err = wait(&mut NativeEnv::new(&mut thread, args.clone())).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "java.lang.IllegalMonitorStateException: current thread is not owner"
+        );
+        // 没有其他线程能唤醒时报告死锁, 锁已经释放
+        assert!(vm.heap().monitor_enter(object, thread.id));
+        let err = wait(&mut NativeEnv::new(&mut thread, args)).unwrap_err();
+        assert!(matches!(err, RuntimeError::Deadlock));
+        assert_eq!(vm.heap().monitor_owner(object), None);
+        assert!(!vm.scheduler().is_waiting(thread.id));
     }
 }
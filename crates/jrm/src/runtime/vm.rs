@@ -0,0 +1,474 @@
+use std::sync::Arc;
+
+use crate::{
+    instance_klass::MethodAccessFlags,
+    runtime::{
+        ClassLoader, Console, RuntimeError,
+        gc::{Collected, GcCause, GcOptions},
+        heap::{Heap, Object, string_bytes},
+        native::NativeRegistry,
+        scheduler::{MAIN_THREAD_ID, Scheduler},
+        slot::{ObjectRef, Slot},
+        stack_trace::backtrace,
+        string::JavaString,
+        thread::Thread,
+    },
+};
+
+/// 所有线程共享的虚拟机状态
+pub struct Vm {
+    class_loader: ClassLoader,
+    heap: Heap,
+    natives: NativeRegistry,
+    console: Console,
+    scheduler: Scheduler,
+    inline_caches: bool,
+}
+
+impl Vm {
+    pub fn new(class_loader: ClassLoader) -> Self {
+        Self::with_options(class_loader, GcOptions::default())
+    }
+    pub fn with_options(class_loader: ClassLoader, options: GcOptions) -> Self {
+        Self {
+            class_loader,
+            heap: Heap::new(options),
+            natives: NativeRegistry::default(),
+            console: Console::default(),
+            scheduler: Scheduler::default(),
+            inline_caches: true,
+        }
+    }
+    /// 替换标准输出和标准错误, 用于捕获Java程序的输出
+    pub fn with_console(mut self, console: Console) -> Self {
+        self.console = console;
+        self
+    }
+    /// 关闭调用点的内联缓存, 每次调用都查找虚方法表, 用于比较缓存的效果
+    pub fn with_inline_caches(mut self, enabled: bool) -> Self {
+        self.inline_caches = enabled;
+        self
+    }
+    pub fn class_loader(&self) -> &ClassLoader {
+        &self.class_loader
+    }
+    pub fn heap(&self) -> &Heap {
+        &self.heap
+    }
+    pub fn natives(&self) -> &NativeRegistry {
+        &self.natives
+    }
+    pub fn console(&self) -> &Console {
+        &self.console
+    }
+    pub fn scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
+    pub fn inline_caches(&self) -> bool {
+        self.inline_caches
+    }
+    /// 分配`size`字节之前的安全点, 需要回收时`thread_roots`给出当前线程栈中的引用
+    pub fn reserve(
+        &self,
+        size: usize,
+        thread_roots: impl FnOnce() -> Vec<ObjectRef>,
+    ) -> Result<(), RuntimeError> {
+        self.heap
+            .reserve(size, &self.console, || self.roots(thread_roots()))
+    }
+    /// 字符串池中没有`string`时先经过分配的安全点
+    pub fn intern(
+        &self,
+        string: JavaString,
+        thread_roots: impl FnOnce() -> Vec<ObjectRef>,
+    ) -> Result<ObjectRef, RuntimeError> {
+        if let Some(object_ref) = self.heap.interned(&string) {
+            return Ok(object_ref);
+        }
+        self.reserve(string_bytes(&string), thread_roots)?;
+        Ok(self.heap.intern(string))
+    }
+    /// 立即进行一次完整的回收
+    pub fn collect_garbage(&self, cause: GcCause, thread_roots: Vec<ObjectRef>) -> Collected {
+        self.heap
+            .collect(cause, &self.console, self.roots(thread_roots))
+    }
+    /// 当前线程栈之外还要加上其他线程和所有已加载类的静态字段等
+    fn roots(&self, mut roots: Vec<ObjectRef>) -> Vec<ObjectRef> {
+        self.scheduler.push_roots(&mut roots);
+        for klass in self.class_loader.method_area().classes() {
+            klass.push_roots(&mut roots);
+        }
+        roots
+    }
+    /// 在主线程上执行`public static void main(String[])`, `args`作为字符串数组传入.
+    /// 其他非守护线程也结束后返回, 启动失败和未捕获的异常都已经输出到标准错误
+    pub fn run_main(
+        self: &Arc<Self>,
+        class_name: &str,
+        args: &[String],
+    ) -> Result<(), RuntimeError> {
+        let klass = self
+            .class_loader
+            .load_class(class_name)
+            .inspect_err(|err| {
+                self.print_error(&format!(
+                    "Error: Could not find or load main class {}\nCaused by: {}",
+                    class_name.replace('/', "."),
+                    err
+                ))
+            })?;
+        let main = klass
+            .find_method("main", "([Ljava/lang/String;)V")
+            .filter(|method| {
+                method
+                    .access_flags
+                    .contains(MethodAccessFlags::PUBLIC | MethodAccessFlags::STATIC)
+            })
+            .ok_or_else(|| {
+                self.print_error(&format!(
+                    "Error: Main method not found in class {}, please define the main method as:\n   \
+                     public static void main(String[] args)",
+                    klass.name().replace('/', ".")
+                ));
+                RuntimeError::NoSuchMethodError(format!(
+                    "{}.main([Ljava/lang/String;)V",
+                    klass.name()
+                ))
+            })?;
+        let array = self
+            .main_args(args)
+            .inspect_err(|err| self.print_error(&self.uncaught_exception_message("main", err)))?;
+
+        let mut thread = Thread::new(MAIN_THREAD_ID, main, self.clone());
+        thread
+            .current_frame_mut()
+            .set_args(vec![Slot::Ref(Some(array))]);
+        if let Err(err) = thread.initialize_class(&klass) {
+            self.print_error(&self.uncaught_exception_message("main", &err));
+            return Err(err);
+        }
+        self.scheduler.run(self, thread)
+    }
+    /// 传给main方法的字符串数组
+    fn main_args(&self, args: &[String]) -> Result<ObjectRef, RuntimeError> {
+        let string_array = self.class_loader.load_class("[Ljava/lang/String;")?;
+        let array = self.heap.alloc_array(string_array, args.len())?;
+        for (index, arg) in args.iter().enumerate() {
+            let string = self.heap.alloc(Object::String(arg.as_str().into()));
+            self.heap
+                .array_store(Some(array), index as i32, Slot::Ref(Some(string)))?;
+        }
+        Ok(array)
+    }
+    /// 输出一行到标准错误
+    fn print_error(&self, message: &str) {
+        let _ = self.console.write(2, format!("{}\n", message).as_bytes());
+    }
+    /// 线程因未捕获的异常终止时输出的内容, Java异常对象带有栈轨迹
+    pub fn uncaught_exception_message(&self, thread_name: &str, err: &RuntimeError) -> String {
+        let mut message = format!("Exception in thread \"{}\" {}", thread_name, err);
+        if let RuntimeError::Throwable { object, .. } = err
+            && let Some(elements) = backtrace(&self.heap, *object)
+        {
+            for element in elements.iter() {
+                message.push_str(&format!("\n\tat {}", element));
+            }
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rstest::{fixture, rstest};
+
+    use crate::{
+        runtime::{
+            ClassLoader, ClassPath, CollectorKind, Console, RuntimeError, Vm, gc::GcOptions,
+            heap::Object, slot::Slot,
+        },
+        test_context::{Output, TestContext},
+    };
+
+    #[fixture]
+    fn vm() -> Arc<Vm> {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        Arc::new(Vm::new(ClassLoader::new(class_path)))
+    }
+
+    #[rstest]
+    fn test_run_main(vm: Arc<Vm>) {
+        vm.run_main("Arrays", &["hello".to_string(), "world".to_string()])
+            .unwrap();
+        let klass = vm.class_loader().load_class("Arrays").unwrap();
+        let count = klass.find_field("count", "I").unwrap();
+        assert_eq!(klass.get_static(count.offset), Slot::from(2));
+        let first = klass.find_field("first", "Ljava/lang/String;").unwrap();
+        let Slot::Ref(Some(first)) = klass.get_static(first.offset) else {
+            panic!("args[0] is null");
+        };
+        assert!(matches!(vm.heap().get(first), Object::String(string) if string == "hello"));
+    }
+
+    #[rstest]
+    fn test_run_main_without_args(vm: Arc<Vm>) {
+        vm.run_main("Arrays", &[]).unwrap();
+        let klass = vm.class_loader().load_class("Arrays").unwrap();
+        let count = klass.find_field("count", "I").unwrap();
+        assert_eq!(klass.get_static(count.offset), Slot::from(0));
+    }
+
+    #[rstest]
+    fn test_run_main_uncaught(vm: Arc<Vm>) {
+        let err = vm.run_main("Exceptions", &[]).unwrap_err();
+        assert!(matches!(err, RuntimeError::Throwable { .. }));
+        assert_eq!(
+            vm.uncaught_exception_message("main", &err),
+            "Exception in thread \"main\" ExceptionsCustom: custom\n\
+             \tat Exceptions.thrower(Exceptions.java:24)\n\
+             \tat Exceptions.main(Exceptions.java:20)"
+        );
+    }
+
+    #[rstest]
+    fn test_run_main_uncaught_nested(vm: Arc<Vm>) {
+        let err = vm.run_main("StackTraces", &[]).unwrap_err();
+        assert_eq!(
+            vm.uncaught_exception_message("main", &err),
+            "Exception in thread \"main\" StackTracesException: deep\n\
+             \tat StackTraces.level2(StackTraces.java:9)\n\
+             \tat StackTraces.level1(StackTraces.java:13)\n\
+             \tat StackTraces.main(StackTraces.java:49)"
+        );
+    }
+
+    /// 输出到内存缓冲区的虚拟机, 返回标准输出和标准错误
+    fn vm_with_output() -> (Arc<Vm>, Output, Output) {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let (out, err) = (Output::default(), Output::default());
+        let vm = Vm::new(ClassLoader::new(class_path))
+            .with_console(Console::new(out.clone(), err.clone()));
+        (Arc::new(vm), out, err)
+    }
+
+    #[test]
+    fn test_hello_world() {
+        let (vm, out, err) = vm_with_output();
+        vm.run_main("Simple1Impl", &[]).unwrap();
+        assert_eq!(out.contents(), "hello!\n");
+        assert_eq!(err.contents(), "");
+    }
+
+    #[test]
+    fn test_class_library() {
+        let (vm, out, err) = vm_with_output();
+        vm.run_main("ClassLibrary", &[]).unwrap();
+        // 与JDK 17的输出相同
+        assert_eq!(
+            out.contents(),
+            r#"a1c2.5true3null
+15
+llun3eurt5.2c1a
+--yz
+true
+true
+-122
+ff 11111111111111111111111111111111
+9223372036854775807
+-2147483648
+-9223372036854775808
+true z -3
+0.30000000000000004
+1.0E10
+0.001
+-0.0
+1.5
+0.005
+Infinity
+7 2.5 4.0 1024.0
+3 2 -2.0 -0.0
+x
+ok
+42
+object
+null
+12 o 7 World HELLO, WORLD
+-505841268
+trim|-1|true
+true true false false
+true Q true
+true true
+[I
+class java.lang.String
+1,1,4
+5 true
+java.lang.NumberFormatException: For input string: "x1"
+java.lang.ArrayIndexOutOfBoundsException: arraycopy: last source index 8 out of bounds for int[5]
+npe
+java.lang.CloneNotSupportedException: ClassLibrary$NotCloneable
+java.lang.IllegalStateException: outer
+inner
+"#
+        );
+        assert_eq!(
+            err.contents(),
+            "to stderr\n\
+             java.lang.IllegalStateException: outer\n\
+             \tat ClassLibrary.main(ClassLibrary.java:83)\n\
+             Caused by: java.lang.RuntimeException: inner\n\
+             \tat ClassLibrary.main(ClassLibrary.java:83)\n"
+        );
+    }
+
+    #[test]
+    fn test_strings() {
+        let (vm, out, err) = vm_with_output();
+        vm.run_main("Strings", &[]).unwrap();
+        // 与JDK 17的输出相同
+        assert_eq!(
+            out.contents(),
+            "false\ntrue\ntrue\ntrue\ntrue\ntrue\n3 0 93315\n2 646394 true\n3 d83d 1888219 1\n1 d800\ntrue\ntrue\n"
+        );
+        assert_eq!(err.contents(), "");
+    }
+
+    #[test]
+    fn test_threads() {
+        let (vm, out, err) = vm_with_output();
+        vm.run_main("Threads", &[]).unwrap();
+        // 与JDK 17的输出相同
+        assert_eq!(
+            out.contents(),
+            "main true true\n\
+             30000 false Thread-2\n\
+             sum 5050\n\
+             abc\n\
+             worker Thread[worker,5,]\n\
+             true\n\
+             current thread is not owner\n\
+             started\n\
+             timeout value is negative\n\
+             done true\n"
+        );
+        assert_eq!(
+            err.contents(),
+            "Exception in thread \"failing\" java.lang.IllegalStateException: boom\n\
+             \tat Threads$3.run(Threads.java:175)\n"
+        );
+    }
+
+    #[test]
+    fn test_gc_log_to_console() {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let options = GcOptions {
+            log: true,
+            stress: true,
+            ..Default::default()
+        };
+        let (out, err) = (Output::default(), Output::default());
+        let vm = Vm::with_options(ClassLoader::new(class_path), options)
+            .with_console(Console::new(out.clone(), err.clone()));
+        Arc::new(vm).run_main("Simple1Impl", &[]).unwrap();
+        assert_eq!(out.contents(), "hello!\n");
+        let log = err.contents();
+        assert!(log.starts_with("[gc] GC(0) Pause "), "{}", log);
+        assert!(log.lines().all(|line| line.starts_with("[gc] GC(")));
+    }
+
+    #[rstest]
+    #[case::virtual_cached("virtual", true, "3060\n")]
+    #[case::virtual_uncached("virtual", false, "3060\n")]
+    #[case::interface_uncached("interface", false, "100\n")]
+    #[case::monomorphic_uncached("monomorphic", false, "90\n")]
+    fn test_inline_caches(#[case] loop_name: &str, #[case] enabled: bool, #[case] expected: &str) {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        let out = Output::default();
+        let vm = Vm::new(ClassLoader::new(class_path))
+            .with_console(Console::new(out.clone(), Output::default()))
+            .with_inline_caches(enabled);
+        let vm = Arc::new(vm);
+        vm.run_main("Poly", &[loop_name.to_string(), "30".to_string()])
+            .unwrap();
+        assert_eq!(out.contents(), expected);
+        // 关闭时调用点的缓存不会绑定接收者类
+        let poly = vm.class_loader().load_class("Poly").unwrap();
+        let sites = [
+            ("virtualLoop", "(LPolyBase;LPolyBase;LPolyBase;I)I", 42),
+            ("interfaceLoop", "(LPolyShape;LPolyShape;LPolyShape;I)I", 42),
+            ("monomorphicLoop", "(LPolyBase;I)I", 11),
+        ];
+        for (name, descriptor, pc) in sites {
+            let method = poly.find_method(name, descriptor).unwrap();
+            let cache = method.inline_caches.get(pc).unwrap();
+            let called = name.starts_with(loop_name);
+            assert_eq!(cache.receiver().is_some(), enabled && called, "{}", name);
+        }
+    }
+
+    #[rstest]
+    #[case::mark_sweep(CollectorKind::MarkSweep)]
+    #[case::generational(CollectorKind::Generational)]
+    fn test_churn(#[case] collector: CollectorKind) {
+        let mut class_path = ClassPath::default();
+        class_path.push(TestContext);
+        // 小堆使循环中多次回收
+        let options = GcOptions {
+            collector,
+            max_heap: 64 * 1024,
+            ..Default::default()
+        };
+        let out = Output::default();
+        let vm = Vm::with_options(ClassLoader::new(class_path), options)
+            .with_console(Console::new(out.clone(), Output::default()));
+        let vm = Arc::new(vm);
+        vm.run_main("Churn", &["2000".to_string()]).unwrap();
+        // 与JDK 17的输出相同
+        assert_eq!(out.contents(), "14084171\n");
+        assert!(vm.heap().stats().collections > 0);
+    }
+
+    #[test]
+    fn test_uncaught_to_console() {
+        let (vm, out, err) = vm_with_output();
+        vm.run_main("Exceptions", &[]).unwrap_err();
+        assert_eq!(out.contents(), "");
+        assert!(
+            err.contents()
+                .starts_with("Exception in thread \"main\" ExceptionsCustom: custom\n")
+        );
+    }
+
+    #[rstest]
+    fn test_main_not_found(vm: Arc<Vm>) {
+        let err = vm.run_main("Fields", &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            RuntimeError::NoSuchMethodError(msg) if msg == "Fields.main([Ljava/lang/String;)V"
+        ));
+    }
+
+    #[rstest]
+    #[case::missing_class(
+        "Missing",
+        "Error: Could not find or load main class Missing\n\
+         Caused by: java.lang.NoClassDefFoundError: Missing\n"
+    )]
+    #[case::missing_main(
+        "Fields",
+        "Error: Main method not found in class Fields, please define the main method as:\n   \
+         public static void main(String[] args)\n"
+    )]
+    fn test_launch_error_to_console(#[case] class_name: &str, #[case] expected: &str) {
+        let (vm, out, err) = vm_with_output();
+        vm.run_main(class_name, &[]).unwrap_err();
+        assert_eq!(out.contents(), "");
+        assert_eq!(err.contents(), expected);
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     class_file_parser::{ClassParser, ParserContext},
     class_reader::ClassReader,
     instance_klass::InstanceKlass,
+    runtime::{BootClassPath, ClassPathEntry},
 };
 
 #[cfg(test)]
@@ -23,3 +24,37 @@ impl TestContext {
         return instance_klass;
     }
 }
+
+#[cfg(test)]
+impl ClassPathEntry for TestContext {
+    fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+        // java.lang等由内建的类库提供
+        match Self::get(&format!("{}.class", name)) {
+            Some(file) => Some(file.data.into_owned()),
+            None => BootClassPath.read_class(name),
+        }
+    }
+}
+
+/// 可以共享的内存缓冲区, 作为`Console`捕获Java程序的输出
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct Output(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl Output {
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+#[cfg(test)]
+impl std::io::Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
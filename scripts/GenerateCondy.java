@@ -0,0 +1,51 @@
+import java.nio.file.Files;
+import java.nio.file.Path;
+
+import jdk.internal.org.objectweb.asm.ClassWriter;
+import jdk.internal.org.objectweb.asm.ConstantDynamic;
+import jdk.internal.org.objectweb.asm.Handle;
+import jdk.internal.org.objectweb.asm.MethodVisitor;
+import jdk.internal.org.objectweb.asm.Opcodes;
+
+// javac不会生成CONSTANT_Dynamic, 用JDK内部的ASM生成加载动态常量的Condy.class, 引导方法见asset/CondyBootstrap.java
+// java --add-exports java.base/jdk.internal.org.objectweb.asm=ALL-UNNAMED scripts/GenerateCondy.java <输出目录>
+public class GenerateCondy {
+    static final String BOOTSTRAP_PREFIX = "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/Class;";
+
+    static Handle bootstrap(String name, String arguments, String returnType) {
+        return new Handle(Opcodes.H_INVOKESTATIC, "CondyBootstrap", name,
+                BOOTSTRAP_PREFIX + arguments + ")" + returnType, false);
+    }
+
+    static void load(ClassWriter writer, String name, String descriptor, ConstantDynamic constant, int returnOpcode) {
+        MethodVisitor method = writer.visitMethod(Opcodes.ACC_STATIC, name, descriptor, null, null);
+        method.visitCode();
+        method.visitLdcInsn(constant);
+        method.visitInsn(returnOpcode);
+        method.visitMaxs(0, 0);
+        method.visitEnd();
+    }
+
+    public static void main(String[] args) throws Exception {
+        ClassWriter writer = new ClassWriter(ClassWriter.COMPUTE_MAXS);
+        writer.visit(Opcodes.V11, Opcodes.ACC_SUPER, "Condy", null, "java/lang/Object", null);
+        writer.visitSource("Condy.java", null);
+
+        ConstantDynamic greeting = new ConstantDynamic("greeting", "Ljava/lang/String;",
+                bootstrap("describe", "", "Ljava/lang/Object;"));
+        load(writer, "describe", "()Ljava/lang/Object;", greeting, Opcodes.ARETURN);
+        load(writer, "sum", "()J", new ConstantDynamic("sum", "J",
+                bootstrap("sum", "IJ", "J"), 1, 2L), Opcodes.LRETURN);
+        load(writer, "unbox", "()I", new ConstantDynamic("answer", "I",
+                bootstrap("identity", "Ljava/lang/Object;", "Ljava/lang/Object;"), 42), Opcodes.IRETURN);
+        load(writer, "nested", "()Ljava/lang/Object;", new ConstantDynamic("outer", "Ljava/lang/Object;",
+                bootstrap("identity", "Ljava/lang/Object;", "Ljava/lang/Object;"), greeting), Opcodes.ARETURN);
+        load(writer, "fail", "()Ljava/lang/Object;", new ConstantDynamic("boom", "Ljava/lang/Object;",
+                bootstrap("fail", "", "Ljava/lang/Object;")), Opcodes.ARETURN);
+        load(writer, "wrongType", "()Ljava/lang/Object;", new ConstantDynamic("wrong", "Ljava/lang/Integer;",
+                bootstrap("describe", "", "Ljava/lang/Object;")), Opcodes.ARETURN);
+
+        writer.visitEnd();
+        Files.write(Path.of(args[0], "Condy.class"), writer.toByteArray());
+    }
+}
@@ -1,3 +1,25 @@
 #! /usr/bin/env bash
-find -type f -path "*/asset/*.class" -exec rm {} \;
-find -type f -path "*/asset/*.java" | xargs -t -I {} javac {}
\ No newline at end of file
+# 重新生成仓库中所有的class文件, 使用JDK 17
+set -e
+cd "$(dirname "$0")/../crates/jrm"
+
+# classlib是内建的java.*类库, 不能和JDK的java.base一起编译, 以classlib自身为启动类路径
+find classlib -type f -name "*.class" -delete
+javac -source 8 -target 8 -nowarn -bootclasspath classlib -d classlib $(find classlib -name "*.java")
+
+cd asset
+find . -type f -name "*.class" -delete
+# 使用类库的测试类运行在classlib上, 以Java 8为目标
+target8=(Churn.java ClassLibrary.java CondyBootstrap.java Monitors.java Strings.java Threads.java)
+javac -source 8 -target 8 -nowarn -bootclasspath ../classlib "${target8[@]}"
+# 其他测试类以JDK 17的类库编译
+others=$(find . -maxdepth 1 -name "*.java" $(printf -- '-not -name %s ' "${target8[@]}"))
+javac --release 17 $others
+# dispatch下是分布在不同包中的类, 以asset为源路径一起编译
+javac --release 17 -sourcepath . $(find dispatch -name "*.java")
+# ErrorsMissing用于测试NoClassDefFoundError, 编译后删除
+rm ErrorsMissing.class
+cd ../../..
+
+# javac不能生成CONSTANT_Dynamic, Condy.class由JDK内部的ASM生成
+java --add-exports java.base/jdk.internal.org.objectweb.asm=ALL-UNNAMED scripts/GenerateCondy.java crates/jrm/asset